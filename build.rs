@@ -1,7 +1,15 @@
+#[cfg(feature = "librclone")]
 use std::path::PathBuf;
+#[cfg(feature = "librclone")]
 use std::process::Command;
+#[cfg(feature = "librclone")]
 use std::{env, fs};
 
+// without librclone, galion drives the demo backend and the rc hosts: no Go build
+#[cfg(not(feature = "librclone"))]
+fn main() {}
+
+#[cfg(feature = "librclone")]
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let lib_name = "librclone";
     let rclone_repo = format!("github.com/rclone/rclone/{}", lib_name);
@@ -87,6 +95,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 // https://github.com/rust-lang/docs.rs/issues/3112
+#[cfg(feature = "librclone")]
 const FAKE_BINDINGS: &str = r#"
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
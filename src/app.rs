@@ -7,44 +7,298 @@ use rand::Rng;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use serde_json::json;
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+use std::time::SystemTime;
 
+use crate::commands::GalionCommand;
+use crate::config_format::ConfigFormat;
 use crate::errors::GalionError;
-use crate::librclone::rclone::Rclone;
+use crate::history::JobHistory;
+use crate::i18n::{Locale, set_locale};
+use crate::jobs::ExecutionPolicy;
+use crate::librclone::RcHost;
+use crate::librclone::rclone::{RcRemote, RcRemoteConfig, Rclone};
+use crate::notify::{MqttConfig, WebhookConfig};
+use crate::options::SyncOptions;
 use crate::remote::ConfigOrigin;
-use crate::remote::RemoteConfiguration;
+use crate::remote::{RemoteConfiguration, RemoteMetadata, SyncRecord, expand_path};
+use crate::report::SummaryFormat;
+use crate::session::Session;
+use crate::settings::Theme;
+use crate::system::{LoadThrottle, MeteredPolicy};
+use crate::template::RemoteTemplate;
+use crate::unlock::ask_password;
 
 /// remote configuration
-#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct GalionConfig {
     /// list of remote configuration
     pub(crate) remote_configurations: Vec<RemoteConfiguration>,
 
+    /// Other rclone config files whose remotes are added to the ones of the main rclone config
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) rclone_configs: Vec<PathBuf>,
+
+    /// Rclone rcd hosts, one is driven instead of the local rclone with `--rc-host`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) rc_hosts: Vec<RcHost>,
+
+    /// Galion metadata of the remotes of the rclone config, by remote name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) rclone_remotes: BTreeMap<String, RemoteMetadata>,
+
+    /// Maximum number of jobs kept in memory, older finished jobs are moved to the history
+    #[serde(default = "GalionConfig::default_max_jobs_in_memory")]
+    pub(crate) max_jobs_in_memory: usize,
+
+    /// Maximum number of jobs running at the same time, the others wait in the queue
+    #[serde(default = "GalionConfig::default_max_running_jobs")]
+    pub(crate) max_running_jobs: usize,
+
+    /// Directory of the batch summaries, next to the config file if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) report_dir: Option<PathBuf>,
+
+    /// Format of the batch summaries
+    #[serde(default)]
+    pub(crate) summary_format: SummaryFormat,
+
+    /// MQTT broker receiving the job events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) mqtt: Option<MqttConfig>,
+
+    /// Rclone options of all the syncs, the options of a remote override them
+    #[serde(default, skip_serializing_if = "SyncOptions::is_empty")]
+    pub(crate) defaults: SyncOptions,
+
+    /// Webhooks receiving the job events
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) webhooks: Vec<WebhookConfig>,
+
+    /// How the jobs of a run-all batch are launched
+    #[serde(default)]
+    pub(crate) batch_policy: ExecutionPolicy,
+
+    /// How the jobs of each group are launched, [`GalionConfig::batch_policy`] if not set
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) group_policies: BTreeMap<String, ExecutionPolicy>,
+
+    /// What to do with the syncs on a metered connection
+    #[serde(default)]
+    pub(crate) metered_policy: MeteredPolicy,
+
+    /// Bandwidth limit on a metered connection with the `limit` policy
+    #[serde(default = "GalionConfig::default_metered_bwlimit")]
+    pub(crate) metered_bwlimit: String,
+
+    /// Pause the syncs while the machine is on battery
+    #[serde(default)]
+    pub(crate) pause_on_battery: bool,
+
+    /// With `pause_on_battery`, only pause the syncs below this charge in percent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) battery_threshold: Option<u8>,
+
+    /// Slow down the syncs while the machine is busy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) load_throttle: Option<LoadThrottle>,
+
+    /// A warning is shown when a sync runs longer than its median duration times this factor, 0 to never warn
+    #[serde(default = "GalionConfig::default_duration_alert_factor")]
+    pub(crate) duration_alert_factor: f64,
+
+    /// Consecutive failed syncs after which a remote is quarantined, 0 to never quarantine
+    #[serde(default = "GalionConfig::default_quarantine_after")]
+    pub(crate) quarantine_after: u32,
+
+    /// Number of times in a row a sync failing with a retryable error, like a timeout, is queued again
+    #[serde(default)]
+    pub(crate) sync_retries: u32,
+
+    /// Compute the size to transfer before a sync, for an accurate progress
+    #[serde(default)]
+    pub(crate) precompute_size: bool,
+
+    /// Time between two listings of the rclone remotes, like `5m`, only on demand if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) remotes_refresh_interval: Option<String>,
+
+    /// Time between two health checks of the destinations, like `1h`, only on demand if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) health_check_interval: Option<String>,
+
+    /// Directory of the log files of the remotes, `~/.local/state/galion/logs` if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) log_dir: Option<PathBuf>,
+
+    /// Colors of the ui
+    #[serde(default, skip_serializing_if = "Theme::is_default")]
+    pub(crate) theme: Theme,
+
+    /// Redraw time of the ui, when nothing happens
+    #[serde(default = "GalionConfig::default_refresh_rate_ms")]
+    pub(crate) refresh_rate_ms: u64,
+
+    /// Ask before deleting a remote or a file
+    #[serde(default = "GalionConfig::default_confirm_deletions")]
+    pub(crate) confirm_deletions: bool,
+
+    /// Language of the interface, from the environment if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) language: Option<Locale>,
+
+    /// Templates of remotes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) templates: Vec<RemoteTemplate>,
+
+    /// Rhai script reacting to the events, like a finished job
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) hooks_script: Option<PathBuf>,
+
     /// Config path
     #[serde(skip)]
     pub(crate) config_path: PathBuf,
+
+    /// Format of the config file
+    #[serde(skip)]
+    pub(crate) config_format: ConfigFormat,
+}
+
+impl Default for GalionConfig {
+    fn default() -> Self {
+        Self {
+            remote_configurations: Vec::new(),
+            rclone_configs: Vec::new(),
+            rc_hosts: Vec::new(),
+            rclone_remotes: BTreeMap::new(),
+            max_jobs_in_memory: Self::default_max_jobs_in_memory(),
+            max_running_jobs: Self::default_max_running_jobs(),
+            report_dir: None,
+            summary_format: SummaryFormat::default(),
+            mqtt: None,
+            defaults: SyncOptions::default(),
+            webhooks: Vec::new(),
+            batch_policy: ExecutionPolicy::default(),
+            group_policies: BTreeMap::new(),
+            metered_policy: MeteredPolicy::default(),
+            metered_bwlimit: Self::default_metered_bwlimit(),
+            pause_on_battery: false,
+            battery_threshold: None,
+            load_throttle: None,
+            duration_alert_factor: Self::default_duration_alert_factor(),
+            quarantine_after: Self::default_quarantine_after(),
+            sync_retries: 0,
+            precompute_size: false,
+            remotes_refresh_interval: None,
+            health_check_interval: None,
+            log_dir: None,
+            theme: Theme::default(),
+            refresh_rate_ms: Self::default_refresh_rate_ms(),
+            confirm_deletions: Self::default_confirm_deletions(),
+            language: None,
+            templates: Vec::new(),
+            hooks_script: None,
+            config_path: PathBuf::new(),
+            config_format: ConfigFormat::default(),
+        }
+    }
 }
 
 impl GalionConfig {
-    /// Load the config
+    /// Default for [`GalionConfig::max_jobs_in_memory`]
+    fn default_max_jobs_in_memory() -> usize {
+        50
+    }
+
+    /// Default for [`GalionConfig::max_running_jobs`]
+    fn default_max_running_jobs() -> usize {
+        4
+    }
+
+    /// Default for [`GalionConfig::refresh_rate_ms`]
+    fn default_refresh_rate_ms() -> u64 {
+        500
+    }
+
+    /// Default for [`GalionConfig::confirm_deletions`]
+    fn default_confirm_deletions() -> bool {
+        true
+    }
+
+    /// Default for [`GalionConfig::duration_alert_factor`]
+    fn default_duration_alert_factor() -> f64 {
+        2.0
+    }
+
+    /// Default for [`GalionConfig::quarantine_after`]
+    fn default_quarantine_after() -> u32 {
+        5
+    }
+
+    /// Default for [`GalionConfig::metered_bwlimit`]
+    fn default_metered_bwlimit() -> String {
+        "512K".to_string()
+    }
+
+    /// Load the config, in the given format or the one of its extension
     /// # Errors
     /// Fails if fails to log the config
-    fn load_config(config_path: Option<PathBuf>) -> Result<GalionConfig, GalionError> {
+    pub(crate) fn load_config(
+        config_path: Option<PathBuf>,
+        config_format: Option<ConfigFormat>,
+    ) -> Result<GalionConfig, GalionError> {
         let config_path = config_path.unwrap_or(GalionConfig::get_default_config_path()?);
+        let config_format = config_format.unwrap_or_else(|| ConfigFormat::from_path(&config_path));
         if !config_path.exists() {
             if let Some(parent) = config_path.parent() {
                 std::fs::create_dir_all(parent)?;
             }
-            let config_json = serde_json::to_string(&GalionConfig::default())?;
-            std::fs::write(&config_path, config_json)?;
+            let config_data = config_format.serialize(&GalionConfig::default())?;
+            write_file_atomic(&config_path, config_data.as_bytes())?;
         }
         let config_data = std::fs::read_to_string(&config_path)?;
-        let mut loaded_config = serde_json::from_str::<GalionConfig>(&config_data)?;
+        let mut loaded_config = config_format
+            .deserialize::<GalionConfig>(&config_data)
+            .map_err(|e| format!("{}: {e}", config_path.display()))?;
         loaded_config.config_path = config_path;
+        loaded_config.config_format = config_format;
         Ok(loaded_config)
     }
 
-    /// Get the config path
+    /// Config of the demo mode, written in the temporary directory so the real config is untouched
+    /// # Errors
+    /// Fails if the config cannot be written
+    fn demo_config() -> Result<GalionConfig, GalionError> {
+        let config_path = std::env::temp_dir().join(APP_NAME).join("demo.json");
+        if let Some(parent) = config_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let remote = |name: &str, src: &str, dest: &str| json!({ "remote_name": name, "remote_src": src, "remote_dest": dest });
+        let mut remotes = [
+            remote("photos", "~/Pictures", "gdrive:Photos"),
+            remote("documents", "~/Documents", "nas:backup/documents"),
+            remote("music", "~/Music", "archive:music"),
+            remote("projects", "~/Projects", "nas:projects"),
+        ];
+        remotes[0]["tags"] = json!(["family"]);
+        remotes[1]["group"] = json!("nightly");
+        remotes[1]["schedule"] = json!({ "cron": "0 2 * * *" });
+        remotes[2]["group"] = json!("nightly");
+        remotes[2]["verify"] = json!(true);
+        remotes[3]["operation"] = json!("bisync");
+        remotes[3]["notes"] = json!("Shared with the laptop");
+        let config = json!({ "remote_configurations": remotes });
+        write_file_atomic(&config_path, serde_json::to_string(&config)?.as_bytes())?;
+        Self::load_config(Some(config_path), None)
+    }
+
+    /// Get the config path, the first existing of `galion.json`, `galion.toml` and `galion.yaml`
     /// # Errors
     /// Fails if [`home_dir`] not found
     pub fn get_default_config_path() -> Result<PathBuf, GalionError> {
@@ -52,7 +306,36 @@ impl GalionConfig {
         path.push(".config");
         path.push(APP_NAME);
         path.push("galion.json");
-        Ok(path)
+        let existing = [ConfigFormat::Json, ConfigFormat::Toml, ConfigFormat::Yaml]
+            .into_iter()
+            .map(|format| path.with_extension(format.extension()))
+            .find(|path| path.exists());
+        Ok(existing.unwrap_or(path))
+    }
+
+    /// Job history store, next to the config file
+    pub fn history(&self) -> JobHistory {
+        JobHistory::new(self.config_path.with_file_name(JobHistory::FILE_NAME))
+    }
+
+    /// Ui session file, next to the config file
+    pub fn session_path(&self) -> PathBuf {
+        self.config_path.with_file_name(Session::FILE_NAME)
+    }
+
+    /// Directory of the batch summaries
+    pub fn reports_path(&self) -> PathBuf {
+        self.report_dir
+            .clone()
+            .unwrap_or_else(|| self.config_path.with_file_name("reports"))
+    }
+
+    /// Policy of a group, or of a run-all batch without group
+    pub fn execution_policy(&self, group: Option<&str>) -> ExecutionPolicy {
+        group
+            .and_then(|group| self.group_policies.get(group))
+            .copied()
+            .unwrap_or(self.batch_policy)
     }
 
     /// Returns the remotes
@@ -60,6 +343,361 @@ impl GalionConfig {
         &self.remote_configurations
     }
 
+    /// Load the rclone details of the remote at `idx`, if not cached yet
+    /// Returns true if the details were fetched
+    /// # Errors
+    /// Fails if rclone fails to get the remote
+    pub fn load_remote_details(
+        &mut self,
+        rclone: &Rclone,
+        idx: usize,
+    ) -> Result<bool, GalionError> {
+        let Some(remote) = self.remote_configurations.get_mut(idx) else {
+            return Ok(false);
+        };
+        if !remote.needs_details() {
+            return Ok(false);
+        }
+        let details = rclone.get_remote(&remote.remote_name)?;
+        remote.set_details(details);
+        Ok(true)
+    }
+
+    /// Names of the remotes whose rclone details are not loaded yet
+    pub fn pending_details(&self) -> Vec<String> {
+        self.remote_configurations
+            .iter()
+            .filter(|r| r.needs_details())
+            .map(|r| r.remote_name.clone())
+            .collect()
+    }
+
+    /// Fetch the rclone details of remotes concurrently on a scoped thread pool, until `on_details` returns false
+    /// Failed lookups are left to [`GalionConfig::load_remote_details`]
+    pub fn fetch_remote_details(
+        rclone: &Rclone,
+        names: &[String],
+        on_details: &(dyn Fn(&str, RcRemoteConfig) -> bool + Sync),
+    ) {
+        if names.is_empty() {
+            return;
+        }
+        let chunk_size = names.len().div_ceil(DETAILS_WORKERS);
+        thread::scope(|s| {
+            for chunk in names.chunks(chunk_size) {
+                s.spawn(move || {
+                    for name in chunk {
+                        if let Ok(details) = rclone.get_remote(name)
+                            && !on_details(name, details)
+                        {
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+    }
+
+    /// Load the rclone details of every remote, blocking until they are all fetched
+    pub fn load_all_remote_details(&mut self, rclone: &Rclone) {
+        let (tx_details, rx_details) = mpsc::channel();
+        Self::fetch_remote_details(rclone, &self.pending_details(), &|name, details| {
+            tx_details.send((name.to_string(), details)).is_ok()
+        });
+        drop(tx_details);
+        for (name, details) in rx_details {
+            self.set_remote_details(&name, details);
+        }
+    }
+
+    /// Apply the sync states counted by the background thread, returns the newly quarantined remotes
+    pub fn apply_sync_records(&mut self, records: &[SyncRecord]) -> Vec<String> {
+        let mut quarantined = Vec::new();
+        for record in records {
+            if let Some(remote) = self
+                .remote_configurations
+                .iter_mut()
+                .find(|remote| remote.remote_name == record.name)
+                && remote.apply_sync_record(record)
+            {
+                quarantined.push(record.name.clone());
+            }
+        }
+        quarantined
+    }
+
+    /// Cache the rclone details of a remote, returns true if they gave it its destination
+    pub fn set_remote_details(&mut self, name: &str, details: RcRemoteConfig) -> bool {
+        let Some(remote) = self
+            .remote_configurations
+            .iter_mut()
+            .find(|r| r.remote_name == name && r.needs_details())
+        else {
+            return false;
+        };
+        let had_dest = remote.remote_dest.is_some();
+        remote.set_details(details);
+        !had_dest && remote.remote_dest.is_some()
+    }
+
+    /// Remote of a template instantiated with `key=value` values
+    /// # Errors
+    /// Fails if the template doesn't exist or if a value is missing
+    pub fn instantiate_template<'a, I: IntoIterator<Item = &'a str>>(
+        &self,
+        template_name: &str,
+        values: I,
+    ) -> Result<RemoteConfiguration, GalionError> {
+        let template = self
+            .templates
+            .iter()
+            .find(|template| template.name == template_name)
+            .ok_or_else(|| format!("no template named '{template_name}'"))?;
+        template.instantiate(&RemoteTemplate::parse_values(values)?)
+    }
+
+    /// Add a new remote at the top of the list
+    /// # Errors
+    /// Fails if the name is already used
+    pub fn add_remote(&mut self, remote: RemoteConfiguration) -> Result<(), GalionError> {
+        if self.is_name_taken(&remote.remote_name, None) {
+            return Err(GalionError::new(format!(
+                "A remote named '{}' already exists",
+                remote.remote_name
+            )));
+        }
+        self.remote_configurations.insert(0, remote);
+        Ok(())
+    }
+
+    /// Is the remote name used by another remote than the one at `except_idx`
+    pub fn is_name_taken(&self, name: &str, except_idx: Option<usize>) -> bool {
+        self.remote_configurations
+            .iter()
+            .enumerate()
+            .any(|(i, r)| Some(i) != except_idx && r.remote_name == name)
+    }
+
+    /// Does the chain of `after` of the remote come back to it, the remote at `except_idx` is the one replaced by `remote`
+    pub fn is_chain_cycle(&self, remote: &RemoteConfiguration, except_idx: Option<usize>) -> bool {
+        let mut seen = vec![remote.remote_name.as_str()];
+        let mut next = remote.after.as_deref();
+        while let Some(name) = next {
+            if seen.contains(&name) {
+                return true;
+            }
+            seen.push(name);
+            next = self
+                .remote_configurations
+                .iter()
+                .enumerate()
+                .find(|(i, r)| Some(*i) != except_idx && r.remote_name == name)
+                .and_then(|(_, r)| r.after.as_deref());
+        }
+        false
+    }
+
+    /// Remotes launched after a successful sync of the remote `name`
+    pub fn chained_remotes<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl Iterator<Item = &'a RemoteConfiguration> + 'a {
+        self.remote_configurations
+            .iter()
+            .filter(move |r| r.after.as_deref() == Some(name))
+    }
+
+    /// Unique name for a copy of the remote `name`
+    pub fn copy_name(&self, name: &str) -> String {
+        let mut candidate = format!("{name}-copy");
+        let mut count = 2;
+        while self.is_name_taken(&candidate, None) {
+            candidate = format!("{name}-copy-{count}");
+            count += 1;
+        }
+        candidate
+    }
+
+    /// Replace the remote at `idx` with its edited version
+    ///
+    /// Editing a rclone remote without renaming it keeps a single merged row
+    /// # Errors
+    /// Fails if the new name is already used or if there is no remote at `idx`
+    pub fn apply_edit(
+        &mut self,
+        idx: usize,
+        mut new_remote: RemoteConfiguration,
+    ) -> Result<(), GalionError> {
+        if self.is_name_taken(&new_remote.remote_name, Some(idx)) {
+            return Err(GalionError::new(format!(
+                "A remote named '{}' already exists",
+                new_remote.remote_name
+            )));
+        }
+        if self.is_chain_cycle(&new_remote, Some(idx)) {
+            return Err(GalionError::new(format!(
+                "'{}' would run after itself through the chain of remotes",
+                new_remote.remote_name
+            )));
+        }
+        let old_remote = self
+            .remote_configurations
+            .get_mut(idx)
+            .ok_or("Cannot edit remote")?;
+        if old_remote.config_origin == ConfigOrigin::RcloneConfig
+            && old_remote.remote_name == new_remote.remote_name
+            && old_remote.is_metadata_edit(&new_remote)
+        {
+            // no galion entry for a new source, notes or tags
+            RemoteMetadata::from_remote(&new_remote).apply(old_remote);
+        } else if old_remote.config_origin != ConfigOrigin::GalionConfig
+            && old_remote.remote_name == new_remote.remote_name
+        {
+            new_remote.config_origin = ConfigOrigin::Merged;
+            new_remote.details = old_remote.details.take();
+            new_remote.backend_type = old_remote.backend_type.take();
+            new_remote.rclone_config_file = old_remote.rclone_config_file.take();
+            new_remote.description = old_remote.description.take();
+            *old_remote = new_remote;
+        } else if old_remote.config_origin == ConfigOrigin::RcloneConfig {
+            self.remote_configurations.insert(0, new_remote);
+        } else {
+            let old_remote = std::mem::replace(old_remote, new_remote);
+            self.restore_rclone_row(old_remote);
+        }
+        Ok(())
+    }
+
+    /// Remove the remote at `idx`, a merged remote goes back to its rclone row
+    pub fn remove_remote(&mut self, idx: usize) {
+        if idx < self.remote_configurations.len() {
+            let old_remote = self.remote_configurations.remove(idx);
+            self.restore_rclone_row(old_remote);
+        }
+    }
+
+    /// Give back its own row to the rclone remote of a merged remote that left
+    fn restore_rclone_row(&mut self, old_remote: RemoteConfiguration) {
+        if old_remote.config_origin != ConfigOrigin::Merged {
+            return;
+        }
+        let mut rclone_remote = RemoteConfiguration {
+            remote_name: old_remote.remote_name,
+            config_origin: ConfigOrigin::RcloneConfig,
+            backend_type: old_remote.backend_type,
+            rclone_config_file: old_remote.rclone_config_file,
+            description: old_remote.description,
+            ..Default::default()
+        };
+        if let Some(details) = old_remote.details {
+            rclone_remote.set_details(details);
+        }
+        self.remote_configurations.push(rclone_remote);
+    }
+
+    /// Add the rclone remotes to the remotes list
+    ///
+    /// A rclone remote named like a galion remote is merged into it, unless `ignore_duplicate_remote`
+    /// # Errors
+    /// Fails if rclone fails to list the remotes
+    pub fn merge_rclone_remotes(
+        &mut self,
+        rclone: &Rclone,
+        ignore_duplicate_remote: bool,
+    ) -> Result<(), GalionError> {
+        let list_remotes = rclone.list_remotes_long()?;
+        for rclone_remote in list_remotes {
+            self.merge_rclone_remote(&rclone_remote, ignore_duplicate_remote);
+        }
+        Ok(())
+    }
+
+    /// Add a rclone remote to the table, or merge it into the galion remote of the same name
+    fn merge_rclone_remote(&mut self, rclone_remote: &RcRemote, ignore_duplicate_remote: bool) {
+        if let Some(galion_remote) = self
+            .remote_configurations
+            .iter_mut()
+            .find(|r| r.remote_name == rclone_remote.name)
+        {
+            // one row per name: the rclone remote is merged into the galion entry
+            if !ignore_duplicate_remote {
+                galion_remote.config_origin = ConfigOrigin::Merged;
+                galion_remote.set_backend(rclone_remote);
+            }
+            return;
+        }
+        let mut remote_config = RemoteConfiguration {
+            remote_name: rclone_remote.name.clone(),
+            config_origin: ConfigOrigin::RcloneConfig,
+            ..Default::default()
+        };
+        remote_config.set_backend(rclone_remote);
+        if let Some(metadata) = self.rclone_remotes.get(&rclone_remote.name) {
+            metadata.apply(&mut remote_config);
+        }
+        self.remote_configurations.push(remote_config);
+    }
+
+    /// List the rclone remotes again, adding the new ones and dropping the deleted ones,
+    /// the galion remotes are kept as they are, returns the number of added and removed remotes
+    /// # Errors
+    /// Fails if rclone fails to list the remotes
+    pub fn refresh_rclone_remotes(
+        &mut self,
+        rclone: &Rclone,
+        ignore_duplicate_remote: bool,
+    ) -> Result<(usize, usize), GalionError> {
+        let list_remotes = rclone.list_remotes_long()?;
+        let is_listed = |name: &str| list_remotes.iter().any(|r| r.name == name);
+        let before = self.remote_configurations.len();
+        self.remote_configurations.retain(|remote| {
+            remote.config_origin != ConfigOrigin::RcloneConfig || is_listed(&remote.remote_name)
+        });
+        let removed = before - self.remote_configurations.len();
+        for remote in &mut self.remote_configurations {
+            if remote.config_origin == ConfigOrigin::Merged && !is_listed(&remote.remote_name) {
+                remote.config_origin = ConfigOrigin::GalionConfig;
+                remote.backend_type = None;
+                remote.rclone_config_file = None;
+                remote.description = None;
+            }
+        }
+        let mut added = 0;
+        for rclone_remote in &list_remotes {
+            match self
+                .remote_configurations
+                .iter()
+                .find(|r| r.remote_name == rclone_remote.name)
+            {
+                None => added += 1,
+                Some(remote) if remote.config_origin != ConfigOrigin::GalionConfig => continue,
+                Some(_) => {}
+            }
+            self.merge_rclone_remote(rclone_remote, ignore_duplicate_remote);
+        }
+        Ok((added, removed))
+    }
+
+    /// Reload the galion config file and the rclone remotes, the loaded details are kept
+    /// # Errors
+    /// Fails if the config cannot be loaded or if rclone fails to list the remotes
+    pub fn reload(
+        &mut self,
+        rclone: &Rclone,
+        ignore_duplicate_remote: bool,
+    ) -> Result<(), GalionError> {
+        let mut config =
+            Self::load_config(Some(self.config_path.clone()), Some(self.config_format))?;
+        config.merge_rclone_remotes(rclone, ignore_duplicate_remote)?;
+        for remote in &mut self.remote_configurations {
+            if let Some(details) = remote.details.take() {
+                config.set_remote_details(&remote.remote_name, details);
+            }
+        }
+        *self = config;
+        Ok(())
+    }
+
     /// Save galion config
     /// # Errors
     /// Fails if write to file fails
@@ -67,18 +705,117 @@ impl GalionConfig {
         let remotes_to_save = self
             .remote_configurations
             .iter()
-            .filter(|c| c.config_origin == ConfigOrigin::GalionConfig)
+            .filter(|c| c.config_origin != ConfigOrigin::RcloneConfig)
             .cloned()
             .collect::<Vec<RemoteConfiguration>>();
+        // the metadata of the remotes missing from the rclone config are kept
+        let mut rclone_remotes = self.rclone_remotes.clone();
+        for remote in &self.remote_configurations {
+            if remote.config_origin == ConfigOrigin::RcloneConfig {
+                let metadata = RemoteMetadata::from_remote(remote);
+                if metadata.is_empty() {
+                    rclone_remotes.remove(&remote.remote_name);
+                } else {
+                    rclone_remotes.insert(remote.remote_name.clone(), metadata);
+                }
+            }
+        }
         let config = GalionConfig {
             remote_configurations: remotes_to_save,
-            config_path: self.config_path.clone(),
+            rclone_remotes,
+            ..self.clone()
         };
-        std::fs::write(&self.config_path, serde_json::to_string(&config)?)?;
+        write_file_atomic(
+            &self.config_path,
+            self.config_format.serialize(&config)?.as_bytes(),
+        )?;
         Ok(())
     }
 }
 
+/// Watch files for modifications by polling their modification time
+#[derive(Debug)]
+pub(crate) struct FileWatcher {
+    /// watched files and their last known modification time
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl FileWatcher {
+    /// Start watching the files
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        let files = paths
+            .into_iter()
+            .map(|path| {
+                let modified = Self::modified(&path);
+                (path, modified)
+            })
+            .collect();
+        Self { files }
+    }
+
+    /// Modification time of a file, if it exists
+    fn modified(path: &Path) -> Option<SystemTime> {
+        std::fs::metadata(path).and_then(|m| m.modified()).ok()
+    }
+
+    /// Remember the modification time of a file galion wrote, so the write isn't reported as a change
+    pub fn written(&mut self, path: &Path) {
+        for (watched, last_modified) in &mut self.files {
+            if watched == path {
+                *last_modified = Self::modified(watched);
+            }
+        }
+    }
+
+    /// Returns true if a file changed since the last call
+    pub fn changed(&mut self) -> bool {
+        let mut changed = false;
+        for (path, last_modified) in &mut self.files {
+            let modified = Self::modified(path);
+            if modified != *last_modified {
+                *last_modified = modified;
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+/// Write a file atomically: the data is written to a temporary file in the same directory,
+/// synced and then renamed, so the file is never left truncated
+/// # Errors
+/// Fails if the temporary file cannot be written or renamed
+pub(crate) fn write_file_atomic(path: &Path, data: &[u8]) -> Result<(), GalionError> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| GalionError::new(format!("Invalid file path {}", path.display())))?;
+    let mut tmp_name = OsString::from(".");
+    tmp_name.push(file_name);
+    tmp_name.push(".tmp");
+    let tmp_path = parent.join(tmp_name);
+    let write_tmp = || -> Result<(), GalionError> {
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(data)?;
+        file.sync_all()?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    };
+    if let Err(e) = write_tmp() {
+        if std::fs::remove_file(&tmp_path).is_err() {
+            // the temporary file may not exist
+        }
+        return Err(e);
+    }
+    // sync the directory so the rename itself is durable
+    #[cfg(unix)]
+    File::open(parent)?.sync_all()?;
+    Ok(())
+}
+
 /// Galion arguments parsing
 #[derive(Parser, Debug)]
 #[command(name = "galion", version, about = "Galion CLI")]
@@ -86,11 +823,15 @@ impl GalionConfig {
 pub struct GalionArgs {
     /// Path to the configuration file
     #[arg(long, value_name = "FILE")]
-    config: Option<PathBuf>,
+    pub(crate) config: Option<PathBuf>,
 
-    /// Path to the rclone configuration file
+    /// Format of the configuration file, from its extension if not set
+    #[arg(long, value_name = "FORMAT", value_enum)]
+    pub(crate) config_format: Option<ConfigFormat>,
+
+    /// Path to the rclone configuration file, repeat it to add the remotes of other files
     #[arg(long, value_name = "FILE")]
-    rclone_config: Option<PathBuf>,
+    rclone_config: Vec<PathBuf>,
 
     /// Should rclone ask for a password (if needed)
     #[arg(long)]
@@ -100,9 +841,21 @@ pub struct GalionArgs {
     #[arg(long, action=ArgAction::SetTrue)]
     pub(crate) hide_banner: bool,
 
-    /// Ignore duplicate remote
+    /// Ignore the rclone remotes named like a galion remote, instead of merging them
     #[arg(long, action=ArgAction::SetTrue)]
-    ignore_duplicate_remote: bool,
+    pub(crate) ignore_duplicate_remote: bool,
+
+    /// Name of a rclone rcd host of the config, driven instead of the local rclone
+    #[arg(long, value_name = "NAME", conflicts_with = "rclone_config")]
+    pub(crate) rc_host: Option<String>,
+
+    /// Run against a fake rclone backend with fake remotes and jobs, nothing is synced
+    #[arg(long, action=ArgAction::SetTrue, conflicts_with_all = ["config", "config_format", "rclone_config", "rc_host"])]
+    pub(crate) demo: bool,
+
+    /// Subcommand to run instead of the tui
+    #[command(subcommand)]
+    pub(crate) command: Option<GalionCommand>,
 }
 
 /// Galion App
@@ -119,6 +872,12 @@ pub struct GalionApp {
 /// app name
 const APP_NAME: &str = "galion";
 
+/// Number of threads used to fetch the remote details at init
+const DETAILS_WORKERS: usize = 8;
+
+/// Attempts at the password of an encrypted rclone config
+const PASSWORD_ATTEMPTS: usize = 3;
+
 impl GalionApp {
     /// Galion ASCII art
     /// This ASCII pic can be found at <https://asciiart.website/art/4370>
@@ -166,11 +925,33 @@ impl GalionApp {
     /// # Errors
     /// Error if fails
     pub fn try_from_galion_args(galion_args: GalionArgs) -> Result<Self, GalionError> {
-        let config = GalionConfig::load_config(galion_args.config.clone())?;
+        let (config, rclone) = if galion_args.demo {
+            (GalionConfig::demo_config()?, Rclone::demo())
+        } else {
+            let config =
+                GalionConfig::load_config(galion_args.config.clone(), galion_args.config_format)?;
+            let rclone = match &galion_args.rc_host {
+                Some(name) => {
+                    let host = config
+                        .rc_hosts
+                        .iter()
+                        .find(|host| host.name == *name)
+                        .ok_or_else(|| {
+                            format!(
+                                "unknown rc host '{name}' - add it to the rc_hosts of {}",
+                                config.config_path.display()
+                            )
+                        })?;
+                    Rclone::http(host.clone())?
+                }
+                None => Rclone::new(),
+            };
+            (config, rclone)
+        };
         let galion = Self {
-            config,
             galion_args,
-            rclone: Rclone::new(),
+            config,
+            rclone,
         };
         galion.init()
     }
@@ -179,73 +960,41 @@ impl GalionApp {
     /// # Errors
     /// Fails if fails to init
     fn init(mut self) -> Result<Self, GalionError> {
-        if let Some(rclone_config_path) = &self.galion_args.rclone_config {
+        let (main_config, extra_configs) = match self.galion_args.rclone_config.split_first() {
+            Some((main_config, extra_configs)) => (Some(main_config), extra_configs),
+            None => (None, &[][..]),
+        };
+        if let Some(rclone_config_path) = main_config {
             self.rclone
                 .set_config_path(&rclone_config_path.to_string_lossy())?;
         }
         if !self.galion_args.hide_banner {
             println!("{}", Self::logo());
         }
-        self.rclone.set_config_options(&json!({
-            "main": {
-                "LogLevel": "CRITICAL",
-            },
-        }))?;
-        if !self.galion_args.rclone_ask_password {
+        // a rc host keeps its own config files and options
+        if self.galion_args.rc_host.is_none() {
+            for extra_config in extra_configs.iter().chain(&self.config.rclone_configs) {
+                self.rclone
+                    .add_config_file(&expand_path(&extra_config.to_string_lossy()))?;
+            }
             self.rclone.set_config_options(&json!({
                 "main": {
-                    "AskPassword": false,
+                    "LogLevel": "CRITICAL",
                 },
             }))?;
+            if !self.galion_args.rclone_ask_password {
+                self.rclone.set_config_options(&json!({
+                    "main": {
+                        "AskPassword": false,
+                    },
+                }))?;
+            }
         }
         if let Err(e) = self.rclone.dump_config() {
-            let err_string = e.to_string();
-            let err_string = if let Ok(j) = serde_json::from_str::<Value>(&err_string)
-                && let Some(Value::String(str)) = j.get("error")
-            {
-                str.clone()
-            } else {
-                err_string
-            };
-            let max_len = 80;
-            let error_msg = if err_string.len() > max_len {
-                format!("{}...", &err_string[..max_len.saturating_sub(3)])
-            } else {
-                err_string
-            };
-            let msg = if self.galion_args.rclone_ask_password {
-                " and the decryption failed"
-            } else {
-                "and you can retry with the --rclone-ask-password flag"
-            };
-            return Err(GalionError::new(format!(
-                "Failed to get the rclone configuration. Most likely the configuration is encrypted {msg}.\nRclone internal error: {error_msg}"
-            )));
-        }
-        let list_remotes = self.rclone.list_remotes()?;
-        for rclone_remote_name in list_remotes {
-            if self
-                .config
-                .remote_configurations
-                .iter()
-                .any(|r| r.remote_name == rclone_remote_name)
-                && self.galion_args.ignore_duplicate_remote
-            {
-                continue;
-            }
-            let remote_conf = self.rclone.get_remote(&rclone_remote_name)?;
-            let remote_dest = remote_conf
-                .get("remote")
-                .and_then(|v| v.as_str())
-                .map(String::from);
-            let remote_config = RemoteConfiguration {
-                remote_name: rclone_remote_name,
-                remote_src: None,
-                remote_dest,
-                config_origin: ConfigOrigin::RcloneConfig,
-            };
-            self.config.remote_configurations.push(remote_config);
+            self.unlock_config(e)?;
         }
+        self.config
+            .merge_rclone_remotes(&self.rclone, self.galion_args.ignore_duplicate_remote)?;
         if self.config.remote_configurations.is_empty() {
             return Err(GalionError::new(format!(
                 "No remote found in rclone 'config/listremotes' and in the galion config at {} - please add remote with rclone CLI",
@@ -255,4 +1004,88 @@ impl GalionApp {
 
         Ok(self)
     }
+
+    /// Rclone error message, shortened
+    fn rclone_error(e: &GalionError) -> String {
+        let err_string = e.to_string();
+        let err_string = if let Ok(j) = serde_json::from_str::<Value>(&err_string)
+            && let Some(Value::String(str)) = j.get("error")
+        {
+            str.clone()
+        } else {
+            err_string
+        };
+        let max_len = 80;
+        // on chars, the messages can hold localized errors of the system
+        if err_string.chars().count() > max_len {
+            format!(
+                "{}...",
+                err_string
+                    .chars()
+                    .take(max_len.saturating_sub(3))
+                    .collect::<String>()
+            )
+        } else {
+            err_string
+        }
+    }
+
+    /// Ask the password of an encrypted rclone config in a terminal, until the config can be read
+    /// # Errors
+    /// Fails if the config cannot be read and no valid password is given
+    fn unlock_config(&self, mut error: GalionError) -> Result<(), GalionError> {
+        let can_prompt = !self.galion_args.rclone_ask_password
+            && self.galion_args.rc_host.is_none()
+            && io::stdin().is_terminal()
+            && io::stdout().is_terminal()
+            && error.to_string().to_lowercase().contains("password");
+        if can_prompt {
+            set_locale(self.config.language.unwrap_or_else(Locale::detect));
+            let mut attempt_error = None;
+            for _ in 0..PASSWORD_ATTEMPTS {
+                let Some(password) = ask_password(attempt_error.take())? else {
+                    break;
+                };
+                match self
+                    .rclone
+                    .unlock_config(&password)
+                    .and_then(|()| self.rclone.dump_config())
+                {
+                    Ok(_) => return Ok(()),
+                    Err(e) => {
+                        attempt_error = Some(Self::rclone_error(&e));
+                        error = e;
+                    }
+                }
+            }
+        }
+        let msg = if self.galion_args.rclone_ask_password || can_prompt {
+            " and the decryption failed"
+        } else {
+            "and you can retry with the --rclone-ask-password flag"
+        };
+        Err(GalionError::new(format!(
+            "Failed to get the rclone configuration. Most likely the configuration is encrypted {msg}.\nRclone internal error: {}",
+            Self::rclone_error(&error)
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rclone_error() {
+        let error = GalionError::new(json!({ "error": "wrong password" }).to_string());
+        assert_eq!(GalionApp::rclone_error(&error), "wrong password");
+        // 2 bytes per char, a byte slice would split one
+        let long = GalionError::new("é".repeat(100));
+        assert_eq!(
+            GalionApp::rclone_error(&long),
+            format!("{}...", "é".repeat(77))
+        );
+        let short = GalionError::new("é".repeat(80));
+        assert_eq!(GalionApp::rclone_error(&short), "é".repeat(80));
+    }
 }
@@ -0,0 +1,149 @@
+//! Benchmark of the destination of a remote, to choose the transfer settings of its backend
+
+use rand::Rng;
+use std::fmt::Write;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::browser::format_size;
+use crate::errors::GalionError;
+use crate::librclone::Rclone;
+use crate::remote::{RclonePath, expand_path, expand_placeholders};
+
+/// Size of the uploaded payload
+const PAYLOAD_SIZE: usize = 16 * 1024 * 1024;
+
+/// Number of calls measuring the latency
+const LATENCY_PROBES: usize = 5;
+
+/// Above this latency, more parallel transfers and checkers hide the round trips
+const HIGH_LATENCY: Duration = Duration::from_millis(100);
+
+/// Result of a benchmark
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Benchmark {
+    /// benchmarked destination
+    pub(crate) dest: String,
+    /// latency of each stat call
+    pub(crate) latencies: Vec<Duration>,
+    /// time to upload the payload
+    pub(crate) upload: Duration,
+    /// time to download the payload
+    pub(crate) download: Duration,
+}
+
+impl Benchmark {
+    /// Measure the latency of the destination, then upload and download a temporary payload,
+    /// the payload is removed from the destination afterwards
+    /// # Errors
+    /// Fails if the destination cannot be reached or if a transfer fails
+    pub fn run(rclone: &Rclone, dest: &str) -> Result<Self, GalionError> {
+        let dest = expand_placeholders(&expand_path(dest));
+        let (fs, remote_path) = RclonePath::split_fs(&dest);
+        let mut latencies = Vec::with_capacity(LATENCY_PROBES);
+        for _ in 0..LATENCY_PROBES {
+            let start = Instant::now();
+            rclone.stat(fs, remote_path, false)?;
+            latencies.push(start.elapsed());
+        }
+        let local = std::env::temp_dir().join(format!("galion-benchmark-{}", std::process::id()));
+        std::fs::create_dir_all(&local)?;
+        let transfers = Self::transfer(rclone, &local, &dest);
+        let _ = std::fs::remove_dir_all(&local);
+        let (upload, download) = transfers?;
+        Ok(Self {
+            dest,
+            latencies,
+            upload,
+            download,
+        })
+    }
+
+    /// Upload then download a random payload, returns the time of each transfer
+    fn transfer(
+        rclone: &Rclone,
+        local: &Path,
+        dest: &str,
+    ) -> Result<(Duration, Duration), GalionError> {
+        let mut payload = vec![0u8; PAYLOAD_SIZE];
+        // random bytes, so a compressing backend doesn't shrink them
+        rand::rng().fill(&mut payload[..]);
+        std::fs::write(local.join("payload"), payload)?;
+        let local = local.to_string_lossy();
+        let name = format!(".galion-benchmark-{}", std::process::id());
+
+        let start = Instant::now();
+        rclone.copy_file((&local, "payload"), (dest, &name), false, None)?;
+        let upload = start.elapsed();
+        let start = Instant::now();
+        let downloaded = rclone.copy_file((dest, &name), (&local, "download"), false, None);
+        let download = start.elapsed();
+        rclone.delete_file(dest, &name, false, None)?;
+        downloaded?;
+        Ok((upload, download))
+    }
+
+    /// Throughput of a transfer of the payload
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // a positive speed
+    #[allow(clippy::cast_precision_loss)] // the payload is small
+    fn throughput(duration: Duration) -> String {
+        let speed = PAYLOAD_SIZE as f64 / duration.as_secs_f64().max(f64::EPSILON);
+        format!("{}/s", format_size(speed as u64))
+    }
+
+    /// Average latency
+    fn latency(&self) -> Duration {
+        let count = u32::try_from(self.latencies.len())
+            .unwrap_or(u32::MAX)
+            .max(1);
+        self.latencies.iter().sum::<Duration>() / count
+    }
+
+    /// One line summary of the benchmark
+    pub fn summary(&self) -> String {
+        format!(
+            "upload {}, download {}, latency {:.0}ms",
+            Self::throughput(self.upload),
+            Self::throughput(self.download),
+            self.latency().as_secs_f64() * 1000.0
+        )
+    }
+
+    /// Report of the benchmark, with a hint on the transfer settings
+    pub fn report(&self) -> String {
+        let mut report = String::new();
+        let _ = writeln!(report, "Destination: {}", self.dest);
+        let _ = writeln!(report, "Payload: {}", format_size(PAYLOAD_SIZE as u64));
+        let _ = writeln!(
+            report,
+            "Upload: {} ({:.1}s)",
+            Self::throughput(self.upload),
+            self.upload.as_secs_f64()
+        );
+        let _ = writeln!(
+            report,
+            "Download: {} ({:.1}s)",
+            Self::throughput(self.download),
+            self.download.as_secs_f64()
+        );
+        let _ = writeln!(
+            report,
+            "Latency: {:.0}ms average, {:.0}ms max over {} calls",
+            self.latency().as_secs_f64() * 1000.0,
+            self.latencies
+                .iter()
+                .max()
+                .unwrap_or(&Duration::ZERO)
+                .as_secs_f64()
+                * 1000.0,
+            self.latencies.len()
+        );
+        let hint = if self.latency() > HIGH_LATENCY {
+            "High latency: raise transfers and checkers to run more calls in parallel"
+        } else {
+            "Low latency: the default transfers and checkers should be enough"
+        };
+        let _ = write!(report, "\n{hint}");
+        report
+    }
+}
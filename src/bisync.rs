@@ -0,0 +1,388 @@
+//! Bisync conflicts and resync handling
+
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use crate::browser::format_size;
+use crate::errors::GalionError;
+use crate::jobs::SyncJobData;
+use crate::librclone::Rclone;
+use crate::librclone::rclone::RcListItem;
+
+/// Include rule of the files renamed by bisync on a conflict
+pub(crate) const CONFLICT_RULE: &str = "*.conflict*";
+
+/// Suffix of the versions of a conflicted file, followed by 1 for the source and 2 for the destination
+const CONFLICT_SUFFIX: &str = ".conflict";
+
+/// Resolution strategy of a bisync in trouble
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BisyncResolution {
+    /// keep the most recent version
+    KeepNewer,
+    /// keep the version of the source (path1)
+    KeepSrc,
+    /// keep the version of the destination (path2)
+    KeepDest,
+    /// rebuild the bisync listings
+    Resync,
+}
+
+impl BisyncResolution {
+    /// All the strategies, in the popup order
+    pub(crate) const ALL: [Self; 4] =
+        [Self::KeepNewer, Self::KeepSrc, Self::KeepDest, Self::Resync];
+
+    /// Rclone name of the winner side
+    fn rclone_name(self) -> &'static str {
+        match self {
+            Self::KeepNewer => "newer",
+            Self::KeepSrc | Self::Resync => "path1",
+            Self::KeepDest => "path2",
+        }
+    }
+}
+
+impl Display for BisyncResolution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::KeepNewer => write!(f, "keep newer"),
+            Self::KeepSrc => write!(f, "keep src"),
+            Self::KeepDest => write!(f, "keep dest"),
+            Self::Resync => write!(f, "resync"),
+        }
+    }
+}
+
+/// Resolution of a conflicted file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum FileResolution {
+    /// keep both versions, with their suffix
+    #[default]
+    Both,
+    /// keep the version of the source
+    Src,
+    /// keep the version of the destination
+    Dest,
+}
+
+impl FileResolution {
+    /// Next resolution, cycling
+    pub fn next(self) -> Self {
+        match self {
+            Self::Both => Self::Src,
+            Self::Src => Self::Dest,
+            Self::Dest => Self::Both,
+        }
+    }
+
+    /// Previous resolution, cycling
+    pub fn previous(self) -> Self {
+        match self {
+            Self::Both => Self::Dest,
+            Self::Src => Self::Both,
+            Self::Dest => Self::Src,
+        }
+    }
+}
+
+impl Display for FileResolution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Both => write!(f, "keep both"),
+            Self::Src => write!(f, "keep src"),
+            Self::Dest => write!(f, "keep dest"),
+        }
+    }
+}
+
+/// File changed on both sides of a bisync, whose versions are kept with a suffix on both sides
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FileConflict {
+    /// path of the file, relative to the source and the destination
+    pub(crate) path: String,
+    /// version of the source, like `file.conflict1`
+    pub(crate) src_version: Option<RcListItem>,
+    /// version of the destination, like `file.conflict2`
+    pub(crate) dest_version: Option<RcListItem>,
+    /// chosen resolution
+    pub(crate) resolution: FileResolution,
+}
+
+/// Original path and side of a version of a conflicted file, like `file.conflict1` or `file.conflict1.txt`
+fn split_conflict(path: &str) -> Option<(String, u8)> {
+    let idx = path.rfind(CONFLICT_SUFFIX)?;
+    let after = &path[idx + CONFLICT_SUFFIX.len()..];
+    let digits = after.len() - after.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let side = after[..digits].parse::<u8>().ok()?;
+    // the extension is kept after the suffix with `--suffix-keep-extension`
+    Some((format!("{}{}", &path[..idx], &after[digits..]), side))
+}
+
+impl FileConflict {
+    /// Conflicts of the versions found on the source
+    pub fn from_items(items: Vec<RcListItem>) -> Vec<Self> {
+        let mut conflicts = BTreeMap::<String, Self>::new();
+        for item in items {
+            let Some((path, side)) = split_conflict(&item.path) else {
+                continue;
+            };
+            let conflict = conflicts.entry(path.clone()).or_insert_with(|| Self {
+                path,
+                src_version: None,
+                dest_version: None,
+                resolution: FileResolution::default(),
+            });
+            match side {
+                1 => conflict.src_version = Some(item),
+                2 => conflict.dest_version = Some(item),
+                _ => {}
+            }
+        }
+        conflicts.into_values().collect()
+    }
+
+    /// Row of the conflicts list
+    pub fn describe(&self) -> String {
+        let version = |item: &Option<RcListItem>| {
+            item.as_ref().map_or_else(
+                || "missing".to_string(),
+                |item| {
+                    let size = format_size(u64::try_from(item.size).unwrap_or_default());
+                    format!("{size}, {}", item.mod_time)
+                },
+            )
+        };
+        format!(
+            "{} [{}]\n  src: {}\n  dest: {}",
+            self.path,
+            self.resolution,
+            version(&self.src_version),
+            version(&self.dest_version)
+        )
+    }
+
+    /// Keep the chosen version under the original name on both sides, and delete the other one
+    /// # Errors
+    /// Fails if a version is missing or if rclone fails to move or delete it
+    pub fn apply(&self, rclone: &Rclone, src: &str, dest: &str) -> Result<(), GalionError> {
+        let (winner, loser) = match self.resolution {
+            FileResolution::Both => return Ok(()),
+            FileResolution::Src => (&self.src_version, &self.dest_version),
+            FileResolution::Dest => (&self.dest_version, &self.src_version),
+        };
+        let Some(winner) = winner else {
+            return Err(format!("the chosen version of {} is missing", self.path).into());
+        };
+        // both versions are copied on each side by bisync
+        for fs in [src, dest] {
+            rclone.move_file(fs, &winner.path, fs, &self.path)?;
+            if let Some(loser) = loser {
+                rclone.delete_file(fs, &loser.path, false, None)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Options of a bisync run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BisyncRun {
+    /// strategy chosen after a previous run
+    pub(crate) resolution: Option<BisyncResolution>,
+    /// rebuild the listings, needed after a critical error
+    pub(crate) resync: bool,
+}
+
+impl BisyncRun {
+    /// Params of `sync/bisync`
+    pub(crate) fn params(self) -> Value {
+        match (self.resolution, self.resync) {
+            (None, false) => json!({}),
+            (None | Some(BisyncResolution::Resync), _) => json!({ "resync": true }),
+            (Some(resolution), true) => json!({
+                "resync": true,
+                "resyncMode": resolution.rclone_name(),
+            }),
+            (Some(resolution), false) => json!({
+                "conflictResolve": resolution.rclone_name(),
+                "conflictLoser": "delete",
+            }),
+        }
+    }
+}
+
+/// Does a bisync error ask for a resync
+pub(crate) fn needs_resync(error: &str) -> bool {
+    error.contains("--resync")
+}
+
+/// Bisync run needing a resolution
+#[derive(Debug)]
+pub(crate) struct BisyncConflicts {
+    /// finished bisync job
+    pub(crate) job: SyncJobData,
+    /// conflicted files
+    pub(crate) files: Vec<FileConflict>,
+    /// the run failed and asks for a resync
+    pub(crate) needs_resync: bool,
+    /// index of the highlighted strategy in [`BisyncResolution::ALL`]
+    pub(crate) selected: usize,
+    /// index of the highlighted file
+    pub(crate) selected_file: usize,
+    /// the files are focused, not the strategies
+    pub(crate) files_focused: bool,
+}
+
+impl BisyncConflicts {
+    /// Resolution popup of a bisync run, with the files focused if there are some
+    pub fn new(job: SyncJobData, files: Vec<FileConflict>, needs_resync: bool) -> Self {
+        Self {
+            job,
+            files_focused: !files.is_empty(),
+            files,
+            needs_resync,
+            selected: 0,
+            selected_file: 0,
+        }
+    }
+
+    /// Focus the files or the strategies
+    pub fn toggle_focus(&mut self) {
+        self.files_focused = !self.files_focused && !self.files.is_empty();
+    }
+
+    /// Highlight the next file or strategy
+    pub fn next(&mut self) {
+        if self.files_focused {
+            self.selected_file = (self.selected_file + 1).min(self.files.len() - 1);
+        } else {
+            self.selected = (self.selected + 1).min(BisyncResolution::ALL.len() - 1);
+        }
+    }
+
+    /// Highlight the previous file or strategy
+    pub fn previous(&mut self) {
+        if self.files_focused {
+            self.selected_file = self.selected_file.saturating_sub(1);
+        } else {
+            self.selected = self.selected.saturating_sub(1);
+        }
+    }
+
+    /// Change the resolution of the highlighted file
+    pub fn cycle_resolution(&mut self, forward: bool) {
+        if let Some(file) = self.files.get_mut(self.selected_file) {
+            file.resolution = if forward {
+                file.resolution.next()
+            } else {
+                file.resolution.previous()
+            };
+        }
+    }
+
+    /// Apply the resolutions of the files, returns the number of resolved files
+    /// # Errors
+    /// Fails on the first file that cannot be resolved
+    pub fn apply_files(&self, rclone: &Rclone) -> Result<usize, GalionError> {
+        let mut resolved = 0;
+        for file in &self.files {
+            if file.resolution != FileResolution::Both {
+                file.apply(rclone, &self.job.src, &self.job.dest)?;
+                resolved += 1;
+            }
+        }
+        Ok(resolved)
+    }
+
+    /// Job applying the highlighted strategy
+    pub fn resolve(&self) -> SyncJobData {
+        let mut job = self.job.clone();
+        job.job_id = 0;
+        job.bisync = BisyncRun {
+            resolution: Some(BisyncResolution::ALL[self.selected]),
+            resync: self.needs_resync,
+        };
+        job
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(path: &str) -> RcListItem {
+        RcListItem {
+            path: path.to_string(),
+            ..RcListItem::default()
+        }
+    }
+
+    #[test]
+    fn test_split_conflict() {
+        assert_eq!(
+            split_conflict("dir/file.conflict1"),
+            Some(("dir/file".to_string(), 1))
+        );
+        // with `--suffix-keep-extension`
+        assert_eq!(
+            split_conflict("file.conflict2.txt"),
+            Some(("file.txt".to_string(), 2))
+        );
+        assert_eq!(
+            split_conflict("a.conflict.conflict12"),
+            Some(("a.conflict".to_string(), 12))
+        );
+        assert_eq!(split_conflict("file.txt"), None);
+        assert_eq!(split_conflict("file.conflict"), None);
+        assert_eq!(split_conflict("file.conflictx"), None);
+    }
+
+    #[test]
+    fn test_conflicts_from_items() {
+        let conflicts = FileConflict::from_items(vec![
+            item("b.conflict2.txt"),
+            item("a.conflict1"),
+            item("b.conflict1.txt"),
+            item("not_a_conflict"),
+            item("a.conflict3"),
+        ]);
+        assert_eq!(
+            conflicts,
+            [
+                FileConflict {
+                    path: "a".to_string(),
+                    src_version: Some(item("a.conflict1")),
+                    dest_version: None,
+                    resolution: FileResolution::default(),
+                },
+                FileConflict {
+                    path: "b.txt".to_string(),
+                    src_version: Some(item("b.conflict1.txt")),
+                    dest_version: Some(item("b.conflict2.txt")),
+                    resolution: FileResolution::default(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bisync_params() {
+        let run = |resolution, resync| BisyncRun { resolution, resync }.params();
+        assert_eq!(run(None, false), json!({}));
+        assert_eq!(run(None, true), json!({ "resync": true }));
+        assert_eq!(
+            run(Some(BisyncResolution::Resync), false),
+            json!({ "resync": true })
+        );
+        assert_eq!(
+            run(Some(BisyncResolution::KeepDest), true),
+            json!({ "resync": true, "resyncMode": "path2" })
+        );
+        assert_eq!(
+            run(Some(BisyncResolution::KeepNewer), false),
+            json!({ "conflictResolve": "newer", "conflictLoser": "delete" })
+        );
+    }
+}
@@ -0,0 +1,437 @@
+//! Lazily-expanding tree explorer of a remote
+
+use ratatui::widgets::ListState;
+use serde_json::json;
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::errors::GalionError;
+use crate::librclone::rclone::{RcListItem, Rclone};
+
+/// Number of entries shown per directory before a "more" row
+const PAGE_SIZE: usize = 100;
+
+/// Row of the tree
+#[derive(Debug, Clone)]
+pub(crate) enum BrowserRow {
+    /// File or directory
+    Item {
+        /// listed item
+        item: RcListItem,
+        /// depth in the tree
+        depth: usize,
+        /// is the directory expanded
+        expanded: bool,
+    },
+    /// Next entries of a directory, not shown yet
+    More {
+        /// depth in the tree
+        depth: usize,
+        /// hidden entries
+        items: Vec<RcListItem>,
+    },
+}
+
+impl BrowserRow {
+    /// Depth in the tree
+    fn depth(&self) -> usize {
+        match self {
+            Self::Item { depth, .. } | Self::More { depth, .. } => *depth,
+        }
+    }
+
+    /// Text of the row
+    pub(crate) fn label(&self) -> String {
+        match self {
+            Self::Item {
+                item,
+                depth,
+                expanded,
+            } => {
+                let indent = "  ".repeat(*depth);
+                if item.is_dir {
+                    let arrow = if *expanded { "▾" } else { "▸" };
+                    format!("{indent}{arrow} {}/", item.name)
+                } else {
+                    format!(
+                        "{indent}  {}  {}  {}",
+                        item.name,
+                        // negative sizes are unknown
+                        u64::try_from(item.size).map_or_else(|_| "?".to_string(), format_size),
+                        item.mod_time
+                    )
+                }
+            }
+            Self::More { depth, items } => {
+                format!("{}  … {} more", "  ".repeat(*depth), items.len())
+            }
+        }
+    }
+}
+
+/// Human readable size
+pub(crate) fn format_size(size: u64) -> String {
+    /// Units of the sizes
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut unit = 0;
+    let mut scaled = size;
+    while scaled >= 1024 && unit < UNITS.len() - 1 {
+        scaled /= 1024;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{size} B")
+    } else {
+        let divisor = 1u64 << (10 * unit);
+        format!(
+            "{}.{} {}",
+            size / divisor,
+            (size % divisor) * 10 / divisor,
+            UNITS[unit]
+        )
+    }
+}
+
+/// Join a rclone fs and a path relative to it
+pub(crate) fn join_path(fs: &str, path: &str) -> String {
+    if path.is_empty() {
+        fs.to_string()
+    } else if fs.is_empty() || fs.ends_with([':', '/']) {
+        format!("{fs}{path}")
+    } else {
+        format!("{fs}/{path}")
+    }
+}
+
+/// Parent of a rclone path, `None` at the root of a remote or of the filesystem
+fn parent_path(path: &str) -> Option<String> {
+    let trimmed = path.trim_end_matches('/');
+    let idx = trimmed.rfind(['/', ':'])?;
+    let parent = if trimmed[idx..].starts_with(':') {
+        &trimmed[..=idx]
+    } else if idx == 0 {
+        "/"
+    } else {
+        &trimmed[..idx]
+    };
+    (parent != trimmed).then(|| parent.to_string())
+}
+
+/// Tree explorer of a remote path
+#[derive(Debug)]
+pub(crate) struct Browser {
+    /// explored rclone path, the listed paths are relative to it
+    pub(crate) fs: String,
+    /// title of the explorer
+    pub(crate) title: String,
+    /// source to restore to, for the versions of a backup dir
+    pub(crate) restore_to: Option<String>,
+    /// visible rows
+    pub(crate) rows: Vec<BrowserRow>,
+    /// selected row
+    pub(crate) state: ListState,
+}
+
+impl Browser {
+    /// List the root of a remote path
+    /// # Errors
+    /// Fails if the path cannot be listed
+    pub(crate) fn open(rclone: &Rclone, fs: String) -> Result<Self, GalionError> {
+        let items = Self::list_dir(rclone, &fs, "")?;
+        Ok(Self::from_items(fs.clone(), fs, items))
+    }
+
+    /// Explorer of the parent directory, `None` at the root or for the versions and the search results
+    /// # Errors
+    /// Fails if the parent directory cannot be listed
+    pub(crate) fn parent(&self, rclone: &Rclone) -> Result<Option<Self>, GalionError> {
+        if self.restore_to.is_some() || self.title != self.fs {
+            return Ok(None);
+        }
+        let Some(parent) = parent_path(&self.fs) else {
+            return Ok(None);
+        };
+        let mut browser = Self::open(rclone, parent)?;
+        // select the directory we came from
+        let name = self.fs.trim_end_matches('/').rsplit(['/', ':']).next();
+        if let Some(idx) = browser.rows.iter().position(
+            |row| matches!(row, BrowserRow::Item { item, .. } if Some(item.name.as_str()) == name),
+        ) {
+            browser.state.select(Some(idx));
+        }
+        Ok(Some(browser))
+    }
+
+    /// List the dated versions of a backup dir, newest first, restored to `src`
+    /// # Errors
+    /// Fails if the backup dir cannot be listed
+    pub(crate) fn versions(
+        rclone: &Rclone,
+        backup_dir: String,
+        src: String,
+    ) -> Result<Self, GalionError> {
+        let mut items = Self::list_dir(rclone, &backup_dir, "")?;
+        items.reverse();
+        let title = format!("{backup_dir} - versions");
+        let mut browser = Self::from_items(backup_dir, title, items);
+        browser.restore_to = Some(src);
+        Ok(browser)
+    }
+
+    /// Backup path of the selected item and where to restore it, with its type
+    pub(crate) fn restore_target(&self) -> Option<(String, String, bool)> {
+        let restore_to = self.restore_to.as_ref()?;
+        let item = self.selected_item()?;
+        // strip the dated directory
+        let relative_path = item.path.split_once('/').map_or("", |(_, path)| path);
+        Some((
+            join_path(&self.fs, &item.path),
+            join_path(restore_to, relative_path),
+            item.is_dir,
+        ))
+    }
+
+    /// Recursively search the files of a remote path matching an include glob
+    /// # Errors
+    /// Fails if the path cannot be listed
+    pub(crate) fn search(rclone: &Rclone, fs: String, include: &str) -> Result<Self, GalionError> {
+        let mut items = rclone.search(&fs, include)?;
+        items.sort_by(|a, b| a.path.cmp(&b.path));
+        for item in &mut items {
+            // show the full path of the matches
+            item.name.clone_from(&item.path);
+        }
+        let title = format!("{fs} - {} matches for '{include}'", items.len());
+        Ok(Self::from_items(fs, title, items))
+    }
+
+    /// Explorer showing some items
+    fn from_items(fs: String, title: String, items: Vec<RcListItem>) -> Self {
+        let mut state = ListState::default();
+        state.select(Some(0));
+        Self {
+            fs,
+            title,
+            restore_to: None,
+            rows: Self::page(items, 0),
+            state,
+        }
+    }
+
+    /// List a directory, directories first
+    fn list_dir(rclone: &Rclone, fs: &str, path: &str) -> Result<Vec<RcListItem>, GalionError> {
+        let mut items = rclone.list(fs, path, &json!({}))?;
+        items.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+        Ok(items)
+    }
+
+    /// Rows of the first page of entries, with a "more" row for the rest
+    fn page(mut items: Vec<RcListItem>, depth: usize) -> Vec<BrowserRow> {
+        let rest = items.split_off(items.len().min(PAGE_SIZE));
+        let mut rows = items
+            .into_iter()
+            .map(|item| BrowserRow::Item {
+                item,
+                depth,
+                expanded: false,
+            })
+            .collect::<Vec<_>>();
+        if !rest.is_empty() {
+            rows.push(BrowserRow::More { depth, items: rest });
+        }
+        rows
+    }
+
+    /// Selected item
+    pub(crate) fn selected_item(&self) -> Option<&RcListItem> {
+        match self.state.selected().and_then(|idx| self.rows.get(idx)) {
+            Some(BrowserRow::Item { item, .. }) => Some(item),
+            _ => None,
+        }
+    }
+
+    /// Full rclone path of the selected item
+    pub(crate) fn selected_path(&self) -> Option<String> {
+        self.selected_item()
+            .map(|item| join_path(&self.fs, &item.path))
+    }
+
+    /// Full rclone path of the selected directory, or of the directory of the selected file
+    pub(crate) fn selected_dir(&self) -> String {
+        match self.selected_item() {
+            Some(item) if item.is_dir => join_path(&self.fs, &item.path),
+            Some(item) => match item.path.rsplit_once('/') {
+                Some((parent, _)) => join_path(&self.fs, parent),
+                None => self.fs.clone(),
+            },
+            None => self.fs.clone(),
+        }
+    }
+
+    /// Read the selected text file, if it is smaller than `max_size`
+    /// # Errors
+    /// Fails if the selected item is not a small text file or cannot be downloaded
+    pub(crate) fn read_selected(
+        &self,
+        rclone: &Rclone,
+        max_size: u64,
+    ) -> Result<(String, String), GalionError> {
+        let Some(item) = self.selected_item() else {
+            return Err("no file selected".into());
+        };
+        if item.is_dir {
+            return Err("cannot preview a directory".into());
+        }
+        let size = u64::try_from(item.size).map_err(|_| "unknown file size")?;
+        if size > max_size {
+            return Err(format!(
+                "the file is too big ({} > {})",
+                format_size(size),
+                format_size(max_size)
+            )
+            .into());
+        }
+        // rclone cannot stream a file through the rc api, copy it to a temporary file
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        let tmp_dir = std::env::temp_dir();
+        let tmp_name = format!("galion-preview-{}-{unique}", std::process::id());
+        let copied = rclone.copy_file(
+            (&self.fs, &item.path),
+            (&tmp_dir.to_string_lossy(), &tmp_name),
+            false,
+            None,
+        );
+        let content = copied.and_then(|_| Ok(fs::read(tmp_dir.join(&tmp_name))?));
+        if fs::remove_file(tmp_dir.join(&tmp_name)).is_err() {
+            // nothing was copied
+        }
+        let content = content?;
+        if content.contains(&0) {
+            return Err("binary file".into());
+        }
+        let path = join_path(&self.fs, &item.path);
+        Ok((path, String::from_utf8_lossy(&content).into_owned()))
+    }
+
+    /// Rename the selected item
+    /// # Errors
+    /// Fails if the new name is invalid or if the move fails
+    pub(crate) fn rename_selected(
+        &mut self,
+        rclone: &Rclone,
+        new_name: &str,
+    ) -> Result<(), GalionError> {
+        if new_name.is_empty() || new_name.contains('/') {
+            return Err(GalionError::new(format!("invalid name '{new_name}'")));
+        }
+        let Some(idx) = self.state.selected() else {
+            return Ok(());
+        };
+        if let Some(BrowserRow::Item { expanded: true, .. }) = self.rows.get(idx) {
+            // the children paths are about to change
+            self.collapse();
+        }
+        let Some(BrowserRow::Item { item, .. }) = self.rows.get_mut(idx) else {
+            return Ok(());
+        };
+        let new_path = match item.path.rsplit_once('/') {
+            Some((parent, _)) => format!("{parent}/{new_name}"),
+            None => new_name.to_string(),
+        };
+        if item.is_dir {
+            rclone.move_dir(
+                &join_path(&self.fs, &item.path),
+                &join_path(&self.fs, &new_path),
+            )?;
+        } else {
+            rclone.move_file(&self.fs, &item.path, &self.fs, &new_path)?;
+        }
+        // search results show the full path
+        item.name = if item.name == item.path {
+            new_path.clone()
+        } else {
+            new_name.to_string()
+        };
+        item.path = new_path;
+        Ok(())
+    }
+
+    /// Remove the selected row, for a deleted file
+    pub(crate) fn remove_selected(&mut self) {
+        if let Some(idx) = self.state.selected()
+            && idx < self.rows.len()
+        {
+            self.rows.remove(idx);
+            self.state
+                .select(Some(idx.min(self.rows.len().saturating_sub(1))));
+        }
+    }
+
+    /// Select the next row
+    pub(crate) fn next(&mut self) {
+        let last = self.rows.len().saturating_sub(1);
+        let idx = self.state.selected().map_or(0, |idx| (idx + 1).min(last));
+        self.state.select(Some(idx));
+    }
+
+    /// Select the previous row
+    pub(crate) fn previous(&mut self) {
+        let idx = self.state.selected().map_or(0, |idx| idx.saturating_sub(1));
+        self.state.select(Some(idx));
+    }
+
+    /// Expand the selected directory, or show the next entries
+    /// # Errors
+    /// Fails if the directory cannot be listed
+    pub(crate) fn expand(&mut self, rclone: &Rclone) -> Result<(), GalionError> {
+        let Some(idx) = self.state.selected() else {
+            return Ok(());
+        };
+        let (insert_at, new_rows) = match self.rows.get_mut(idx) {
+            Some(BrowserRow::Item {
+                item,
+                depth,
+                expanded,
+            }) if item.is_dir && !*expanded => {
+                let items = Self::list_dir(rclone, &self.fs, &item.path)?;
+                *expanded = true;
+                (idx + 1, Self::page(items, *depth + 1))
+            }
+            Some(BrowserRow::More { depth, items }) => {
+                let items = std::mem::take(items);
+                let depth = *depth;
+                self.rows.remove(idx);
+                (idx, Self::page(items, depth))
+            }
+            _ => return Ok(()),
+        };
+        self.rows.splice(insert_at..insert_at, new_rows);
+        Ok(())
+    }
+
+    /// Collapse the selected directory, or select its parent
+    pub(crate) fn collapse(&mut self) {
+        let Some(idx) = self.state.selected() else {
+            return;
+        };
+        let Some(row) = self.rows.get_mut(idx) else {
+            return;
+        };
+        let depth = row.depth();
+        if let BrowserRow::Item { expanded, .. } = row
+            && *expanded
+        {
+            *expanded = false;
+            let end = self.rows[idx + 1..]
+                .iter()
+                .position(|row| row.depth() <= depth)
+                .map_or(self.rows.len(), |pos| idx + 1 + pos);
+            self.rows.drain(idx + 1..end);
+        } else if let Some(parent) = self.rows[..idx].iter().rposition(|row| row.depth() < depth) {
+            self.state.select(Some(parent));
+        }
+    }
+}
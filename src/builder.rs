@@ -0,0 +1,331 @@
+//! Guided creation of rclone remotes
+
+use serde_json::{Value, json};
+
+use crate::errors::GalionError;
+use crate::input::TextInput;
+use crate::librclone::rclone::{RcProvider, RcProviderOption};
+
+/// Policies of an union remote
+const UNION_POLICIES: [&str; 14] = [
+    "all", "epall", "epff", "eplfs", "eplno", "eplus", "epmfs", "ff", "lfs", "lno", "lus", "mfs",
+    "newest", "rand",
+];
+
+/// Focused part of the union builder
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum UnionFocus {
+    /// remote name
+    Name,
+    /// upstream remotes
+    Members,
+    /// policy, index in [`UnionBuilder::POLICY_NAMES`]
+    Policy(usize),
+}
+
+/// Union remote builder state
+#[derive(Debug)]
+pub(crate) struct UnionBuilder {
+    /// name of the new remote
+    pub(crate) name: TextInput,
+    /// existing remotes, and if they are members of the union
+    pub(crate) members: Vec<(String, bool)>,
+    /// highlighted remote
+    pub(crate) member_cursor: usize,
+    /// index of the selected policies in [`UNION_POLICIES`]
+    policies: [usize; 3],
+    /// focused part
+    pub(crate) focus: UnionFocus,
+}
+
+impl UnionBuilder {
+    /// Names of the policy parameters
+    pub(crate) const POLICY_NAMES: [&str; 3] = ["action_policy", "create_policy", "search_policy"];
+
+    /// Builder with the existing remotes as candidates, with the rclone default policies
+    pub fn new(remotes: Vec<String>) -> Self {
+        let default_policy = |policy| {
+            UNION_POLICIES
+                .iter()
+                .position(|p| *p == policy)
+                .unwrap_or_default()
+        };
+        Self {
+            name: TextInput::default(),
+            members: remotes.into_iter().map(|name| (name, false)).collect(),
+            member_cursor: 0,
+            policies: [
+                default_policy("epall"),
+                default_policy("epmfs"),
+                default_policy("ff"),
+            ],
+            focus: UnionFocus::Name,
+        }
+    }
+
+    /// Selected value of a policy
+    pub fn policy(&self, idx: usize) -> &'static str {
+        UNION_POLICIES[self.policies[idx]]
+    }
+
+    /// Focus the next part
+    pub fn next_focus(&mut self) {
+        self.focus = match self.focus {
+            UnionFocus::Name => UnionFocus::Members,
+            UnionFocus::Members => UnionFocus::Policy(0),
+            UnionFocus::Policy(idx) => UnionFocus::Policy((idx + 1).min(2)),
+        };
+    }
+
+    /// Focus the previous part
+    pub fn previous_focus(&mut self) {
+        self.focus = match self.focus {
+            UnionFocus::Name | UnionFocus::Members => UnionFocus::Name,
+            UnionFocus::Policy(0) => UnionFocus::Members,
+            UnionFocus::Policy(idx) => UnionFocus::Policy(idx - 1),
+        };
+    }
+
+    /// Move in the focused list: the highlighted remote or the policy value
+    pub fn move_cursor(&mut self, forward: bool) {
+        let (value, len) = match self.focus {
+            UnionFocus::Name => return,
+            UnionFocus::Members => (&mut self.member_cursor, self.members.len()),
+            UnionFocus::Policy(idx) => (&mut self.policies[idx], UNION_POLICIES.len()),
+        };
+        if len == 0 {
+            return;
+        }
+        *value = if forward {
+            (*value + 1) % len
+        } else {
+            (*value + len - 1) % len
+        };
+    }
+
+    /// Add or remove the highlighted remote from the members
+    pub fn toggle_member(&mut self) {
+        if let Some((_, selected)) = self.members.get_mut(self.member_cursor) {
+            *selected = !*selected;
+        }
+    }
+
+    /// Name and parameters of the remote to create
+    /// # Errors
+    /// Fails if the name is empty or taken, or if there is no member
+    pub fn finish(&self) -> Result<(String, Value), GalionError> {
+        let name = self.name.value.trim();
+        if name.is_empty() {
+            return Err("the remote name is empty".into());
+        }
+        if self.members.iter().any(|(member, _)| member == name) {
+            return Err(format!("a rclone remote named '{name}' already exists").into());
+        }
+        let upstreams = self
+            .members
+            .iter()
+            .filter(|(_, selected)| *selected)
+            .map(|(member, _)| format!("{member}:"))
+            .collect::<Vec<String>>();
+        if upstreams.is_empty() {
+            return Err("an union needs at least one member".into());
+        }
+        let mut parameters = json!({ "upstreams": upstreams.join(" ") });
+        for (idx, policy_name) in Self::POLICY_NAMES.iter().enumerate() {
+            parameters[policy_name] = json!(self.policy(idx));
+        }
+        Ok((name.to_string(), parameters))
+    }
+}
+
+/// Step of the remote wizard
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum WizardStep {
+    /// choice of the backend type
+    Backend,
+    /// name and options of the remote
+    Options,
+}
+
+/// Field of the remote wizard, the option is `None` for the remote name
+#[derive(Debug)]
+pub(crate) struct WizardField {
+    /// backend option of the field
+    pub(crate) option: Option<RcProviderOption>,
+    /// value
+    pub(crate) input: TextInput,
+}
+
+impl WizardField {
+    /// Label of the field, the first line of the help of the option
+    pub fn label(&self) -> String {
+        let Some(option) = &self.option else {
+            return "Remote name".to_string();
+        };
+        let help = option.help.lines().next().unwrap_or_default();
+        let required = if option.required { " (required)" } else { "" };
+        format!("{}{required}: {help}", option.name)
+    }
+
+    /// Shown value, the passwords are masked
+    pub fn display_value(&self) -> String {
+        if self
+            .option
+            .as_ref()
+            .is_some_and(|option| option.is_password)
+        {
+            "*".repeat(self.input.value.chars().count())
+        } else {
+            self.input.value.clone()
+        }
+    }
+}
+
+/// Remote wizard state: a backend type, then the name and the options of the remote
+#[derive(Debug)]
+pub(crate) struct RemoteWizard {
+    /// backend types
+    providers: Vec<RcProvider>,
+    /// existing remotes
+    remotes: Vec<String>,
+    /// current step
+    pub(crate) step: WizardStep,
+    /// search of the backend types
+    pub(crate) search: TextInput,
+    /// highlighted backend type, in the matching ones
+    pub(crate) cursor: usize,
+    /// fields of the chosen backend type
+    pub(crate) fields: Vec<WizardField>,
+    /// selected field
+    pub(crate) field_idx: usize,
+}
+
+impl RemoteWizard {
+    /// Wizard of the backend types, the existing remotes names are refused
+    pub fn new(providers: Vec<RcProvider>, remotes: Vec<String>) -> Self {
+        Self {
+            providers,
+            remotes,
+            step: WizardStep::Backend,
+            search: TextInput::default(),
+            cursor: 0,
+            fields: Vec::new(),
+            field_idx: 0,
+        }
+    }
+
+    /// Backend types matching the search
+    pub fn matching(&self) -> Vec<&RcProvider> {
+        let search = self.search.value.to_lowercase();
+        self.providers
+            .iter()
+            .filter(|provider| {
+                provider.name.contains(&search)
+                    || provider.description.to_lowercase().contains(&search)
+            })
+            .collect()
+    }
+
+    /// Highlight the next or the previous backend type
+    pub fn move_cursor(&mut self, forward: bool) {
+        let len = self.matching().len();
+        self.cursor = if forward {
+            (self.cursor + 1).min(len.saturating_sub(1))
+        } else {
+            self.cursor.saturating_sub(1)
+        };
+    }
+
+    /// The search changed, the first matching backend type is highlighted
+    pub fn reset_cursor(&mut self) {
+        self.cursor = 0;
+    }
+
+    /// Go to the options of the highlighted backend type, with their default values
+    pub fn choose_backend(&mut self) {
+        let Some(provider) = self.matching().get(self.cursor).copied() else {
+            return;
+        };
+        let options = provider
+            .options
+            .iter()
+            .filter(|option| !option.advanced && option.hide == 0)
+            .cloned()
+            .collect::<Vec<RcProviderOption>>();
+        self.fields = std::iter::once(WizardField {
+            option: None,
+            input: TextInput::default(),
+        })
+        .chain(options.into_iter().map(|option| WizardField {
+            input: TextInput::new(option.default_str.clone()),
+            option: Some(option),
+        }))
+        .collect();
+        self.field_idx = 0;
+        self.step = WizardStep::Options;
+    }
+
+    /// Back to the choice of the backend type
+    pub fn back(&mut self) {
+        self.step = WizardStep::Backend;
+    }
+
+    /// Name of the chosen backend type
+    pub fn backend(&self) -> Option<&str> {
+        self.matching()
+            .get(self.cursor)
+            .map(|provider| provider.name.as_str())
+    }
+
+    /// Select the next or the previous field
+    pub fn move_field(&mut self, forward: bool) {
+        self.field_idx = if forward {
+            (self.field_idx + 1).min(self.fields.len().saturating_sub(1))
+        } else {
+            self.field_idx.saturating_sub(1)
+        };
+    }
+
+    /// Focused input: the search or the selected field
+    pub fn selected(&mut self) -> &mut TextInput {
+        match self.step {
+            WizardStep::Backend => &mut self.search,
+            WizardStep::Options => match self.fields.get_mut(self.field_idx) {
+                Some(field) => &mut field.input,
+                None => &mut self.search,
+            },
+        }
+    }
+
+    /// Name, backend type and parameters of the remote to create, the options left to their default are not set
+    /// # Errors
+    /// Fails if the name is empty or taken, or if a required option is empty
+    pub fn finish(&self) -> Result<(String, String, Value), GalionError> {
+        let backend = self.backend().ok_or("no backend type chosen")?;
+        let name = self
+            .fields
+            .first()
+            .map(|field| field.input.value.trim())
+            .unwrap_or_default();
+        if name.is_empty() {
+            return Err("the remote name is empty".into());
+        }
+        if self.remotes.iter().any(|remote| remote == name) {
+            return Err(format!("a rclone remote named '{name}' already exists").into());
+        }
+        let mut parameters = json!({});
+        for field in &self.fields {
+            let Some(option) = &field.option else {
+                continue;
+            };
+            let value = field.input.value.trim();
+            if value.is_empty() && option.required {
+                return Err(format!("the option {} is required", option.name).into());
+            }
+            if !value.is_empty() && value != option.default_str {
+                parameters[&option.name] = json!(value);
+            }
+        }
+        Ok((name.to_string(), backend.to_string(), parameters))
+    }
+}
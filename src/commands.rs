@@ -0,0 +1,109 @@
+//! Galion subcommands, run without the tui
+
+use clap::Subcommand;
+use std::io::{self, BufRead};
+use std::path::PathBuf;
+
+use crate::app::{GalionApp, GalionArgs, GalionConfig};
+use crate::errors::GalionError;
+use crate::i18n::{Locale, set_locale};
+use crate::keys::cheat_sheet;
+use crate::librclone::rclone::Rclone;
+
+/// Galion subcommands
+#[derive(Subcommand, Debug, Clone)]
+pub enum GalionCommand {
+    /// Obscure a password for a hand-edited rclone config
+    Obscure {
+        /// Password to obscure, read from stdin if missing
+        password: Option<String>,
+    },
+    /// Add a remote
+    Add {
+        /// Template of the remote
+        #[arg(long)]
+        from_template: String,
+        /// Values of the variables of the template: `key=value`
+        values: Vec<String>,
+    },
+    /// Export the job history as CSV
+    ExportHistory {
+        /// CSV file to write, stdout if missing
+        output: Option<PathBuf>,
+    },
+    /// Run the sync of a remote without the tui, exits with an error if it fails
+    Sync {
+        /// Name of the remote
+        remote: String,
+    },
+    /// Print the cheat sheet of the keys
+    Keys {
+        /// Write Markdown tables instead of plain text
+        #[arg(long)]
+        markdown: bool,
+    },
+}
+
+impl GalionCommand {
+    /// Run the subcommand
+    /// # Errors
+    /// Fails if the subcommand fails
+    pub fn run(self, galion_args: GalionArgs) -> Result<(), GalionError> {
+        match self {
+            Self::Obscure { password } => {
+                let password = if let Some(password) = password {
+                    password
+                } else {
+                    let mut line = String::new();
+                    io::stdin().lock().read_line(&mut line)?;
+                    line.trim_end_matches(['\r', '\n']).to_string()
+                };
+                let rclone = Rclone::new();
+                println!("{}", rclone.obscure(&password)?);
+                Ok(())
+            }
+            Self::Add {
+                from_template,
+                values,
+            } => {
+                let mut config = GalionConfig::load_config(
+                    galion_args.config.clone(),
+                    galion_args.config_format,
+                )?;
+                let remote = config
+                    .instantiate_template(&from_template, values.iter().map(String::as_str))?;
+                let name = remote.remote_name.clone();
+                config.add_remote(remote)?;
+                config.save_config()?;
+                println!("Added the remote {name}");
+                Ok(())
+            }
+            Self::Sync { remote } => {
+                let app = GalionApp::try_from_galion_args(galion_args)?;
+                app.run_sync(&remote)
+            }
+            Self::Keys { markdown } => {
+                // the actions are translated like in the ui
+                let config = GalionConfig::load_config(
+                    galion_args.config.clone(),
+                    galion_args.config_format,
+                )?;
+                set_locale(config.language.unwrap_or_else(Locale::detect));
+                print!("{}", cheat_sheet(markdown));
+                Ok(())
+            }
+            Self::ExportHistory { output } => {
+                let config = GalionConfig::load_config(
+                    galion_args.config.clone(),
+                    galion_args.config_format,
+                )?;
+                let csv = config.history().to_csv()?;
+                match output {
+                    Some(path) => std::fs::write(path, csv)?,
+                    None => print!("{csv}"),
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,161 @@
+//! File formats of the galion config
+
+use clap::ValueEnum;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::path::Path;
+
+use crate::errors::GalionError;
+
+/// Format of the galion config file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ConfigFormat {
+    /// `galion.json`
+    #[default]
+    Json,
+    /// `galion.toml`
+    Toml,
+    /// `galion.yaml`
+    Yaml,
+}
+
+impl ConfigFormat {
+    /// Extension of the config files
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Toml => "toml",
+            Self::Yaml => "yaml",
+        }
+    }
+
+    /// Format of a config file from its extension, JSON if unknown
+    pub fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("toml") => Self::Toml,
+            Some("yaml" | "yml") => Self::Yaml,
+            _ => Self::Json,
+        }
+    }
+
+    /// Serialize the config
+    /// # Errors
+    /// Fails if the config cannot be written in this format
+    pub fn serialize<T: Serialize>(self, config: &T) -> Result<String, GalionError> {
+        let serialized = match self {
+            Self::Json => serde_json::to_string(config)?,
+            Self::Toml => toml::to_string_pretty(config)
+                .map_err(|e| format!("Cannot write the TOML config: {e}"))?,
+            Self::Yaml => serde_norway::to_string(config)
+                .map_err(|e| format!("Cannot write the YAML config: {e}"))?,
+        };
+        Ok(serialized)
+    }
+
+    /// Deserialize the config
+    /// # Errors
+    /// Fails if the config is invalid
+    pub fn deserialize<T: DeserializeOwned>(self, data: &str) -> Result<T, GalionError> {
+        let deserialized = match self {
+            Self::Json => serde_json::from_str(data)?,
+            Self::Toml => toml::from_str(data).map_err(|e| format!("Invalid TOML config: {e}"))?,
+            Self::Yaml => {
+                serde_norway::from_str(data).map_err(|e| format!("Invalid YAML config: {e}"))?
+            }
+        };
+        Ok(deserialized)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::GalionConfig;
+    use serde_json::{Value, json};
+
+    /// Config with remotes, tables and unset optional values
+    fn config_with_remotes() -> GalionConfig {
+        serde_json::from_value(json!({
+            "remote_configurations": [
+                {
+                    "remote_name": "photos",
+                    "remote_src": "~/Pictures",
+                    "remote_dest": "gdrive:Photos",
+                    "tags": ["family"],
+                    "schedule": { "cron": "0 2 * * *" },
+                    "options": { "retries": 3 },
+                },
+                { "remote_name": "music", "remote_dest": "archive:music" },
+            ],
+            "rclone_remotes": { "nas": { "notes": "Shared with the laptop" } },
+            "defaults": { "timeout": "5m" },
+            "max_running_jobs": 2,
+            "duration_alert_factor": 1.5,
+        }))
+        .unwrap_or_else(|e| panic!("invalid test config: {e}"))
+    }
+
+    /// Save and load a config, compared through its JSON value
+    fn round_trip(format: ConfigFormat, config: &GalionConfig) -> (String, Value) {
+        let serialized = format
+            .serialize(config)
+            .unwrap_or_else(|e| panic!("cannot serialize to {format:?}: {e}"));
+        let loaded = format
+            .deserialize::<GalionConfig>(&serialized)
+            .unwrap_or_else(|e| panic!("cannot deserialize {format:?}: {e}\n{serialized}"));
+        (
+            serialized,
+            serde_json::to_value(&loaded).unwrap_or_default(),
+        )
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for config in [GalionConfig::default(), config_with_remotes()] {
+            let expected = serde_json::to_value(&config).unwrap_or_default();
+            for format in [ConfigFormat::Json, ConfigFormat::Toml, ConfigFormat::Yaml] {
+                assert_eq!(round_trip(format, &config).1, expected, "{format:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_toml_rules() {
+        let (serialized, _) = round_trip(ConfigFormat::Toml, &config_with_remotes());
+        let table = serialized
+            .parse::<toml::Table>()
+            .unwrap_or_else(|e| panic!("invalid TOML: {e}"));
+        // TOML has no null, the unset values are left out
+        let music = &table["remote_configurations"][1];
+        assert!(music.get("remote_src").is_none());
+        // the values declared after a table in the config are written before the tables
+        assert_eq!(table["max_running_jobs"].as_integer(), Some(2));
+        assert_eq!(table["defaults"]["timeout"].as_str(), Some("5m"));
+        assert!(table["defaults"].get("max_running_jobs").is_none());
+    }
+
+    #[test]
+    fn test_from_path() {
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("galion.toml")),
+            ConfigFormat::Toml
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("galion.YML")),
+            ConfigFormat::Yaml
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("galion.yaml")),
+            ConfigFormat::Yaml
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("galion")),
+            ConfigFormat::Json
+        );
+    }
+}
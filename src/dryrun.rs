@@ -0,0 +1,76 @@
+//! Dry run of a sync, the files it would copy and delete before running it for real
+
+use std::fmt::Write;
+
+use crate::browser::format_size;
+use crate::librclone::rclone::{RcCheck, RcStats};
+
+/// Maximum number of files listed per section of the report
+const LISTED_FILES: usize = 200;
+
+/// Result of a dry run
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct DryRun {
+    /// source of the sync
+    pub(crate) src: String,
+    /// destination of the sync
+    pub(crate) dest: String,
+    /// stats of the dry run sync
+    pub(crate) stats: RcStats,
+    /// files missing or different on the destination
+    pub(crate) copied: Vec<String>,
+    /// files only on the destination
+    pub(crate) deleted: Vec<String>,
+}
+
+impl DryRun {
+    /// Dry run from the stats of the sync and the check of its paths
+    pub fn new(src: String, dest: String, stats: RcStats, check: &RcCheck) -> Self {
+        let mut copied = Vec::new();
+        let mut deleted = Vec::new();
+        for line in &check.combined {
+            match line.split_once(' ') {
+                Some(("-" | "*", path)) => copied.push(path.to_string()),
+                Some(("+", path)) => deleted.push(path.to_string()),
+                _ => {}
+            }
+        }
+        Self {
+            src,
+            dest,
+            stats,
+            copied,
+            deleted,
+        }
+    }
+
+    /// Report of the dry run, with the files
+    pub fn report(&self) -> String {
+        let mut report = String::new();
+        let _ = writeln!(report, "{} -> {}", self.src, self.dest);
+        let _ = writeln!(
+            report,
+            "Would copy {} files ({}) and delete {} files\n",
+            self.copied.len(),
+            format_size(self.stats.bytes),
+            self.deleted.len()
+        );
+        for (title, files) in [("Copied", &self.copied), ("Deleted", &self.deleted)] {
+            if files.is_empty() {
+                continue;
+            }
+            let _ = writeln!(report, "{title}:");
+            for file in files.iter().take(LISTED_FILES) {
+                let _ = writeln!(report, "  {file}");
+            }
+            if files.len() > LISTED_FILES {
+                let _ = writeln!(report, "  … {} more", files.len() - LISTED_FILES);
+            }
+            report.push('\n');
+        }
+        if self.copied.is_empty() && self.deleted.is_empty() {
+            report.push_str("Nothing to do, the destination is up to date\n");
+        }
+        report
+    }
+}
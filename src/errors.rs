@@ -81,3 +81,62 @@ impl From<Value> for GalionError {
         }
     }
 }
+
+/// Class of a rclone error, only the transient failures are worth a retry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ErrorClass {
+    /// transient failure, like a rate limit, a timeout or a reset connection
+    Retryable,
+    /// permanent failure, like an authentication failure or a missing path
+    Fatal,
+}
+
+impl ErrorClass {
+    /// Markers of the permanent failures, checked first
+    const FATAL: [&str; 14] = [
+        "unauthorized",
+        "forbidden",
+        "permission denied",
+        "access denied",
+        "invalid_grant",
+        "invalid credentials",
+        "authentication failed",
+        "couldn't find section in config",
+        "directory not found",
+        "object not found",
+        "no such file or directory",
+        "quota exceeded",
+        "insufficient",
+        "context canceled",
+    ];
+
+    /// Markers of the transient failures
+    const RETRYABLE: [&str; 15] = [
+        "rate limit",
+        "ratelimit",
+        "too many requests",
+        "timeout",
+        "timed out",
+        "deadline exceeded",
+        "connection reset",
+        "connection refused",
+        "broken pipe",
+        "unexpected eof",
+        "temporarily unavailable",
+        "service unavailable",
+        "bad gateway",
+        "no such host",
+        "network is unreachable",
+    ];
+
+    /// Class of an error message, an unknown error is fatal so it is not retried blindly
+    pub fn classify(error: &str) -> Self {
+        let error = error.to_lowercase();
+        let contains = |markers: &[&str]| markers.iter().any(|marker| error.contains(marker));
+        if !contains(&Self::FATAL) && contains(&Self::RETRYABLE) {
+            Self::Retryable
+        } else {
+            Self::Fatal
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! Editor of the rclone filter rules of a remote
+
+use crate::errors::GalionError;
+use crate::input::TextInput;
+use crate::remote::RemoteConfiguration;
+
+/// Check a rclone filter rule, like `- *.tmp`, `+ docs/**` or `!`
+/// # Errors
+/// Fails if the rule doesn't start with `+ `, `- ` or isn't `!`
+pub(crate) fn check_rule(rule: &str) -> Result<(), GalionError> {
+    match rule.split_once(' ') {
+        Some(("+" | "-", pattern)) if !pattern.trim().is_empty() => Ok(()),
+        None if rule == "!" => Ok(()),
+        _ => Err(
+            format!("invalid filter rule '{rule}' - use '+ pattern', '- pattern' or '!'").into(),
+        ),
+    }
+}
+
+/// Filter rules editor state
+#[derive(Debug)]
+pub(crate) struct FilterEditor {
+    /// name of the edited remote
+    pub(crate) remote_name: String,
+    /// rules, in the order rclone applies them
+    pub(crate) rules: Vec<String>,
+    /// highlighted rule
+    pub(crate) cursor: usize,
+    /// new rule
+    pub(crate) input: TextInput,
+}
+
+impl FilterEditor {
+    /// Editor of the rules of a remote
+    pub fn new(remote: &RemoteConfiguration) -> Self {
+        Self {
+            remote_name: remote.remote_name.clone(),
+            rules: remote.filters.clone(),
+            cursor: 0,
+            input: TextInput::default(),
+        }
+    }
+
+    /// Add the typed rule after the highlighted one
+    /// # Errors
+    /// Fails if the rule is invalid
+    pub fn add_rule(&mut self) -> Result<(), GalionError> {
+        let rule = self.input.value.trim().to_string();
+        check_rule(&rule)?;
+        let idx = if self.rules.is_empty() {
+            0
+        } else {
+            self.cursor + 1
+        };
+        self.rules.insert(idx, rule);
+        self.cursor = idx;
+        self.input = TextInput::default();
+        Ok(())
+    }
+
+    /// Remove the highlighted rule
+    pub fn remove_rule(&mut self) {
+        if self.cursor < self.rules.len() {
+            self.rules.remove(self.cursor);
+            self.cursor = self.cursor.min(self.rules.len().saturating_sub(1));
+        }
+    }
+
+    /// Highlight the next or the previous rule
+    pub fn move_cursor(&mut self, forward: bool) {
+        self.cursor = if forward {
+            (self.cursor + 1).min(self.rules.len().saturating_sub(1))
+        } else {
+            self.cursor.saturating_sub(1)
+        };
+    }
+}
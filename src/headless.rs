@@ -0,0 +1,138 @@
+//! Headless sync of a remote, without the tui, to run from cron
+
+use std::collections::BTreeMap;
+use std::mem::{Discriminant, discriminant};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::app::GalionApp;
+use crate::errors::GalionError;
+use crate::jobs::{JobState, ResultJob, ShutdownPolicy, SyncJob, SyncJobData, background_thread};
+use crate::remote::SyncRecord;
+use crate::ui::UiEvent;
+
+/// Minimum time between two progress lines of a running job
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Last printed state of a job
+struct PrintedState {
+    /// kind of the state
+    kind: Discriminant<JobState>,
+    /// when it was printed
+    at: Instant,
+}
+
+impl GalionApp {
+    /// Run the sync of a remote until it finishes, printing its progress to stdout
+    /// # Errors
+    /// Fails if the remote is unknown or if one of its jobs failed
+    pub fn run_sync(&self, remote_name: &str) -> Result<(), GalionError> {
+        // no ui to wait for, the details can give the destination of the remotes
+        let mut config = self.config.clone();
+        config.load_all_remote_details(&self.rclone);
+        let remote = config
+            .remote_configurations
+            .iter()
+            .find(|remote| remote.remote_name == remote_name)
+            .ok_or_else(|| format!("unknown remote '{remote_name}'"))?;
+        let sync_data = SyncJobData::from_remote(remote)
+            .ok_or_else(|| format!("the remote '{remote_name}' has no source or destination"))?;
+        let config = &config;
+        thread::scope(|s| {
+            let rclone = &self.rclone;
+            let (tx_to_thread, rx_to_thread) = mpsc::channel();
+            let (tx_to_ui, rx_events) = mpsc::channel();
+            let sync_handler: thread::ScopedJoinHandle<'_, Result<(), GalionError>> =
+                s.spawn(move || background_thread(rclone, &tx_to_ui, &rx_to_thread, config));
+            // the chained remotes run before the thread exits
+            let sent = tx_to_thread
+                .send(SyncJob::Sync(Box::new(sync_data)))
+                .and_then(|()| tx_to_thread.send(SyncJob::Exit(ShutdownPolicy::Wait)));
+            let (finished, records) = if sent.is_ok() {
+                Self::print_jobs(&rx_events)
+            } else {
+                (BTreeMap::new(), Vec::new())
+            };
+            let thread_result = sync_handler
+                .join()
+                .map_err(|_e| "Error joining the thread")?;
+            thread_result?;
+            self.save_records(&records)?;
+            let failed = finished
+                .iter()
+                .filter(|(_, job_state)| {
+                    !matches!(job_state, JobState::Done(job_status, _) if job_status.success)
+                })
+                .map(|(job_data, _)| job_data.name.as_str())
+                .collect::<Vec<&str>>();
+            match (finished.is_empty(), failed.is_empty()) {
+                (true, _) => Err(format!("The sync of '{remote_name}' did not run").into()),
+                (false, true) => Ok(()),
+                (false, false) => Err(format!("Failed sync: {}", failed.join(", ")).into()),
+            }
+        })
+    }
+
+    /// Save the sync states counted by the background thread, like the ui does
+    /// # Errors
+    /// Fails if the config cannot be saved
+    fn save_records(&self, records: &[SyncRecord]) -> Result<(), GalionError> {
+        if records.is_empty() {
+            return Ok(());
+        }
+        let mut config = self.config.clone();
+        for name in config.apply_sync_records(records) {
+            eprintln!(
+                "{name} quarantined after {} failures, skipped by the batches",
+                config.quarantine_after
+            );
+        }
+        config.save_config()
+    }
+
+    /// Print the changes of state of the jobs until the background thread exits,
+    /// returns the last state of the jobs and the sync states of the remotes
+    fn print_jobs(
+        rx_events: &mpsc::Receiver<UiEvent>,
+    ) -> (BTreeMap<SyncJobData, JobState>, Vec<SyncRecord>) {
+        let mut printed = BTreeMap::<u64, PrintedState>::new();
+        let mut last_jobs = BTreeMap::new();
+        let mut records = Vec::new();
+        while let Ok(event) = rx_events.recv() {
+            let UiEvent::Job(result) = event else {
+                continue;
+            };
+            match result {
+                ResultJob::Exit => break,
+                ResultJob::Error(msg) | ResultJob::Warning(msg) => eprintln!("{msg}"),
+                ResultJob::Crashed(msg) => eprintln!("Background thread restarted: {msg}"),
+                ResultJob::Report(path) => println!("Summary written to {}", path.display()),
+                ResultJob::Recorded(recorded) => records.extend(recorded),
+                ResultJob::Sync(jobs) => {
+                    for (job_data, job_state) in &jobs {
+                        let kind = discriminant(job_state);
+                        let is_new = printed.get(&job_data.submission).is_none_or(|last| {
+                            last.kind != kind
+                                || (matches!(job_state, JobState::Pending(..))
+                                    && last.at.elapsed() >= PROGRESS_INTERVAL)
+                        });
+                        if is_new {
+                            println!("{}: {job_state}", job_data.name);
+                            printed.insert(
+                                job_data.submission,
+                                PrintedState {
+                                    kind,
+                                    at: Instant::now(),
+                                },
+                            );
+                        }
+                    }
+                    last_jobs = jobs;
+                }
+                ResultJob::Health(..) | ResultJob::Benchmark(..) | ResultJob::DryRun(..) => {}
+            }
+        }
+        (last_jobs, records)
+    }
+}
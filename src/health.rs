@@ -0,0 +1,59 @@
+//! Health of the destinations of the remotes, probed to find the expired credentials before a sync
+
+use ratatui::style::Color;
+use std::fmt::Display;
+use std::time::{Duration, Instant};
+
+use crate::librclone::Rclone;
+use crate::remote::{RclonePath, expand_path, expand_placeholders};
+
+/// A probe slower than this is shown as degraded
+const SLOW_PROBE: Duration = Duration::from_secs(5);
+
+/// Health of the destination of a remote
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Health {
+    /// destination reachable
+    Healthy,
+    /// destination reachable but slow to answer
+    Slow(Duration),
+    /// destination unreachable, with the error
+    Failing(String),
+}
+
+impl Health {
+    /// Probe the destination with a stat, `None` for a local destination
+    pub fn probe(rclone: &Rclone, dest: &str) -> Option<Self> {
+        let dest = expand_placeholders(&expand_path(dest));
+        if !matches!(RclonePath::parse(&dest), RclonePath::Remote(_, _)) {
+            return None;
+        }
+        let (fs, remote_path) = RclonePath::split_fs(&dest);
+        let start = Instant::now();
+        // a missing destination is fine, it is created by the first sync
+        Some(match rclone.stat(fs, remote_path, false) {
+            Ok(_) if start.elapsed() > SLOW_PROBE => Self::Slow(start.elapsed()),
+            Ok(_) => Self::Healthy,
+            Err(e) => Self::Failing(e.to_string()),
+        })
+    }
+
+    /// Color of the indicator
+    pub fn color(&self) -> Color {
+        match self {
+            Self::Healthy => Color::Green,
+            Self::Slow(_) => Color::Yellow,
+            Self::Failing(_) => Color::Red,
+        }
+    }
+}
+
+impl Display for Health {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Healthy => write!(f, "healthy"),
+            Self::Slow(duration) => write!(f, "slow ({:.1}s)", duration.as_secs_f64()),
+            Self::Failing(error) => write!(f, "failing: {error}"),
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! Persistent job history
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::browser::format_size;
+use crate::errors::GalionError;
+use crate::report::csv_line;
+
+/// One finished job
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct HistoryEntry {
+    /// rclone job id
+    pub job_id: u64,
+    /// remote name
+    pub name: String,
+    /// job src
+    pub src: String,
+    /// job dest
+    pub dest: String,
+    /// success status
+    pub success: bool,
+    /// error
+    pub error: String,
+    /// start time
+    pub start_time: String,
+    /// end time
+    pub end_time: String,
+    /// duration in seconds
+    pub duration: f64,
+    /// transferred bytes
+    pub bytes: u64,
+    /// transferred files
+    pub files: u64,
+}
+
+/// Job history store, one JSON entry per line
+#[derive(Debug, Clone)]
+pub struct JobHistory {
+    /// path of the history file
+    path: PathBuf,
+}
+
+impl JobHistory {
+    /// History file name
+    pub const FILE_NAME: &str = "history.jsonl";
+
+    /// Header of the CSV export
+    const CSV_HEADER: [&str; 7] = [
+        "remote", "start", "end", "bytes", "files", "result", "error",
+    ];
+
+    /// Create a new history store
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Append entries to the history file
+    /// # Errors
+    /// Fails if the history file cannot be written
+    pub fn append(&self, entries: &[HistoryEntry]) -> Result<(), GalionError> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let mut buf = Vec::new();
+        for entry in entries {
+            serde_json::to_writer(&mut buf, entry)?;
+            buf.push(b'\n');
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(&buf)?;
+        Ok(())
+    }
+
+    /// Read the entries of the history file, oldest first, skipping the invalid lines
+    /// # Errors
+    /// Fails if the history file exists and cannot be read
+    pub fn load(&self) -> Result<Vec<HistoryEntry>, GalionError> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(&self.path)?;
+        Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str::<HistoryEntry>(line).ok())
+            .collect())
+    }
+
+    /// Export the entries as CSV
+    /// # Errors
+    /// Fails if the history file cannot be read
+    pub fn to_csv(&self) -> Result<String, GalionError> {
+        let mut content = csv_line(&Self::CSV_HEADER);
+        for entry in self.load()? {
+            content.push_str(&csv_line(&[
+                entry.name,
+                entry.start_time,
+                entry.end_time,
+                entry.bytes.to_string(),
+                entry.files.to_string(),
+                if entry.success { "success" } else { "failed" }.to_string(),
+                entry.error,
+            ]));
+        }
+        Ok(content)
+    }
+}
+
+/// Past runs of a remote, newest first
+pub(crate) fn runs_report(entries: &[HistoryEntry], name: &str) -> String {
+    let mut report = String::new();
+    for entry in entries.iter().rev().filter(|entry| entry.name == name) {
+        let _ = write!(
+            report,
+            "{}  {}  {}, {} files in {:.0}s",
+            entry.start_time,
+            if entry.success { "success" } else { "failed" },
+            format_size(entry.bytes),
+            entry.files,
+            entry.duration,
+        );
+        if !entry.error.is_empty() {
+            let _ = write!(report, "\n    {}", entry.error);
+        }
+        report.push('\n');
+    }
+    if report.is_empty() {
+        report.push_str("No past run\n");
+    }
+    report
+}
@@ -0,0 +1,182 @@
+//! Hooks, a rhai script reacting to the galion events with a safe subset of actions
+//!
+//! The script defines the functions of the events it handles:
+//! - `on_job_finished(remote, success, error)`
+//! - `on_remote_added(remote)`
+//! - `on_schedule_fired(remote)`
+//!
+//! and calls the actions `enqueue_sync(remote)`, `notify(message)` and `set_bwlimit(rate)`
+
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{AST, Dynamic, Engine, FuncArgs, Scope};
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::errors::GalionError;
+
+/// Action asked by a hook, run by the job manager once the hook returned
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum HookAction {
+    /// queue the sync of a remote, by name
+    Sync(String),
+    /// show a message in the ui
+    Notify(String),
+    /// set the bandwidth limit, like `1M` or `off`
+    Bwlimit(String),
+}
+
+/// Compiled hooks script
+pub(crate) struct Hooks {
+    /// path of the script
+    pub(crate) path: PathBuf,
+    /// engine, without access to the files and the terminal
+    engine: Engine,
+    /// compiled script
+    ast: AST,
+    /// actions asked by the running hook
+    actions: Rc<RefCell<Vec<HookAction>>>,
+}
+
+impl Hooks {
+    /// Maximum operations of a hook, a looping script is stopped
+    const MAX_OPERATIONS: u64 = 1_000_000;
+
+    /// Compile the script
+    /// # Errors
+    /// Fails if the script cannot be read or compiled
+    pub fn load(path: &Path) -> Result<Self, GalionError> {
+        let actions = Rc::new(RefCell::new(Vec::new()));
+        let mut engine = Engine::new();
+        engine
+            .set_max_operations(Self::MAX_OPERATIONS)
+            .set_module_resolver(DummyModuleResolver::new())
+            // the terminal belongs to the ui
+            .on_print(|_| {})
+            .on_debug(|_, _, _| {});
+        let queue = Rc::clone(&actions);
+        engine.register_fn("enqueue_sync", move |remote: &str| {
+            queue
+                .borrow_mut()
+                .push(HookAction::Sync(remote.to_string()));
+        });
+        let queue = Rc::clone(&actions);
+        engine.register_fn("notify", move |message: &str| {
+            queue
+                .borrow_mut()
+                .push(HookAction::Notify(message.to_string()));
+        });
+        let queue = Rc::clone(&actions);
+        engine.register_fn("set_bwlimit", move |rate: &str| {
+            queue
+                .borrow_mut()
+                .push(HookAction::Bwlimit(rate.to_string()));
+        });
+        let ast = engine
+            .compile_file(path.to_path_buf())
+            .map_err(|e| format!("Cannot load the hooks {}: {e}", path.display()))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            engine,
+            ast,
+            actions,
+        })
+    }
+
+    /// Call the hook of an event if the script defines it, returns the asked actions
+    /// # Errors
+    /// Fails if the hook fails, the actions asked before the failure are dropped
+    pub fn call(&self, event: &str, args: impl FuncArgs) -> Result<Vec<HookAction>, GalionError> {
+        if !self
+            .ast
+            .iter_functions()
+            .any(|function| function.name == event)
+        {
+            return Ok(Vec::new());
+        }
+        let result = self
+            .engine
+            .call_fn::<Dynamic>(&mut Scope::new(), &self.ast, event, args);
+        let actions = std::mem::take(&mut *self.actions.borrow_mut());
+        match result {
+            Ok(_) => Ok(actions),
+            Err(e) => Err(format!("Hook {event} failed: {e}").into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hooks of a script written in the temp dir
+    fn hooks(name: &str, script: &str) -> Hooks {
+        let path =
+            std::env::temp_dir().join(format!("galion-hooks-{}-{name}.rhai", std::process::id()));
+        std::fs::write(&path, script).unwrap_or_else(|e| panic!("cannot write the script: {e}"));
+        let hooks = Hooks::load(&path).unwrap_or_else(|e| panic!("cannot load the script: {e}"));
+        let _ = std::fs::remove_file(&path);
+        hooks
+    }
+
+    #[test]
+    fn test_call_collects_the_actions() {
+        let hooks = hooks(
+            "actions",
+            r#"
+            fn on_job_finished(remote, success, error) {
+                if !success {
+                    notify(remote + " failed: " + error);
+                    enqueue_sync(remote);
+                }
+                set_bwlimit("1M");
+            }
+            "#,
+        );
+        let actions = hooks
+            .call(
+                "on_job_finished",
+                ("photos".to_string(), false, "timeout".to_string()),
+            )
+            .unwrap_or_else(|e| panic!("the hook failed: {e}"));
+        assert_eq!(
+            actions,
+            [
+                HookAction::Notify("photos failed: timeout".to_string()),
+                HookAction::Sync("photos".to_string()),
+                HookAction::Bwlimit("1M".to_string()),
+            ]
+        );
+        // the actions of a call are not kept for the next one
+        let actions = hooks
+            .call(
+                "on_job_finished",
+                ("photos".to_string(), true, String::new()),
+            )
+            .unwrap_or_else(|e| panic!("the hook failed: {e}"));
+        assert_eq!(actions, [HookAction::Bwlimit("1M".to_string())]);
+    }
+
+    #[test]
+    fn test_call_of_a_missing_or_failing_hook() {
+        let hooks = hooks(
+            "failing",
+            r#"
+            fn on_remote_added(remote) {
+                notify("added");
+                throw "broken";
+            }
+            "#,
+        );
+        let actions = hooks.call("on_schedule_fired", ("photos".to_string(),));
+        assert_eq!(actions.ok(), Some(Vec::new()));
+        assert!(
+            hooks
+                .call("on_remote_added", ("photos".to_string(),))
+                .is_err()
+        );
+        // the actions asked before the failure are dropped
+        let actions = hooks.call("on_schedule_fired", ("photos".to_string(),));
+        assert_eq!(actions.ok(), Some(Vec::new()));
+    }
+}
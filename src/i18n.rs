@@ -0,0 +1,99 @@
+//! Translations of the user interface, from the files of the `locales` directory
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use crate::errors::GalionError;
+
+/// Language of the interface
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    /// English
+    #[default]
+    En,
+    /// French
+    Fr,
+}
+
+/// Translations of a locale, by key
+type Strings = BTreeMap<String, String>;
+
+/// Locale of the interface, set once at startup
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+/// English strings, also used for the keys missing in the other locales
+static EN: OnceLock<Strings> = OnceLock::new();
+
+/// French strings
+static FR: OnceLock<Strings> = OnceLock::new();
+
+impl Locale {
+    /// Locale of the environment, from `LC_ALL`, `LC_MESSAGES` or `LANG`
+    pub fn detect() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| value.get(..2).and_then(|lang| lang.parse().ok()))
+            .unwrap_or_default()
+    }
+
+    /// Strings of the locale
+    fn strings(self) -> &'static Strings {
+        let (cell, content) = match self {
+            Self::En => (&EN, include_str!("../locales/en.json")),
+            Self::Fr => (&FR, include_str!("../locales/fr.json")),
+        };
+        // a broken locale file falls back to English, then to the keys
+        cell.get_or_init(|| serde_json::from_str(content).unwrap_or_default())
+    }
+}
+
+impl Display for Locale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::En => write!(f, "en"),
+            Self::Fr => write!(f, "fr"),
+        }
+    }
+}
+
+impl FromStr for Locale {
+    type Err = GalionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "en" => Ok(Self::En),
+            "fr" => Ok(Self::Fr),
+            other => Err(format!("unknown language '{other}' - use en or fr").into()),
+        }
+    }
+}
+
+/// Set the locale of the interface, only the first call is used
+pub(crate) fn set_locale(locale: Locale) {
+    let _ = LOCALE.set(locale);
+}
+
+/// Translation of a key, in English if missing, the key itself if unknown
+pub(crate) fn tr(key: &'static str) -> &'static str {
+    let locale = LOCALE.get().copied().unwrap_or_default();
+    locale
+        .strings()
+        .get(key)
+        .or_else(|| Locale::En.strings().get(key))
+        .map_or(key, String::as_str)
+}
+
+/// Translation of a key with its `{placeholder}` replaced by values
+pub(crate) fn tr_with(key: &'static str, values: &[(&str, &str)]) -> String {
+    values
+        .iter()
+        .fold(tr(key).to_string(), |text, (placeholder, value)| {
+            text.replace(&format!("{{{placeholder}}}"), value)
+        })
+}
@@ -0,0 +1,106 @@
+//! Single line text input
+
+/// Text input state
+#[derive(Debug, Default, Clone)]
+pub(crate) struct TextInput {
+    /// Input value
+    pub(crate) value: String,
+    /// Position of cursor in the input, in chars
+    pub(crate) character_index: usize,
+}
+
+impl TextInput {
+    /// Create an input with the cursor at the end
+    pub fn new<S: Into<String>>(value: S) -> Self {
+        let value = value.into();
+        let character_index = value.chars().count();
+        Self {
+            value,
+            character_index,
+        }
+    }
+
+    /// Byte index of the cursor
+    fn byte_index(&self) -> usize {
+        self.value
+            .char_indices()
+            .map(|(i, _)| i)
+            .nth(self.character_index)
+            .unwrap_or(self.value.len())
+    }
+
+    /// Add a char at the cursor
+    pub fn enter_char(&mut self, new_char: char) {
+        let index = self.byte_index();
+        self.value.insert(index, new_char);
+        self.move_cursor_right();
+    }
+
+    /// Insert a pasted text at the cursor, without its control chars like the newlines
+    pub fn insert_str(&mut self, text: &str) {
+        let text = text.chars().filter(|c| !c.is_control()).collect::<String>();
+        let index = self.byte_index();
+        self.value.insert_str(index, &text);
+        self.character_index += text.chars().count();
+    }
+
+    /// Move the cursor to the right
+    pub fn move_cursor_right(&mut self) {
+        self.character_index = self
+            .character_index
+            .saturating_add(1)
+            .min(self.value.chars().count());
+    }
+
+    /// Move the cursor to the left
+    pub fn move_cursor_left(&mut self) {
+        self.character_index = self.character_index.saturating_sub(1);
+    }
+
+    /// Delete the char before the cursor
+    pub fn delete_char(&mut self) {
+        if self.character_index == 0 {
+            return;
+        }
+        // remove works on bytes, so rebuild the string from the chars
+        let current_index = self.character_index;
+        self.value = self
+            .value
+            .chars()
+            .take(current_index - 1)
+            .chain(self.value.chars().skip(current_index))
+            .collect();
+        self.move_cursor_left();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_str() {
+        let mut input = TextInput::new("héllo");
+        input.character_index = 2;
+        input.insert_str("çà");
+        assert_eq!(input.value, "héçàllo");
+        assert_eq!(input.character_index, 4);
+        // the newlines of a pasted text are dropped
+        let mut input = TextInput::default();
+        input.insert_str("remote:\r\ndir\t\n");
+        assert_eq!(input.value, "remote:dir");
+        assert_eq!(input.character_index, input.value.chars().count());
+        input.enter_char('!');
+        assert_eq!(input.value, "remote:dir!");
+    }
+
+    #[test]
+    fn test_delete_char() {
+        let mut input = TextInput::new("ab€");
+        input.delete_char();
+        assert_eq!((input.value.as_str(), input.character_index), ("ab", 2));
+        input.character_index = 0;
+        input.delete_char();
+        assert_eq!(input.value, "ab");
+    }
+}
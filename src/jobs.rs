@@ -0,0 +1,2506 @@
+//! Rclone jobs, handled by the background thread
+
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::any::Any;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, sleep};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use time::{OffsetDateTime, macros::format_description};
+
+use crate::GalionError;
+use crate::app::GalionConfig;
+use crate::bench::Benchmark;
+use crate::bisync::BisyncRun;
+use crate::browser::{format_size, join_path};
+use crate::dryrun::DryRun;
+use crate::errors::ErrorClass;
+use crate::health::Health;
+use crate::history::{HistoryEntry, JobHistory};
+use crate::hooks::{HookAction, Hooks};
+use crate::librclone::Rclone;
+use crate::librclone::rclone::{RcCheck, RcJobStatus, RcStats};
+use crate::logs::RemoteLog;
+use crate::notify::{JobEvent, Notifier};
+use crate::options::SyncOptions;
+use crate::remote::{
+    Operation, OverlapPolicy, Priority, RclonePath, RemoteConfiguration, SpaceCheck, SyncRecord,
+    expand_path, expand_placeholders, normalize_path, parse_duration,
+};
+use crate::report::{BatchSummary, SummaryRow, report_date};
+use crate::stats::median_duration;
+use crate::system::{MeteredPolicy, is_metered, power_state};
+use crate::ui::UiEvent;
+use crate::watch::SourceWatcher;
+
+/// Maximum time to wait for the stopped jobs to end
+const STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Poll time of the running jobs
+const POLL_TIME: Duration = Duration::from_millis(500);
+
+/// Delay before retrying a job whose destination is unreachable
+const OFFLINE_RETRY: Duration = Duration::from_secs(30);
+
+/// Delay before retrying a sync failing with a retryable error
+const ERROR_RETRY: Duration = Duration::from_mins(1);
+
+/// Errors of an unreachable destination
+const OFFLINE_ERRORS: [&str; 6] = [
+    "no such host",
+    "dial tcp",
+    "connection refused",
+    "network is unreachable",
+    "i/o timeout",
+    "temporary failure in name resolution",
+];
+
+/// Time the size to transfer of a remote is reused
+const SIZE_CACHE_TIME: Duration = Duration::from_hours(1);
+
+/// Minimum delay between two checks of the state of the machine
+const SYSTEM_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Destinations probed at once by a health check
+const HEALTH_WORKERS: usize = 8;
+
+/// Maximum number of restarts of the background thread
+const MAX_RESTARTS: usize = 5;
+
+/// Delay before restarting the background thread
+const RESTART_DELAY: Duration = Duration::from_secs(1);
+
+/// Failed reads of the status of a job in a row before it is stopped and marked failed
+const STATUS_ERRORS: u32 = 5;
+
+/// Kind of a galion job
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd, Ord, Eq)]
+pub enum JobKind {
+    /// Sync the source to the destination
+    #[default]
+    Sync,
+    /// Delete the source file
+    DeleteFile,
+    /// Copy the source file to the destination file
+    CopyFile,
+    /// Copy the source directory to the destination directory
+    Copy,
+    /// Two way sync of the source and the destination
+    Bisync,
+    /// Copy of a remote, like a sync without the deletions
+    RemoteCopy,
+    /// Move of a remote, the source files are deleted once transferred
+    RemoteMove,
+}
+
+impl From<&Operation> for JobKind {
+    fn from(operation: &Operation) -> Self {
+        match operation {
+            Operation::Sync => Self::Sync,
+            Operation::Copy => Self::RemoteCopy,
+            Operation::Move => Self::RemoteMove,
+            Operation::Bisync => Self::Bisync,
+        }
+    }
+}
+
+/// [`SyncJob`] data
+#[derive(Debug, Clone, PartialEq, PartialOrd, Ord, Eq)]
+pub struct SyncJobData {
+    /// sync job id
+    pub(crate) job_id: u64,
+    /// sync job name
+    pub(crate) name: String,
+    /// sync job src
+    pub(crate) src: String,
+    /// sync job dest
+    pub(crate) dest: String,
+    /// kind of job
+    pub(crate) kind: JobKind,
+    /// backup dir of a sync, a dated subdirectory is used for each run
+    pub(crate) backup_dir: Option<String>,
+    /// rclone options of a sync
+    pub(crate) options: SyncOptions,
+    /// options of a bisync
+    pub(crate) bisync: BisyncRun,
+    /// priority in the queue
+    pub(crate) priority: Priority,
+    /// submission number, keeps the queued jobs apart and in order
+    pub(crate) submission: u64,
+    /// the job is stopped if it runs longer
+    pub(crate) max_duration: Option<Duration>,
+    /// check of the free space of the destination
+    pub(crate) space_check: SpaceCheck,
+    /// rclone filter file of a sync
+    pub(crate) filter_from: Option<String>,
+    /// rclone filter rules of a sync
+    pub(crate) filters: Vec<String>,
+    /// what to do with a sync launched while another sync of the remote is running
+    pub(crate) overlap: OverlapPolicy,
+    /// check the files after a successful sync
+    pub(crate) verify: bool,
+}
+
+impl SyncJobData {
+    /// Prefix of the rclone groups of the galion jobs
+    const GROUP_PREFIX: &str = "galion/";
+
+    /// Unique rclone group of a new job, the remote name can be found back with [`SyncJobData::name_from_group`]
+    fn new_group(&self) -> String {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default();
+        format!("{}{unique}/{}", Self::GROUP_PREFIX, self.name)
+    }
+
+    /// Job of a remote, `None` without a source and a destination
+    pub(crate) fn from_remote(remote: &RemoteConfiguration) -> Option<Self> {
+        Some(Self {
+            job_id: 0, // fake job id
+            name: remote.remote_name.clone(),
+            src: remote.remote_src.clone()?,
+            dest: remote.remote_dest.clone()?,
+            kind: JobKind::from(&remote.operation),
+            backup_dir: remote.backup_dir.clone(),
+            options: remote.options.clone(),
+            bisync: BisyncRun::default(),
+            priority: remote.priority,
+            submission: 0,
+            max_duration: remote
+                .max_duration
+                .as_deref()
+                .and_then(|d| parse_duration(d).ok()),
+            space_check: remote.space_check,
+            filter_from: remote.filter_from.clone(),
+            filters: remote.filters.clone(),
+            overlap: remote.overlap,
+            verify: remote.verify,
+        })
+    }
+
+    /// Is it a sync of a remote, not an operation of the explorer
+    pub(crate) fn is_sync(&self) -> bool {
+        matches!(
+            self.kind,
+            JobKind::Sync | JobKind::Bisync | JobKind::RemoteCopy | JobKind::RemoteMove
+        )
+    }
+
+    /// Label of the job in the jobs panel
+    pub(crate) fn label(&self) -> String {
+        match self.kind {
+            JobKind::Sync => format!("job {} ({})", self.name, self.job_id),
+            JobKind::Bisync => format!("bisync {} ({})", self.name, self.job_id),
+            JobKind::RemoteCopy => format!("copy {} ({})", self.name, self.job_id),
+            JobKind::RemoteMove => format!("move {} ({})", self.name, self.job_id),
+            JobKind::DeleteFile => format!("delete {} ({})", self.src, self.job_id),
+            JobKind::CopyFile | JobKind::Copy => {
+                format!("copy {} -> {} ({})", self.src, self.dest, self.job_id)
+            }
+        }
+    }
+
+    /// Remote name of a job launched by galion, from its rclone group
+    fn name_from_group(group: &str) -> Option<&str> {
+        group
+            .strip_prefix(Self::GROUP_PREFIX)
+            .and_then(|g| g.split_once('/'))
+            .map(|(_, name)| name)
+    }
+}
+
+/// How the jobs of a batch are launched
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExecutionPolicy {
+    /// all together, up to the limit of running jobs
+    #[default]
+    Parallel,
+    /// one after the other, in the order of the remotes
+    Sequential,
+}
+
+impl Display for ExecutionPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parallel => write!(f, "parallel"),
+            Self::Sequential => write!(f, "sequential"),
+        }
+    }
+}
+
+impl FromStr for ExecutionPolicy {
+    type Err = GalionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "parallel" => Ok(Self::Parallel),
+            "sequential" => Ok(Self::Sequential),
+            _ => Err(format!("unknown policy '{s}' - use parallel or sequential").into()),
+        }
+    }
+}
+
+/// rclone job type
+pub type JobsList = BTreeMap<SyncJobData, JobState>;
+
+/// Job statut
+#[derive(Debug)]
+pub enum ResultJob {
+    /// Exit
+    Exit,
+    /// A job could not be launched
+    Error(String),
+    /// The background thread crashed and was restarted
+    Crashed(String),
+    /// The summary of a batch was written
+    Report(PathBuf),
+    /// Non-fatal error, like a failed notification
+    Warning(String),
+    /// Health of the destination of a remote
+    Health(String, Health),
+    /// Benchmark of the destination of a remote
+    Benchmark(String, Result<Benchmark, String>),
+    /// Dry run of the sync of a remote
+    DryRun(String, Result<DryRun, String>),
+    /// Sync
+    Sync(JobsList),
+    /// Sync states of the remotes whose syncs finished, to save in the config
+    Recorded(Vec<SyncRecord>),
+}
+
+/// What to do with the running jobs when exiting
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShutdownPolicy {
+    /// Stop the running jobs
+    Cancel,
+    /// Wait for the running jobs to finish
+    Wait,
+}
+
+/// Job statut
+#[derive(Debug)]
+pub enum SyncJob {
+    /// Exit
+    Exit(ShutdownPolicy),
+    /// Sync
+    Sync(Box<SyncJobData>),
+    /// Jobs summarized together once they are all done
+    Batch(Vec<SyncJobData>, ExecutionPolicy),
+    /// Saved config
+    Config(Box<GalionConfig>),
+    /// Probe the destination of a remote, of all the remotes if `None`
+    Health(Option<String>),
+    /// Benchmark the destination of a remote, by remote name and destination
+    Benchmark(String, String),
+    /// Dry run of a sync
+    DryRun(Box<SyncJobData>),
+    /// Cancel a waiting job, by submission number
+    Cancel(u64),
+}
+
+impl Display for RcJobStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.error.is_empty() {
+            write!(f, "success: {}, duration: {}", self.success, self.duration)
+        } else {
+            write!(
+                f,
+                "success: {} ({}), duration: {}",
+                self.success, self.error, self.duration
+            )
+        }
+    }
+}
+
+/// Job state
+#[derive(Debug, PartialEq, Clone)]
+pub enum JobState {
+    /// Waiting for a free slot to be launched
+    Queued,
+    /// The destination is unreachable, queued again at the given time
+    Offline(Instant),
+    /// Failed with a retryable error, queued again at the given time
+    Retrying(Instant),
+    /// Sent
+    Sent,
+    /// Waiting to finish, with the transfer stats
+    Pending(RcJobStatus, RcStats),
+    /// Done, with the final transfer stats
+    Done(RcJobStatus, RcStats),
+    /// Failed on galion side (launch or status error)
+    Failed(String),
+    /// Stopped after running longer than its maximum duration, never retried
+    TimedOut(Duration),
+    /// Cancelled from the ui, stopped in rclone if it was running
+    Cancelled,
+}
+
+impl JobState {
+    /// Is this job waiting
+    pub(crate) fn is_waiting(&self) -> bool {
+        match self {
+            Self::Queued
+            | Self::Offline(_)
+            | Self::Retrying(_)
+            | Self::Sent
+            | Self::Pending(_, _) => true,
+            Self::Done(_, _) | Self::Failed(_) | Self::TimedOut(_) | Self::Cancelled => false,
+        }
+    }
+
+    /// Is this job launched and not finished
+    pub(crate) fn is_running(&self) -> bool {
+        matches!(self, Self::Sent | Self::Pending(_, _))
+    }
+
+    /// Is this job an error
+    pub(crate) fn success_color(&self) -> Color {
+        match self {
+            Self::Queued | Self::Cancelled => Color::DarkGray,
+            Self::Offline(_) | Self::Retrying(_) => Color::Yellow,
+            Self::Sent | Self::Pending(_, _) => Color::Blue,
+            Self::Done(s, _) if s.success => Color::Green,
+            Self::Done(_, _) | Self::Failed(_) | Self::TimedOut(_) => match self.error_class() {
+                Some(ErrorClass::Retryable) => Color::Magenta,
+                _ => Color::Red,
+            },
+        }
+    }
+
+    /// Class of the error of a failed job, `None` if it didn't fail
+    pub(crate) fn error_class(&self) -> Option<ErrorClass> {
+        match self {
+            Self::Done(job_status, _) if !job_status.success => {
+                Some(ErrorClass::classify(&job_status.error))
+            }
+            Self::Failed(error) => Some(ErrorClass::classify(error)),
+            // the next run would be stopped the same way
+            Self::TimedOut(_) => Some(ErrorClass::Fatal),
+            _ => None,
+        }
+    }
+
+    /// Error of a failed job, empty if it didn't fail
+    pub(crate) fn error(&self) -> String {
+        match self {
+            Self::Done(job_status, _) => job_status.error.clone(),
+            Self::Failed(error) => error.clone(),
+            Self::TimedOut(max_duration) => format!(
+                "stopped after running longer than its max duration of {}s",
+                max_duration.as_secs()
+            ),
+            Self::Queued
+            | Self::Offline(_)
+            | Self::Retrying(_)
+            | Self::Sent
+            | Self::Pending(_, _)
+            | Self::Cancelled => String::new(),
+        }
+    }
+
+    /// Create the history entry of a finished job
+    pub(crate) fn history_entry(&self, job_data: &SyncJobData) -> Option<HistoryEntry> {
+        let (job_status, stats) = match self {
+            Self::Queued
+            | Self::Offline(_)
+            | Self::Retrying(_)
+            | Self::Sent
+            | Self::Pending(_, _)
+            | Self::Cancelled => return None,
+            Self::Done(job_status, stats) => (job_status.clone(), stats.clone()),
+            Self::Failed(_) | Self::TimedOut(_) => (
+                RcJobStatus {
+                    error: self.error(),
+                    ..RcJobStatus::default()
+                },
+                RcStats::default(),
+            ),
+        };
+        Some(HistoryEntry {
+            job_id: job_data.job_id,
+            name: job_data.name.clone(),
+            src: job_data.src.clone(),
+            dest: job_data.dest.clone(),
+            success: job_status.success,
+            error: job_status.error,
+            start_time: job_status.start_time,
+            end_time: job_status.end_time,
+            duration: job_status.duration,
+            bytes: stats.bytes,
+            files: stats.transfers,
+        })
+    }
+}
+
+impl Display for JobState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JobState::Queued => write!(f, "queued"),
+            JobState::Offline(_) => write!(f, "waiting for connectivity"),
+            JobState::Retrying(_) => write!(f, "retrying after a transient error"),
+            JobState::Sent => write!(f, "sent"),
+            JobState::Pending(job_status, stats) if stats.total_bytes > 0 => {
+                #[allow(clippy::cast_precision_loss)] // only shown as a percent
+                let percent = stats.bytes as f64 * 100.0 / stats.total_bytes as f64;
+                write!(
+                    f,
+                    "waiting: {} / {} ({percent:.0}%",
+                    format_size(stats.bytes),
+                    format_size(stats.total_bytes),
+                )?;
+                if let Some(eta) = stats.eta {
+                    write!(f, ", eta {eta:.0}s")?;
+                }
+                write!(f, "), start_time: {}", job_status.start_time)
+            }
+            JobState::Pending(job_status, _) => {
+                write!(f, "waiting: start_time: {}", job_status.start_time)
+            }
+            JobState::Done(job_status, _) => write!(f, "done: {job_status}"),
+            JobState::Cancelled => write!(f, "cancelled"),
+            JobState::Failed(error) => write!(f, "failed: {error}"),
+            JobState::TimedOut(_) => write!(f, "failed: {}", self.error()),
+        }
+    }
+}
+
+/// Jobs launched together, with their last known state and transferred bytes
+struct Batch {
+    /// start date
+    started: String,
+    /// how the jobs are launched
+    policy: ExecutionPolicy,
+    /// jobs of the batch, by submission number, 0 if not queued yet
+    jobs: Vec<(SyncJobData, JobState)>,
+}
+
+impl Batch {
+    /// Summary of the finished batch
+    fn summary(&self) -> BatchSummary {
+        let rows = self
+            .jobs
+            .iter()
+            .map(|(job_data, job_state)| {
+                let (success, bytes, duration, error) = match job_state {
+                    JobState::Done(job_status, stats) => (
+                        job_status.success,
+                        stats.bytes,
+                        job_status.duration,
+                        job_status.error.clone(),
+                    ),
+                    JobState::Failed(_) | JobState::TimedOut(_) => {
+                        (false, 0, 0.0, job_state.error())
+                    }
+                    JobState::Cancelled => (false, 0, 0.0, "cancelled".to_string()),
+                    JobState::Pending(_, stats) => (false, stats.bytes, 0.0, String::new()),
+                    JobState::Queued
+                    | JobState::Offline(_)
+                    | JobState::Retrying(_)
+                    | JobState::Sent => (false, 0, 0.0, String::new()),
+                };
+                SummaryRow {
+                    name: job_data.name.clone(),
+                    success,
+                    bytes,
+                    duration,
+                    error,
+                }
+            })
+            .collect();
+        BatchSummary {
+            started: self.started.clone(),
+            rows,
+        }
+    }
+}
+
+/// Dry run running in rclone: a sync without changes, then the check of its paths
+struct PendingDryRun {
+    /// sync of the dry run, with its expanded paths and the job id of the dry run sync
+    sync_data: SyncJobData,
+    /// rclone group of the dry run sync
+    group: String,
+    /// rclone filter of the sync, the check sees the same files
+    filter: Option<Value>,
+    /// stats of the finished dry run sync and the job id of the check
+    check: Option<(RcStats, u64)>,
+}
+
+impl PendingDryRun {
+    /// Rclone job of the current step
+    fn job_id(&self) -> u64 {
+        self.check
+            .as_ref()
+            .map_or(self.sync_data.job_id, |(_, check_id)| *check_id)
+    }
+}
+
+/// Rclone `_filter` of a job with filter rules or a filter file
+/// # Errors
+/// Fails if the filter file cannot be read, instead of syncing without the filters
+fn filter_rules(sync_data: &SyncJobData) -> Result<Option<Value>, GalionError> {
+    let mut filter = json!({});
+    if !sync_data.filters.is_empty() {
+        filter["FilterRule"] = json!(sync_data.filters);
+    }
+    if let Some(filter_from) = &sync_data.filter_from {
+        let path = expand_path(filter_from);
+        if let Err(e) = std::fs::File::open(&path) {
+            return Err(format!("cannot read the filter file {path}: {e}").into());
+        }
+        filter["FilterFrom"] = json!([path]);
+    }
+    Ok(Some(filter).filter(|filter| filter != &json!({})))
+}
+
+/// State of the background thread, kept when the thread is restarted
+struct JobManager<'a> {
+    /// rclone instance
+    rclone: &'a Rclone,
+    /// galion config, updated by the ui when it is saved
+    config: GalionConfig,
+    /// job history store
+    history: JobHistory,
+    /// tracked jobs
+    tracking_jobs: JobsList,
+    /// exit once the running jobs are done
+    draining: bool,
+    /// running batch
+    batch: Option<Batch>,
+    /// errors to show in the ui, like the failed notifications
+    warnings: Vec<String>,
+    /// failed reads of the status of the running jobs in a row, by job id
+    status_errors: BTreeMap<u64, u32>,
+    /// thread sending the job events
+    notifier: Notifier,
+    /// last submission number
+    submissions: u64,
+    /// deadline of the running jobs with a maximum duration, by job id
+    deadlines: BTreeMap<u64, Instant>,
+    /// is the connection metered, with the time of the last check
+    metered: Option<(bool, Instant)>,
+    /// are the syncs paused by the battery, with the time of the last check
+    on_battery: Option<(bool, Instant)>,
+    /// is the machine busy, with the time of the last check
+    busy: Option<(bool, Instant)>,
+    /// bandwidth limit set in rclone
+    bwlimit: String,
+    /// last periodic health check
+    last_health_check: Option<Instant>,
+    /// size to transfer of the remotes, with the time it was computed
+    transfer_sizes: BTreeMap<String, (i64, Instant)>,
+    /// size to transfer of the running syncs, by job id
+    expected_sizes: BTreeMap<u64, u64>,
+    /// check job of the verified syncs, by sync job id
+    verifications: BTreeMap<u64, u64>,
+    /// dry runs running in rclone
+    dry_runs: Vec<PendingDryRun>,
+    /// results of the finished dry runs, by remote name, not sent to the ui yet
+    dry_run_results: Vec<(String, Result<DryRun, String>)>,
+    /// time after which a running sync is unusually long, by job id
+    duration_alerts: BTreeMap<u64, Instant>,
+    /// consecutive retries of the syncs failing with a retryable error, by remote name
+    retries: BTreeMap<String, u32>,
+    /// sync states of the remotes whose syncs finished, not sent to the ui yet
+    records: Vec<SyncRecord>,
+    /// hooks script
+    hooks: Option<Hooks>,
+    /// actions asked by the hooks, run at the next dispatch
+    hook_actions: Vec<HookAction>,
+    /// bandwidth limit set by the hooks, when neither the metered connection nor the load limit it
+    hook_bwlimit: Option<String>,
+    /// planned scheduled run of the remotes, with its cron expression, by remote name
+    next_runs: BTreeMap<String, (String, OffsetDateTime)>,
+    /// watcher of the sources of the remotes with `watch`
+    watcher: SourceWatcher,
+}
+
+impl<'a> JobManager<'a> {
+    /// Create the job manager, with the jobs rclone is already tracking
+    fn new(rclone: &'a Rclone, config: &'a GalionConfig) -> Self {
+        let mut manager = Self {
+            rclone,
+            config: config.clone(),
+            history: config.history(),
+            tracking_jobs: JobsList::new(),
+            draining: false,
+            batch: None,
+            warnings: Vec::new(),
+            status_errors: BTreeMap::new(),
+            notifier: Notifier::new(),
+            submissions: 0,
+            deadlines: BTreeMap::new(),
+            metered: None,
+            on_battery: None,
+            busy: None,
+            bwlimit: "off".to_string(),
+            last_health_check: None,
+            transfer_sizes: BTreeMap::new(),
+            expected_sizes: BTreeMap::new(),
+            verifications: BTreeMap::new(),
+            dry_runs: Vec::new(),
+            dry_run_results: Vec::new(),
+            duration_alerts: BTreeMap::new(),
+            retries: BTreeMap::new(),
+            records: Vec::new(),
+            hooks: None,
+            hook_actions: Vec::new(),
+            hook_bwlimit: None,
+            next_runs: BTreeMap::new(),
+            watcher: SourceWatcher::new(),
+        };
+        manager.load_hooks();
+        manager.update_watches();
+        manager.attach_jobs();
+        manager
+    }
+
+    /// Rebuild the jobs launched by galion that rclone is still tracking, when attaching to it
+    ///
+    /// librclone runs in galion, it has no job of a previous run to attach
+    fn attach_jobs(&mut self) {
+        if self.rclone.backend_name().is_none() {
+            return;
+        }
+        let job_list = match self.rclone.job_list() {
+            Ok(job_list) => job_list,
+            Err(e) => {
+                self.warnings.push(format!(
+                    "Cannot list the rclone jobs, the running syncs are not followed: {e}"
+                ));
+                return;
+            }
+        };
+        for job_id in job_list.job_ids {
+            let Ok(job_status) = self.rclone.job_status(job_id) else {
+                continue;
+            };
+            let Some(name) = SyncJobData::name_from_group(&job_status.group) else {
+                // not a galion job
+                continue;
+            };
+            // the job runs with the settings of its remote
+            let Some(sync_data) = self
+                .config
+                .remotes()
+                .iter()
+                .find(|r| r.remote_name == name)
+                .and_then(SyncJobData::from_remote)
+            else {
+                self.warnings.push(format!(
+                    "The rclone job {job_id} of {name} is not followed, the remote is not in the config"
+                ));
+                continue;
+            };
+            let job_data = SyncJobData {
+                job_id,
+                submission: self.next_submission(),
+                ..sync_data
+            };
+            let job_state = if job_status.finished {
+                JobState::Done(job_status, RcStats::default())
+            } else {
+                if let Some(max_duration) = job_data.max_duration {
+                    let elapsed =
+                        Duration::try_from_secs_f64(job_status.duration).unwrap_or_default();
+                    self.deadlines.insert(
+                        job_id,
+                        Instant::now() + max_duration.saturating_sub(elapsed),
+                    );
+                }
+                JobState::Pending(job_status, RcStats::default())
+            };
+            self.tracking_jobs.insert(job_data, job_state);
+        }
+    }
+
+    /// Load the hooks script of the config, if it changed
+    fn load_hooks(&mut self) {
+        if self.hooks.as_ref().map(|hooks| &hooks.path) == self.config.hooks_script.as_ref() {
+            return;
+        }
+        self.hooks = match self.config.hooks_script.as_deref().map(Hooks::load) {
+            Some(Ok(hooks)) => Some(hooks),
+            Some(Err(e)) => {
+                self.warnings.push(e.to_string());
+                None
+            }
+            None => None,
+        };
+    }
+
+    /// Replace the config by the one saved by the ui, the hooks see the added remotes
+    fn update_config(&mut self, config: GalionConfig) {
+        let added = config
+            .remotes()
+            .iter()
+            .filter(|remote| {
+                !self
+                    .config
+                    .remotes()
+                    .iter()
+                    .any(|old| old.remote_name == remote.remote_name)
+            })
+            .map(|remote| remote.remote_name.clone())
+            .collect::<Vec<String>>();
+        self.config = config;
+        self.load_hooks();
+        self.update_watches();
+        for name in added {
+            self.run_hook("on_remote_added", (name,));
+        }
+    }
+
+    /// Call a hook, its actions are run at the next dispatch
+    fn run_hook(&mut self, event: &str, args: impl rhai::FuncArgs) {
+        let Some(hooks) = &self.hooks else {
+            return;
+        };
+        match hooks.call(event, args) {
+            Ok(actions) => self.hook_actions.extend(actions),
+            Err(e) => self.warnings.push(e.to_string()),
+        }
+    }
+
+    /// Run the actions asked by the hooks
+    fn run_hook_actions(&mut self) {
+        for action in std::mem::take(&mut self.hook_actions) {
+            match action {
+                HookAction::Sync(name) if self.is_quarantined(&name) => self.warnings.push(
+                    format!("Hook: the remote {name} is quarantined, not synced"),
+                ),
+                HookAction::Sync(name) => {
+                    match self
+                        .config
+                        .remotes()
+                        .iter()
+                        .find(|remote| remote.remote_name == name)
+                        .and_then(SyncJobData::from_remote)
+                    {
+                        Some(sync_data) => {
+                            self.enqueue(sync_data);
+                        }
+                        None => self
+                            .warnings
+                            .push(format!("Hook: no remote {name} to sync")),
+                    }
+                }
+                HookAction::Notify(message) => self.warnings.push(message),
+                HookAction::Bwlimit(rate) => self.hook_bwlimit = Some(rate),
+            }
+        }
+    }
+
+    /// Next submission number
+    fn next_submission(&mut self) -> u64 {
+        self.submissions += 1;
+        self.submissions
+    }
+
+    /// Add a job to the queue, it is launched by [`JobManager::dispatch`]
+    fn enqueue(&mut self, mut sync_data: SyncJobData) -> SyncJobData {
+        // a job already waiting in the queue with the same paths is not queued twice
+        if let Some(queued) = self.tracking_jobs.iter().find_map(|(job_data, job_state)| {
+            (matches!(
+                job_state,
+                JobState::Queued | JobState::Offline(_) | JobState::Retrying(_)
+            ) && job_data.kind == sync_data.kind
+                && job_data.src == sync_data.src
+                && job_data.dest == sync_data.dest)
+                .then(|| job_data.clone())
+        }) {
+            return queued;
+        }
+        sync_data.job_id = 0;
+        sync_data.submission = self.next_submission();
+        if sync_data.overlap == OverlapPolicy::Refuse && self.is_overlapping(&sync_data, true) {
+            let job_state = JobState::Failed(format!(
+                "refused, a sync of {} is already running",
+                sync_data.name
+            ));
+            self.notify(&sync_data, &job_state);
+            self.tracking_jobs.insert(sync_data.clone(), job_state);
+            return sync_data;
+        }
+        self.tracking_jobs
+            .insert(sync_data.clone(), JobState::Queued);
+        sync_data
+    }
+
+    /// Is another sync of the same remote running, or also queued with `with_queued`
+    fn is_overlapping(&self, sync_data: &SyncJobData, with_queued: bool) -> bool {
+        sync_data.is_sync()
+            && self.tracking_jobs.iter().any(|(job_data, job_state)| {
+                job_data.submission != sync_data.submission
+                    && job_data.name == sync_data.name
+                    && job_data.is_sync()
+                    && (job_state.is_running() || (with_queued && job_state.is_waiting()))
+            })
+    }
+
+    /// Is the destination of a sync unreachable, a quick probe to not fail the syncs of an offline machine
+    fn is_offline(&self, sync_data: &SyncJobData) -> bool {
+        if !sync_data.is_sync() {
+            return false;
+        }
+        let dest = expand_placeholders(&expand_path(&sync_data.dest));
+        if !matches!(RclonePath::parse(&dest), RclonePath::Remote(_, _)) {
+            return false;
+        }
+        let (fs, remote_path) = RclonePath::split_fs(&dest);
+        match self.rclone.stat(fs, remote_path, false) {
+            Ok(_) => false,
+            Err(e) => {
+                let error = e.to_string().to_lowercase();
+                OFFLINE_ERRORS.iter().any(|pattern| error.contains(pattern))
+            }
+        }
+    }
+
+    /// Check the connection, at most every [`SYSTEM_CHECK_INTERVAL`], returns true if the syncs are paused
+    fn check_metered(&mut self) -> bool {
+        if self.config.metered_policy == MeteredPolicy::Ignore {
+            return false;
+        }
+        let was_metered = match self.metered {
+            Some((metered, checked)) if checked.elapsed() < SYSTEM_CHECK_INTERVAL => {
+                return metered && self.config.metered_policy == MeteredPolicy::Pause;
+            }
+            Some((metered, _)) => metered,
+            None => false,
+        };
+        let metered = is_metered().unwrap_or(false);
+        self.metered = Some((metered, Instant::now()));
+        if metered && !was_metered && self.config.metered_policy == MeteredPolicy::Pause {
+            self.warnings
+                .push("Metered connection: the syncs are paused".to_string());
+        }
+        metered && self.config.metered_policy == MeteredPolicy::Pause
+    }
+
+    /// Check the battery, at most every [`SYSTEM_CHECK_INTERVAL`], returns true if the syncs are paused
+    fn check_battery(&mut self) -> bool {
+        if !self.config.pause_on_battery {
+            return false;
+        }
+        if let Some((paused, checked)) = self.on_battery
+            && checked.elapsed() < SYSTEM_CHECK_INTERVAL
+        {
+            return paused;
+        }
+        let paused = power_state().is_some_and(|power| {
+            power.on_battery
+                && match (self.config.battery_threshold, power.charge) {
+                    (Some(threshold), Some(charge)) => charge < threshold,
+                    _ => true,
+                }
+        });
+        if paused && !self.on_battery.is_some_and(|(was_paused, _)| was_paused) {
+            self.warnings
+                .push("On battery: the syncs wait for AC power".to_string());
+        }
+        self.on_battery = Some((paused, Instant::now()));
+        paused
+    }
+
+    /// Is the machine busy, checked at most every [`SYSTEM_CHECK_INTERVAL`]
+    fn is_busy(&mut self) -> bool {
+        let Some(load_throttle) = &self.config.load_throttle else {
+            return false;
+        };
+        if let Some((busy, checked)) = self.busy
+            && checked.elapsed() < SYSTEM_CHECK_INTERVAL
+        {
+            return busy;
+        }
+        let busy = load_throttle.is_busy();
+        self.busy = Some((busy, Instant::now()));
+        busy
+    }
+
+    /// Set the bandwidth limit of the metered connection or of the busy machine, removed otherwise
+    fn apply_bwlimit(&mut self) {
+        let is_metered_limited = self.config.metered_policy == MeteredPolicy::Limit
+            && self.metered.is_some_and(|(metered, _)| metered);
+        let rate = if is_metered_limited {
+            self.config.metered_bwlimit.clone()
+        } else if let Some(bwlimit) = self
+            .config
+            .load_throttle
+            .as_ref()
+            .map(|load_throttle| load_throttle.bwlimit.clone())
+            && self.is_busy()
+        {
+            bwlimit
+        } else {
+            self.hook_bwlimit
+                .clone()
+                .unwrap_or_else(|| "off".to_string())
+        };
+        if rate == self.bwlimit {
+            return;
+        }
+        match self.rclone.bwlimit(&rate) {
+            Ok(()) => self.bwlimit = rate,
+            Err(e) => self
+                .warnings
+                .push(format!("Cannot set the bandwidth limit: {e}")),
+        }
+    }
+
+    /// Launch the queued jobs while there are free slots, the highest priority then the oldest first
+    fn dispatch(&mut self) {
+        let now = Instant::now();
+        self.run_hook_actions();
+        let syncs_paused = self.check_metered() || self.check_battery();
+        self.apply_bwlimit();
+        for job_state in self.tracking_jobs.values_mut() {
+            if matches!(job_state, JobState::Offline(retry) | JobState::Retrying(retry) if *retry <= now)
+            {
+                *job_state = JobState::Queued;
+            }
+        }
+        loop {
+            let running = self
+                .tracking_jobs
+                .values()
+                .filter(|job_state| job_state.is_running())
+                .count();
+            if running >= self.config.max_running_jobs.max(1) {
+                return;
+            }
+            let Some(next) = self
+                .tracking_jobs
+                .iter()
+                .filter(|(_, job_state)| **job_state == JobState::Queued)
+                .filter(|(job_data, _)| !syncs_paused || !job_data.is_sync())
+                .filter(|(job_data, _)| {
+                    job_data.overlap != OverlapPolicy::Queue
+                        || !self.is_overlapping(job_data, false)
+                })
+                .map(|(job_data, _)| job_data)
+                .min_by_key(|job_data| (Reverse(job_data.priority), job_data.submission))
+                .cloned()
+            else {
+                return;
+            };
+            if self.is_offline(&next) {
+                self.tracking_jobs
+                    .insert(next, JobState::Offline(now + OFFLINE_RETRY));
+                continue;
+            }
+            self.tracking_jobs.remove(&next);
+            if let Err(e) = self.launch(next.clone()) {
+                let job_state = JobState::Failed(format!("cannot launch the job: {e}"));
+                self.notify(&next, &job_state);
+                self.tracking_jobs.insert(next, job_state);
+            }
+        }
+    }
+
+    /// Is there a job, a batch or a dry run waiting to finish
+    fn is_jobs_waiting(&self) -> bool {
+        self.batch.is_some()
+            || !self.dry_runs.is_empty()
+            || self.tracking_jobs.values().any(JobState::is_waiting)
+    }
+
+    /// Publish the event of a job entering a state, and log it
+    fn notify(&mut self, job_data: &SyncJobData, job_state: &JobState) {
+        self.log(job_data, job_state);
+        if let Some(event) = JobEvent::new(job_data, job_state) {
+            self.notifier.send(&self.config, &event);
+            if event.is_remote_sync && event.event != "started" {
+                self.run_hook(
+                    "on_job_finished",
+                    (event.remote, event.event == "success", event.error),
+                );
+            }
+        }
+    }
+
+    /// Write the state of a job in the log file of its remote
+    fn log(&mut self, job_data: &SyncJobData, job_state: &JobState) {
+        let log_file = self
+            .config
+            .remotes()
+            .iter()
+            .find(|remote| remote.remote_name == job_data.name)
+            .and_then(|remote| remote.log_file.as_deref());
+        let Some(log) = RemoteLog::new(&job_data.name, log_file, self.config.log_dir.as_deref())
+        else {
+            return;
+        };
+        let mut line = format!("{} {}: {job_state}", report_date(), job_data.label());
+        if let JobState::Done(_, stats) = job_state {
+            let _ = write!(
+                line,
+                ", {} in {} files",
+                format_size(stats.bytes),
+                stats.transfers
+            );
+        }
+        if let Err(e) = log.append(&line) {
+            self.warnings
+                .push(format!("Cannot write the log of {}: {e}", job_data.name));
+        }
+    }
+
+    /// Update the status of the waiting jobs, a job whose status cannot be read
+    /// [`STATUS_ERRORS`] times in a row is stopped and marked failed, then the dry runs
+    fn update_jobs(&mut self) {
+        let mut finished = Vec::new();
+        for (job_sync_data, job_state) in &mut self.tracking_jobs {
+            if !job_state.is_running() {
+                // skip done and queued jobs
+                continue;
+            }
+            *job_state = match self.rclone.job_status(job_sync_data.job_id) {
+                Ok(job_status) => {
+                    self.status_errors.remove(&job_sync_data.job_id);
+                    // stats are only informative
+                    let mut stats = self
+                        .rclone
+                        .core_stats(&job_status.group)
+                        .unwrap_or_default();
+                    if let Some(expected) = self.expected_sizes.get(&job_sync_data.job_id) {
+                        // the precomputed size is used instead of the rolling estimate of rclone
+                        stats.total_bytes = (*expected).max(stats.bytes);
+                        #[allow(clippy::cast_precision_loss)] // sizes are far below 2^52
+                        let remaining = (stats.total_bytes - stats.bytes) as f64;
+                        stats.eta = Some(remaining / stats.speed).filter(|eta| eta.is_finite());
+                    }
+                    if job_status.finished
+                        && job_status.success
+                        && job_sync_data.verify
+                        && job_sync_data.kind == JobKind::Sync
+                    {
+                        Self::verify(
+                            self.rclone,
+                            &mut self.verifications,
+                            job_sync_data,
+                            job_status,
+                            stats,
+                        )
+                    } else if job_status.finished {
+                        JobState::Done(job_status, stats)
+                    } else {
+                        JobState::Pending(job_status, stats)
+                    }
+                }
+                Err(e) => {
+                    let errors = self.status_errors.entry(job_sync_data.job_id).or_default();
+                    *errors += 1;
+                    if *errors < STATUS_ERRORS {
+                        // still running in rclone, a failed job would let a new sync overlap it
+                        job_state.clone()
+                    } else {
+                        if self.rclone.job_stop(job_sync_data.job_id).is_err() {
+                            // rclone doesn't know the job anymore
+                        }
+                        JobState::Failed(format!(
+                            "cannot get the job status {STATUS_ERRORS} times in a row: {e}"
+                        ))
+                    }
+                }
+            };
+            if let (Some(max_duration), Some(deadline)) = (
+                job_sync_data.max_duration,
+                self.deadlines.get(&job_sync_data.job_id),
+            ) && job_state.is_waiting()
+                && Instant::now() > *deadline
+            {
+                if self.rclone.job_stop(job_sync_data.job_id).is_err() {
+                    // the job just finished
+                }
+                *job_state = JobState::TimedOut(max_duration);
+            }
+            if job_state.is_running()
+                && self
+                    .duration_alerts
+                    .get(&job_sync_data.job_id)
+                    .is_some_and(|alert| Instant::now() > *alert)
+            {
+                self.duration_alerts.remove(&job_sync_data.job_id);
+                self.warnings.push(format!(
+                    "Job {} runs for more than {} times its median duration, throttled or hung?",
+                    job_sync_data.name, self.config.duration_alert_factor
+                ));
+            }
+            if !job_state.is_waiting() {
+                self.deadlines.remove(&job_sync_data.job_id);
+                self.duration_alerts.remove(&job_sync_data.job_id);
+                self.expected_sizes.remove(&job_sync_data.job_id);
+                // the next sync has less to transfer
+                self.transfer_sizes.remove(&job_sync_data.name);
+            }
+            if !job_state.is_waiting() {
+                self.status_errors.remove(&job_sync_data.job_id);
+                finished.push((job_sync_data.clone(), job_state.clone()));
+            }
+        }
+        for (job_data, job_state) in finished {
+            self.notify(&job_data, &job_state);
+            self.record_sync(&job_data, &job_state);
+            self.retry(&job_data, &job_state);
+            self.chain(&job_data, &job_state);
+        }
+        self.update_dry_runs();
+    }
+
+    /// Queue the syncs of the remotes chained after a successful sync
+    fn chain(&mut self, job_data: &SyncJobData, job_state: &JobState) {
+        if self.draining
+            || !job_data.is_sync()
+            || !matches!(job_state, JobState::Done(job_status, _) if job_status.success)
+        {
+            return;
+        }
+        let chained = self
+            .config
+            .chained_remotes(&job_data.name)
+            // a cycle written by hand in the config would never end
+            .filter(|remote| !remote.quarantined && !self.config.is_chain_cycle(remote, None))
+            .filter_map(SyncJobData::from_remote)
+            .collect::<Vec<SyncJobData>>();
+        for sync_data in chained {
+            self.enqueue(sync_data);
+        }
+    }
+
+    /// Count the finished sync of a remote, quarantining the remotes failing too often
+    fn record_sync(&mut self, job_data: &SyncJobData, job_state: &JobState) {
+        if !job_data.is_sync() || *job_state == JobState::Cancelled {
+            return;
+        }
+        let success = matches!(job_state, JobState::Done(job_status, _) if job_status.success);
+        let quarantine_after = self.config.quarantine_after;
+        if let Some(remote) = self
+            .config
+            .remote_configurations
+            .iter_mut()
+            .find(|remote| remote.remote_name == job_data.name)
+        {
+            self.records
+                .push(remote.record_sync(success, quarantine_after));
+        }
+    }
+
+    /// Is a remote quarantined after too many failed syncs
+    fn is_quarantined(&self, name: &str) -> bool {
+        self.config
+            .remotes()
+            .iter()
+            .any(|remote| remote.remote_name == name && remote.quarantined)
+    }
+
+    /// Cancel a waiting job, a running one is stopped in rclone
+    fn cancel(&mut self, submission: u64) {
+        let Some((job_data, job_state)) = self
+            .tracking_jobs
+            .iter_mut()
+            .find(|(job_data, _)| job_data.submission == submission)
+        else {
+            return;
+        };
+        if !job_state.is_waiting() {
+            return;
+        }
+        if job_state.is_running()
+            && let Err(e) = self.rclone.job_stop(job_data.job_id)
+        {
+            self.warnings
+                .push(format!("Cannot stop the job {}: {e}", job_data.name));
+            return;
+        }
+        *job_state = JobState::Cancelled;
+        self.deadlines.remove(&job_data.job_id);
+        self.status_errors.remove(&job_data.job_id);
+        self.duration_alerts.remove(&job_data.job_id);
+        self.expected_sizes.remove(&job_data.job_id);
+        self.verifications.remove(&job_data.job_id);
+        let job_data = job_data.clone();
+        self.log(&job_data, &JobState::Cancelled);
+    }
+
+    /// Queue again a sync failing with a retryable error, up to `sync_retries` times in a row,
+    /// unless the remote is quarantined
+    fn retry(&mut self, job_data: &SyncJobData, job_state: &JobState) {
+        if self.draining || !job_data.is_sync() {
+            return;
+        }
+        if self.is_quarantined(&job_data.name) {
+            self.retries.remove(&job_data.name);
+            return;
+        }
+        let retries = self.retries.entry(job_data.name.clone()).or_default();
+        if job_state.error_class() != Some(ErrorClass::Retryable)
+            || *retries >= self.config.sync_retries
+        {
+            self.retries.remove(&job_data.name);
+            return;
+        }
+        *retries += 1;
+        let mut retry = job_data.clone();
+        retry.job_id = 0;
+        retry.submission = self.next_submission();
+        self.tracking_jobs
+            .insert(retry, JobState::Retrying(Instant::now() + ERROR_RETRY));
+    }
+
+    /// State of a successful sync whose files are checked, pending until the check is done
+    fn verify(
+        rclone: &Rclone,
+        verifications: &mut BTreeMap<u64, u64>,
+        sync_data: &SyncJobData,
+        mut job_status: RcJobStatus,
+        stats: RcStats,
+    ) -> JobState {
+        let check_id = if let Some(check_id) = verifications.get(&sync_data.job_id) {
+            *check_id
+        } else {
+            let check_job = filter_rules(sync_data).and_then(|filter| {
+                rclone.check_job(
+                    &sync_data.src,
+                    &sync_data.dest,
+                    Some(&job_status.group),
+                    filter.as_ref(),
+                )
+            });
+            job_status.success = false;
+            match check_job.map(|job| job.job_id) {
+                Ok(Some(check_id)) => {
+                    verifications.insert(sync_data.job_id, check_id);
+                    return JobState::Pending(job_status, stats);
+                }
+                Ok(None) => job_status.error = "no job id for the verification".to_string(),
+                Err(e) => job_status.error = format!("cannot verify the sync: {e}"),
+            }
+            return JobState::Done(job_status, stats);
+        };
+        let check_status = match rclone.job_status(check_id) {
+            Ok(check_status) if !check_status.finished => {
+                return JobState::Pending(job_status, stats);
+            }
+            Ok(check_status) => check_status,
+            Err(e) => RcJobStatus {
+                error: format!("cannot get the status: {e}"),
+                ..RcJobStatus::default()
+            },
+        };
+        verifications.remove(&sync_data.job_id);
+        // the lines of the identical files start with `=`
+        let mismatches = check_status
+            .raw
+            .get("output")
+            .and_then(|output| serde_json::from_value::<RcCheck>(output.clone()).ok())
+            .map(|check| {
+                check
+                    .combined
+                    .into_iter()
+                    .filter(|line| !line.starts_with('='))
+                    .collect::<Vec<String>>()
+            })
+            .unwrap_or_default();
+        if !check_status.success || !mismatches.is_empty() {
+            job_status.success = false;
+            job_status.error = if check_status.error.is_empty() {
+                format!("verification failed: {} mismatches", mismatches.len())
+            } else {
+                format!("verification failed: {}", check_status.error)
+            };
+        }
+        job_status.raw["verification"] = json!(mismatches);
+        JobState::Done(job_status, stats)
+    }
+
+    /// Move the oldest finished jobs to the history when there are too many jobs
+    fn spill_finished_jobs(&mut self) {
+        let max_jobs = self.config.max_jobs_in_memory;
+        let excess = self.tracking_jobs.len().saturating_sub(max_jobs);
+        if excess == 0 {
+            return;
+        }
+        let to_spill = self
+            .tracking_jobs
+            .iter()
+            .filter_map(|(job_data, job_state)| {
+                job_state
+                    .history_entry(job_data)
+                    .map(|entry| (job_data.clone(), entry))
+            })
+            .take(excess)
+            .collect::<Vec<(SyncJobData, HistoryEntry)>>();
+        let entries = to_spill
+            .iter()
+            .map(|(_, entry)| entry.clone())
+            .collect::<Vec<HistoryEntry>>();
+        // keep the jobs in memory if the history cannot be written
+        if self.history.append(&entries).is_ok() {
+            for (job_data, _) in to_spill {
+                self.tracking_jobs.remove(&job_data);
+            }
+        }
+    }
+
+    /// Move all the finished jobs to the history, when the thread exits
+    fn save_history(&mut self) {
+        let (finished, entries): (Vec<SyncJobData>, Vec<HistoryEntry>) = self
+            .tracking_jobs
+            .iter()
+            .filter_map(|(job_data, job_state)| {
+                Some((job_data.clone(), job_state.history_entry(job_data)?))
+            })
+            .unzip();
+        if self.history.append(&entries).is_ok() {
+            for job_data in finished {
+                self.tracking_jobs.remove(&job_data);
+            }
+        }
+    }
+
+    /// Stop the running jobs, and wait a bit for rclone to end them before finalizing
+    fn stop_jobs(&self) {
+        let running_ids = self
+            .tracking_jobs
+            .iter()
+            .filter(|(_, job_state)| job_state.is_running())
+            .map(|(job_data, _)| job_data.job_id)
+            .chain(self.verifications.values().copied())
+            .chain(self.dry_runs.iter().map(PendingDryRun::job_id))
+            .collect::<Vec<u64>>();
+        for job_id in &running_ids {
+            if self.rclone.job_stop(*job_id).is_err() {
+                // the job already finished
+            }
+        }
+        let deadline = Instant::now() + STOP_TIMEOUT;
+        while Instant::now() < deadline
+            && running_ids.iter().any(|job_id| {
+                self.rclone
+                    .job_status(*job_id)
+                    .is_ok_and(|job_status| !job_status.finished)
+            })
+        {
+            sleep(Duration::from_millis(100));
+        }
+    }
+
+    /// Check a job before launching it, to report a specific error instead of a rclone one
+    fn preflight(&self, sync_data: &SyncJobData) -> Result<(), GalionError> {
+        let remotes = self.rclone.list_remotes()?;
+        if let RclonePath::Remote(name, _) = RclonePath::parse(&sync_data.dest)
+            && !remotes.iter().any(|r| r == name)
+        {
+            return Err(GalionError::new(format!(
+                "the destination remote '{name}' doesn't exist in the rclone config"
+            )));
+        }
+        match RclonePath::parse(&sync_data.src) {
+            RclonePath::Local(path) => {
+                if !Path::new(path).exists() {
+                    return Err(GalionError::new(format!(
+                        "the source path '{path}' doesn't exist"
+                    )));
+                }
+            }
+            RclonePath::Remote(name, _) => {
+                if !remotes.iter().any(|r| r == name) {
+                    return Err(GalionError::new(format!(
+                        "the source remote '{name}' doesn't exist in the rclone config"
+                    )));
+                }
+                let (fs, remote_path) = RclonePath::split_fs(&sync_data.src);
+                if self.rclone.stat(fs, remote_path, false)?.is_none() {
+                    return Err(GalionError::new(format!(
+                        "the source '{}' doesn't exist",
+                        sync_data.src
+                    )));
+                }
+            }
+            RclonePath::Backend(_) => {}
+        }
+        Ok(())
+    }
+
+    /// Duration after which a sync is unusually long, from the durations of the previous syncs
+    fn expected_duration(&self, sync_data: &SyncJobData) -> Option<Duration> {
+        let factor = self.config.duration_alert_factor;
+        if !sync_data.is_sync() || factor <= 0.0 {
+            return None;
+        }
+        let mut entries = self.history.load().unwrap_or_default();
+        entries.extend(
+            self.tracking_jobs
+                .iter()
+                .filter_map(|(job_data, job_state)| job_state.history_entry(job_data)),
+        );
+        let median = median_duration(&entries, &sync_data.name)?;
+        Duration::try_from_secs_f64(median * factor).ok()
+    }
+
+    /// Size to transfer by a sync, cached per remote for [`SIZE_CACHE_TIME`]
+    /// # Errors
+    /// Fails if the size of the source cannot be computed
+    fn transfer_size(&mut self, sync_data: &SyncJobData) -> Result<i64, GalionError> {
+        if let Some((size, computed)) = self.transfer_sizes.get(&sync_data.name)
+            && computed.elapsed() < SIZE_CACHE_TIME
+        {
+            return Ok(*size);
+        }
+        let src_bytes = self.rclone.size(&sync_data.src)?.bytes;
+        // the files already on the destination are not transferred again
+        let dest_bytes = self
+            .rclone
+            .size(&sync_data.dest)
+            .map(|size| size.bytes)
+            .unwrap_or_default();
+        let needed = src_bytes.saturating_sub(dest_bytes).max(0);
+        self.transfer_sizes
+            .insert(sync_data.name.clone(), (needed, Instant::now()));
+        Ok(needed)
+    }
+
+    /// Compare the size to transfer with the free space of the destination
+    /// # Errors
+    /// Fails if the destination would fill up and the check refuses it
+    fn check_free_space(&mut self, sync_data: &SyncJobData) -> Result<(), GalionError> {
+        if sync_data.space_check == SpaceCheck::Off {
+            return Ok(());
+        }
+        // without quota there is nothing to compare
+        let Some(free) = self
+            .rclone
+            .about(&sync_data.dest)
+            .ok()
+            .and_then(|about| about.free)
+        else {
+            return Ok(());
+        };
+        let needed = self.transfer_size(sync_data)?;
+        if needed <= free {
+            return Ok(());
+        }
+        let to_size = |bytes: i64| format_size(u64::try_from(bytes).unwrap_or_default());
+        let msg = format!(
+            "the destination '{}' would fill up: {} to transfer, {} free",
+            sync_data.dest,
+            to_size(needed),
+            to_size(free)
+        );
+        if sync_data.space_check == SpaceCheck::Refuse {
+            return Err(msg.into());
+        }
+        self.warnings.push(format!("Job {}: {msg}", sync_data.name));
+        Ok(())
+    }
+
+    /// Launch a job, a job that fails to launch is tracked as failed
+    /// # Errors
+    /// Fails if a path is invalid or if the preflight checks fail
+    fn launch(&mut self, mut sync_data: SyncJobData) -> Result<(), GalionError> {
+        let group = sync_data.new_group();
+        if sync_data.is_sync() {
+            sync_data.src = normalize_path(&expand_path(&sync_data.src))?;
+            sync_data.dest = normalize_path(&expand_placeholders(&expand_path(&sync_data.dest)))?;
+        }
+        let mut expected_size = None;
+        let res = match sync_data.kind {
+            JobKind::Sync | JobKind::RemoteCopy | JobKind::RemoteMove => {
+                self.preflight(&sync_data)?;
+                self.check_free_space(&sync_data)?;
+                if self.config.precompute_size {
+                    // only informative, the sync runs without it
+                    expected_size = self
+                        .transfer_size(&sync_data)
+                        .ok()
+                        .and_then(|size| u64::try_from(size).ok());
+                }
+                let params = self.sync_params(&sync_data)?;
+                let (src, dest) = (&sync_data.src, &sync_data.dest);
+                match sync_data.kind {
+                    JobKind::RemoteCopy => self.rclone.copy(src, dest, true, Some(&group), &params),
+                    JobKind::RemoteMove => {
+                        self.rclone
+                            .move_files(src, dest, true, Some(&group), &params)
+                    }
+                    _ => self.rclone.sync(src, dest, true, Some(&group), &params),
+                }
+            }
+            JobKind::Bisync => {
+                self.preflight(&sync_data)?;
+                let mut params = sync_data.bisync.params();
+                if let Some(filter) = filter_rules(&sync_data)? {
+                    params["_filter"] = filter;
+                }
+                let options = sync_data.options.with_defaults(&self.config.defaults);
+                if options.create_empty_src_dirs == Some(true) {
+                    params["createEmptySrcDirs"] = json!(true);
+                }
+                self.rclone
+                    .bisync(&sync_data.src, &sync_data.dest, true, Some(&group), &params)
+            }
+            JobKind::Copy => self.rclone.copy(
+                &sync_data.src,
+                &sync_data.dest,
+                true,
+                Some(&group),
+                &json!({}),
+            ),
+            JobKind::DeleteFile => {
+                let (fs, remote_path) = RclonePath::split_fs(&sync_data.src);
+                self.rclone.delete_file(fs, remote_path, true, Some(&group))
+            }
+            JobKind::CopyFile => self.rclone.copy_file(
+                RclonePath::split_fs(&sync_data.src),
+                RclonePath::split_fs(&sync_data.dest),
+                true,
+                Some(&group),
+            ),
+        };
+        let job_state = match res {
+            Ok(job) => match job.job_id {
+                Some(job_id) => {
+                    sync_data.job_id = job_id;
+                    if let Some(max_duration) = sync_data.max_duration {
+                        self.deadlines.insert(job_id, Instant::now() + max_duration);
+                    }
+                    if let Some(size) = expected_size {
+                        self.expected_sizes.insert(job_id, size);
+                    }
+                    if let Some(expected) = self.expected_duration(&sync_data) {
+                        self.duration_alerts
+                            .insert(job_id, Instant::now() + expected);
+                    }
+                    self.notify(&sync_data, &JobState::Sent);
+                    self.tracking_jobs.insert(sync_data, JobState::Sent);
+                    return Ok(());
+                }
+                None => JobState::Failed("no job id in the rclone response".to_string()),
+            },
+            Err(e) => JobState::Failed(e.to_string()),
+        };
+        self.notify(&sync_data, &job_state);
+        self.tracking_jobs.insert(sync_data, job_state);
+        Ok(())
+    }
+
+    /// Rclone parameters of a sync, with its options, its filters and its dated backup dir
+    /// # Errors
+    /// Fails if the filter file cannot be read
+    fn sync_params(&mut self, sync_data: &SyncJobData) -> Result<Value, GalionError> {
+        let options = sync_data.options.with_defaults(&self.config.defaults);
+        let mut config = options.to_config().unwrap_or_default();
+        if self.is_busy() {
+            // fewer parallel transfers and checks on a busy machine
+            config.insert("Transfers".to_string(), json!(1));
+            config.insert("Checkers".to_string(), json!(2));
+        }
+        if let Some(backup_dir) = &sync_data.backup_dir {
+            let dated_dir = join_path(backup_dir, &backup_dir_version());
+            config.insert("BackupDir".to_string(), json!(dated_dir));
+        }
+        let mut params = json!({});
+        if !config.is_empty() {
+            params["_config"] = Value::Object(config);
+        }
+        if let Some(filter) = filter_rules(sync_data)? {
+            params["_filter"] = filter;
+        }
+        if options.create_empty_src_dirs == Some(true) {
+            params["createEmptySrcDirs"] = json!(true);
+        }
+        Ok(params)
+    }
+
+    /// Launch a sync without changing anything, the files it would copy and delete are listed
+    /// by [`JobManager::update_dry_runs`] once it is done
+    /// # Errors
+    /// Fails if it is not a sync or if the dry run cannot be launched
+    fn dry_run(&mut self, mut sync_data: SyncJobData) -> Result<(), GalionError> {
+        if sync_data.kind != JobKind::Sync {
+            return Err("only the syncs have a dry run".into());
+        }
+        sync_data.src = normalize_path(&expand_path(&sync_data.src))?;
+        sync_data.dest = normalize_path(&expand_placeholders(&expand_path(&sync_data.dest)))?;
+        self.preflight(&sync_data)?;
+        let mut params = self.sync_params(&sync_data)?;
+        params["_config"]["DryRun"] = json!(true);
+        let group = sync_data.new_group();
+        let job = self
+            .rclone
+            .sync(&sync_data.src, &sync_data.dest, true, Some(&group), &params)?;
+        sync_data.job_id = job.job_id.ok_or("no job id in the rclone response")?;
+        self.dry_runs.push(PendingDryRun {
+            sync_data,
+            group,
+            filter: params.get("_filter").cloned(),
+            check: None,
+        });
+        Ok(())
+    }
+
+    /// Follow the running dry runs: a finished dry run sync is followed by the check of its paths,
+    /// the files of a finished check are the result of the dry run
+    fn update_dry_runs(&mut self) {
+        for dry_run in std::mem::take(&mut self.dry_runs) {
+            let job_id = dry_run.job_id();
+            let name = dry_run.sync_data.name.clone();
+            let job_status = match self.rclone.job_status(job_id) {
+                Ok(job_status) => {
+                    self.status_errors.remove(&job_id);
+                    job_status
+                }
+                Err(e) => {
+                    let errors = self.status_errors.entry(job_id).or_default();
+                    *errors += 1;
+                    if *errors < STATUS_ERRORS {
+                        self.dry_runs.push(dry_run);
+                    } else {
+                        self.status_errors.remove(&job_id);
+                        if self.rclone.job_stop(job_id).is_err() {
+                            // rclone doesn't know the job anymore
+                        }
+                        self.dry_run_results.push((
+                            name,
+                            Err(format!(
+                                "cannot get the job status {STATUS_ERRORS} times in a row: {e}"
+                            )),
+                        ));
+                    }
+                    continue;
+                }
+            };
+            if !job_status.finished {
+                self.dry_runs.push(dry_run);
+                continue;
+            }
+            let PendingDryRun {
+                sync_data,
+                group,
+                filter,
+                check,
+            } = dry_run;
+            let result = match check {
+                None if !job_status.success => Err(job_status.error),
+                None => {
+                    let stats = self.rclone.core_stats(&group).unwrap_or_default();
+                    let check_job = self.rclone.check_job(
+                        &sync_data.src,
+                        &sync_data.dest,
+                        None,
+                        filter.as_ref(),
+                    );
+                    match check_job.map(|job| job.job_id) {
+                        Ok(Some(check_id)) => {
+                            self.dry_runs.push(PendingDryRun {
+                                sync_data,
+                                group,
+                                filter,
+                                check: Some((stats, check_id)),
+                            });
+                            continue;
+                        }
+                        Ok(None) => Err("no job id for the check".to_string()),
+                        Err(e) => Err(format!("cannot check the paths: {e}")),
+                    }
+                }
+                // a check finding differences fails, its output still lists the files
+                Some((stats, _)) => match job_status
+                    .raw
+                    .get("output")
+                    .and_then(|output| serde_json::from_value::<RcCheck>(output.clone()).ok())
+                {
+                    Some(check) => Ok(DryRun::new(sync_data.src, sync_data.dest, stats, &check)),
+                    None if job_status.error.is_empty() => {
+                        Err("no files in the check output".to_string())
+                    }
+                    None => Err(format!("cannot check the paths: {}", job_status.error)),
+                },
+            };
+            self.dry_run_results.push((name, result));
+        }
+    }
+
+    /// Queue the jobs of a batch, only the first one if the batch is sequential
+    fn launch_batch(
+        &mut self,
+        jobs: Vec<SyncJobData>,
+        policy: ExecutionPolicy,
+    ) -> Result<(), GalionError> {
+        if self.batch.is_some() {
+            return Err("a batch is already running".into());
+        }
+        let mut batch = Batch {
+            started: report_date(),
+            policy,
+            jobs: Vec::new(),
+        };
+        for (idx, mut sync_data) in jobs.into_iter().enumerate() {
+            if policy == ExecutionPolicy::Parallel || idx == 0 {
+                sync_data = self.enqueue(sync_data);
+            } else {
+                sync_data.submission = 0;
+            }
+            batch.jobs.push((sync_data, JobState::Queued));
+        }
+        self.batch = Some(batch);
+        self.dispatch();
+        Ok(())
+    }
+
+    /// Update the running batch, returns the path of its summary once it is done
+    /// # Errors
+    /// Fails if the summary cannot be written
+    fn update_batch(&mut self) -> Result<Option<PathBuf>, GalionError> {
+        let Some(mut batch) = self.batch.take() else {
+            return Ok(None);
+        };
+        for (job_data, job_state) in &mut batch.jobs {
+            // the job id is set once launched, the spilled jobs keep their last state
+            if let Some((tracked_data, tracked_state)) = self
+                .tracking_jobs
+                .iter()
+                .find(|(tracked_data, _)| tracked_data.submission == job_data.submission)
+            {
+                job_data.clone_from(tracked_data);
+                job_state.clone_from(tracked_state);
+            }
+        }
+        let is_member_running = batch
+            .jobs
+            .iter()
+            .any(|(job_data, job_state)| job_data.submission != 0 && job_state.is_waiting());
+        if batch.policy == ExecutionPolicy::Sequential
+            && !is_member_running
+            && let Some((job_data, _)) = batch.jobs.iter_mut().find(|(d, _)| d.submission == 0)
+        {
+            *job_data = self.enqueue(job_data.clone());
+            self.dispatch();
+        }
+        if batch
+            .jobs
+            .iter()
+            .any(|(_, job_state)| job_state.is_waiting())
+        {
+            self.batch = Some(batch);
+            return Ok(None);
+        }
+        batch
+            .summary()
+            .write(&self.config.reports_path(), self.config.summary_format)
+            .map(Some)
+    }
+
+    /// Send the summary of the finished batch to the ui, returns false if the ui exited
+    fn send_batch_summary(&mut self, tx_to_ui: &Sender<UiEvent>) -> bool {
+        let res_job = match self.update_batch() {
+            Ok(None) => return true,
+            Ok(Some(path)) => ResultJob::Report(path),
+            Err(e) => ResultJob::Error(format!("Cannot write the batch summary: {e}")),
+        };
+        tx_to_ui.send(UiEvent::Job(res_job)).is_ok()
+    }
+
+    /// Send the jobs, the warnings and the sync states to the ui, returns false if the ui exited
+    fn send_jobs(&mut self, tx_to_ui: &Sender<UiEvent>) -> bool {
+        self.warnings.extend(self.notifier.errors());
+        for warning in self.warnings.drain(..) {
+            if tx_to_ui
+                .send(UiEvent::Job(ResultJob::Warning(warning)))
+                .is_err()
+            {
+                return false;
+            }
+        }
+        for (name, dry_run) in self.dry_run_results.drain(..) {
+            if tx_to_ui
+                .send(UiEvent::Job(ResultJob::DryRun(name, dry_run)))
+                .is_err()
+            {
+                return false;
+            }
+        }
+        if !self.records.is_empty() {
+            let records = std::mem::take(&mut self.records);
+            if tx_to_ui
+                .send(UiEvent::Job(ResultJob::Recorded(records)))
+                .is_err()
+            {
+                return false;
+            }
+        }
+        tx_to_ui
+            .send(UiEvent::Job(ResultJob::Sync(self.tracking_jobs.clone())))
+            .is_ok()
+    }
+
+    /// Send the last notifications and their errors, then tell the ui the thread exits
+    fn send_exit(&mut self, tx_to_ui: &Sender<UiEvent>) {
+        self.warnings.extend(self.notifier.flush());
+        if !self.send_jobs(tx_to_ui) || tx_to_ui.send(UiEvent::Job(ResultJob::Exit)).is_err() {
+            // ui already exited
+        }
+    }
+
+    /// Time until the next periodic health check, `None` if they are disabled
+    fn next_health_check(&self) -> Option<Duration> {
+        let interval = parse_duration(self.config.health_check_interval.as_deref()?).ok()?;
+        Some(self.last_health_check.map_or(Duration::ZERO, |last| {
+            interval.saturating_sub(last.elapsed())
+        }))
+    }
+
+    /// Plan the next run of the scheduled remotes, returns the time until the first one
+    fn next_schedule(&mut self) -> Option<Duration> {
+        let now = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
+        let schedules = self
+            .config
+            .remotes()
+            .iter()
+            .filter_map(|remote| Some((remote.remote_name.clone(), remote.schedule.as_ref()?)))
+            .filter(|(_, schedule)| schedule.enabled)
+            .collect::<Vec<_>>();
+        self.next_runs
+            .retain(|name, _| schedules.iter().any(|(scheduled, _)| scheduled == name));
+        for (name, schedule) in schedules {
+            if self
+                .next_runs
+                .get(&name)
+                .is_some_and(|(cron, _)| *cron == schedule.cron)
+            {
+                continue;
+            }
+            match schedule.next_run_after(now) {
+                Some(next) => {
+                    self.next_runs.insert(name, (schedule.cron.clone(), next));
+                }
+                None => {
+                    self.next_runs.remove(&name);
+                }
+            }
+        }
+        self.next_runs
+            .values()
+            .map(|(_, next)| Duration::try_from(*next - now).unwrap_or(Duration::ZERO))
+            .min()
+    }
+
+    /// Queue the syncs of the remotes whose scheduled run is due or whose watched source changed,
+    /// the quarantined remotes are skipped
+    fn run_schedules(&mut self) {
+        self.run_watches();
+        if self.draining || self.next_schedule() != Some(Duration::ZERO) {
+            return;
+        }
+        let now = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
+        let due = self
+            .next_runs
+            .iter()
+            .filter(|(_, (_, next))| *next <= now)
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<String>>();
+        for name in due {
+            // planned again at the next call
+            self.next_runs.remove(&name);
+            let Some(remote) = self
+                .config
+                .remotes()
+                .iter()
+                .find(|remote| remote.remote_name == name)
+            else {
+                continue;
+            };
+            if remote.quarantined {
+                continue;
+            }
+            if let Some(sync_data) = SyncJobData::from_remote(remote) {
+                self.enqueue(sync_data);
+                self.run_hook("on_schedule_fired", (name,));
+            }
+        }
+    }
+
+    /// Watch the sources of the remotes with `watch`
+    fn update_watches(&mut self) {
+        if let Err(e) = self.watcher.update(self.config.remotes()) {
+            self.warnings.push(e.to_string());
+        }
+    }
+
+    /// Queue the syncs of the remotes whose watched source changed, the quarantined remotes are skipped
+    fn run_watches(&mut self) {
+        if self.draining {
+            return;
+        }
+        for name in self.watcher.changed() {
+            if let Some(sync_data) = self
+                .config
+                .remotes()
+                .iter()
+                .find(|remote| remote.remote_name == name && !remote.quarantined)
+                .and_then(SyncJobData::from_remote)
+            {
+                self.enqueue(sync_data);
+            }
+        }
+    }
+
+    /// Time until the next health check, scheduled run or check of the watched sources
+    fn next_wakeup(&mut self) -> Option<Duration> {
+        [
+            self.next_health_check(),
+            self.next_schedule(),
+            self.watcher.next_check(),
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+    }
+
+    /// Probe the destinations of the remotes, or of one remote, on a scoped thread pool:
+    /// a slow destination holds the jobs for one probe, not for all of them.
+    /// Returns false if the ui exited
+    fn send_health(&mut self, tx_to_ui: &Sender<UiEvent>, name: Option<&str>) -> bool {
+        if name.is_none() {
+            self.last_health_check = Some(Instant::now());
+        }
+        let remotes = self
+            .config
+            .remotes()
+            .iter()
+            .filter(|remote| name.is_none_or(|name| remote.remote_name == name))
+            .filter_map(|remote| Some((remote.remote_name.clone(), remote.remote_dest.clone()?)))
+            .collect::<Vec<(String, String)>>();
+        if remotes.is_empty() {
+            return true;
+        }
+        let rclone = self.rclone;
+        let chunk_size = remotes.len().div_ceil(HEALTH_WORKERS);
+        thread::scope(|s| {
+            let workers = remotes
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    let tx_to_ui = tx_to_ui.clone();
+                    s.spawn(move || {
+                        for (name, dest) in chunk {
+                            let Some(health) = Health::probe(rclone, dest) else {
+                                continue;
+                            };
+                            if tx_to_ui
+                                .send(UiEvent::Job(ResultJob::Health(name.clone(), health)))
+                                .is_err()
+                            {
+                                return false;
+                            }
+                        }
+                        true
+                    })
+                })
+                .collect::<Vec<_>>();
+            workers
+                .into_iter()
+                .all(|worker| worker.join().unwrap_or(false))
+        })
+    }
+
+    /// Launch a dry run, an error is sent right away to the ui, returns false if the ui exited
+    fn send_dry_run(&mut self, tx_to_ui: &Sender<UiEvent>, sync_data: SyncJobData) -> bool {
+        let name = sync_data.name.clone();
+        if let Err(e) = self.dry_run(sync_data) {
+            self.dry_run_results.push((name, Err(e.to_string())));
+        }
+        self.send_jobs(tx_to_ui)
+    }
+
+    /// Benchmark a destination and send its result to the ui, returns false if the ui exited
+    fn send_benchmark(&self, tx_to_ui: &Sender<UiEvent>, name: String, dest: &str) -> bool {
+        let benchmark = Benchmark::run(self.rclone, dest).map_err(|e| e.to_string());
+        tx_to_ui
+            .send(UiEvent::Job(ResultJob::Benchmark(name, benchmark)))
+            .is_ok()
+    }
+
+    /// Loop of the background thread
+    /// # Errors
+    /// Fails if the loop fails
+    fn run(
+        &mut self,
+        tx_to_ui: &Sender<UiEvent>,
+        rx_to_ui: &Receiver<SyncJob>,
+    ) -> Result<(), GalionError> {
+        // the attached jobs and the warnings of the start
+        if !self.send_jobs(tx_to_ui) {
+            return Ok(());
+        }
+        loop {
+            self.run_schedules();
+            let is_jobs_waiting = self.is_jobs_waiting();
+            if self.draining && !is_jobs_waiting {
+                self.send_exit(tx_to_ui);
+                return Ok(());
+            }
+            if self.next_health_check() == Some(Duration::ZERO) && !self.send_health(tx_to_ui, None)
+            {
+                self.stop_jobs();
+                return Ok(());
+            }
+            let res_job = if is_jobs_waiting {
+                self.update_jobs();
+                self.dispatch();
+                let batch_sent = self.send_batch_summary(tx_to_ui);
+                self.spill_finished_jobs();
+                if !batch_sent || !self.send_jobs(tx_to_ui) {
+                    self.stop_jobs();
+                    return Ok(());
+                }
+                match rx_to_ui.try_recv() {
+                    Ok(job) => job,
+                    Err(mpsc::TryRecvError::Empty) => {
+                        sleep(POLL_TIME);
+                        continue;
+                    }
+                    Err(mpsc::TryRecvError::Disconnected) => {
+                        self.stop_jobs();
+                        return Ok(());
+                    }
+                }
+            } else if let Some(timeout) = self.next_wakeup() {
+                match rx_to_ui.recv_timeout(timeout) {
+                    Ok(job) => job,
+                    Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                    Err(mpsc::RecvTimeoutError::Disconnected) => return Ok(()),
+                }
+            } else {
+                match rx_to_ui.recv() {
+                    Ok(job) => job,
+                    Err(_) => {
+                        return Ok(());
+                    }
+                }
+            };
+            match res_job {
+                SyncJob::Exit(ShutdownPolicy::Cancel) => {
+                    self.stop_jobs();
+                    return Ok(());
+                }
+                SyncJob::Exit(ShutdownPolicy::Wait) => self.draining = true,
+                SyncJob::Config(config) => {
+                    self.update_config(*config);
+                    self.dispatch();
+                }
+                SyncJob::Cancel(submission) => {
+                    self.cancel(submission);
+                    if !self.send_jobs(tx_to_ui) {
+                        return Ok(());
+                    }
+                }
+                SyncJob::Health(name) => {
+                    if !self.send_health(tx_to_ui, name.as_deref()) {
+                        return Ok(());
+                    }
+                }
+                SyncJob::DryRun(sync_data) => {
+                    if !self.send_dry_run(tx_to_ui, *sync_data) {
+                        return Ok(());
+                    }
+                }
+                SyncJob::Benchmark(name, dest) => {
+                    if !self.send_benchmark(tx_to_ui, name, &dest) {
+                        return Ok(());
+                    }
+                }
+                SyncJob::Sync(sync_data_received) => {
+                    self.enqueue(*sync_data_received);
+                    self.dispatch();
+                    if !self.send_jobs(tx_to_ui) {
+                        return Ok(());
+                    }
+                }
+                SyncJob::Batch(jobs, policy) => {
+                    if let Err(e) = self.launch_batch(jobs, policy) {
+                        let msg = format!("Cannot launch the batch: {e}");
+                        if tx_to_ui.send(UiEvent::Job(ResultJob::Error(msg))).is_err() {
+                            return Ok(());
+                        }
+                    } else if !self.send_batch_summary(tx_to_ui) || !self.send_jobs(tx_to_ui) {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Name of the dated backup dir of a new sync
+fn backup_dir_version() -> String {
+    let now = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
+    let format = format_description!("[year]-[month]-[day]_[hour][minute][second]");
+    now.format(&format)
+        .unwrap_or_else(|_| now.unix_timestamp().to_string())
+}
+
+/// Message of a caught panic
+fn panic_message(panic: &(dyn Any + Send)) -> String {
+    if let Some(msg) = panic.downcast_ref::<&str>() {
+        (*msg).to_string()
+    } else if let Some(msg) = panic.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Background thread to use rclone, restarted when it crashes
+/// # Errors
+/// Fails if the thread crashed too many times
+pub(crate) fn background_thread(
+    rclone: &Rclone,
+    tx_to_ui: &Sender<UiEvent>,
+    rx_to_ui: &Receiver<SyncJob>,
+    config: &GalionConfig,
+) -> Result<(), GalionError> {
+    let mut manager = JobManager::new(rclone, config);
+    let mut restarts = 0;
+    loop {
+        let err = match panic::catch_unwind(AssertUnwindSafe(|| manager.run(tx_to_ui, rx_to_ui))) {
+            Ok(Ok(())) => {
+                manager.save_history();
+                return Ok(());
+            }
+            Ok(Err(err)) => err.to_string(),
+            Err(panic) => panic_message(panic.as_ref()),
+        };
+        restarts += 1;
+        if restarts > MAX_RESTARTS {
+            manager.save_history();
+            if tx_to_ui.send(UiEvent::Job(ResultJob::Exit)).is_err() {
+                // ui already exited
+            }
+            return Err(GalionError::new(format!(
+                "Background thread crashed: {err}"
+            )));
+        }
+        if tx_to_ui
+            .send(UiEvent::Job(ResultJob::Crashed(err)))
+            .is_err()
+        {
+            return Ok(());
+        }
+        sleep(RESTART_DELAY);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::librclone::MockRclone;
+
+    /// Config whose logs stay in the temporary directory
+    fn test_config() -> GalionConfig {
+        GalionConfig {
+            sync_retries: 3,
+            log_dir: Some(std::env::temp_dir().join("galion-tests")),
+            ..GalionConfig::default()
+        }
+    }
+
+    /// Remote synced by the tests
+    fn test_remote() -> RemoteConfiguration {
+        RemoteConfiguration {
+            remote_name: "photos".to_string(),
+            remote_src: Some("/tmp/photos".to_string()),
+            remote_dest: Some("nas:photos".to_string()),
+            ..RemoteConfiguration::default()
+        }
+    }
+
+    /// Running sync of a remote
+    fn running_sync(job_id: u64, max_duration: Option<Duration>) -> SyncJobData {
+        SyncJobData::from_remote(&test_remote()).map_or_else(
+            || panic!("the remote has a source and a destination"),
+            |sync_data| SyncJobData {
+                job_id,
+                max_duration,
+                ..sync_data
+            },
+        )
+    }
+
+    #[test]
+    fn test_deadline_stop_is_not_retried() {
+        let rclone = Rclone::with_backend(
+            MockRclone::new()
+                .respond("job/status", Ok(json!({ "id": 1, "finished": false })))
+                .respond("core/stats", Ok(json!({})))
+                .respond("job/stop", Ok(json!({}))),
+        );
+        let config = test_config();
+        let mut manager = JobManager::new(&rclone, &config);
+        let sync_data = running_sync(1, Some(Duration::from_mins(1)));
+        manager.deadlines.insert(1, Instant::now());
+        manager.tracking_jobs.insert(
+            sync_data.clone(),
+            JobState::Pending(RcJobStatus::default(), RcStats::default()),
+        );
+        sleep(Duration::from_millis(1));
+        manager.update_jobs();
+        assert_eq!(
+            manager.tracking_jobs.get(&sync_data),
+            Some(&JobState::TimedOut(Duration::from_mins(1)))
+        );
+        assert_eq!(manager.tracking_jobs.len(), 1, "no retry is queued");
+        assert!(manager.retries.is_empty());
+    }
+
+    #[test]
+    fn test_status_errors_keep_the_job_running() {
+        let rclone = Rclone::with_backend(
+            MockRclone::new()
+                .respond("job/status", Err(json!({ "error": "connection reset" })))
+                .respond("job/stop", Ok(json!({}))),
+        );
+        let config = test_config();
+        let mut manager = JobManager::new(&rclone, &config);
+        let sync_data = running_sync(1, None);
+        let running = JobState::Pending(RcJobStatus::default(), RcStats::default());
+        manager
+            .tracking_jobs
+            .insert(sync_data.clone(), running.clone());
+        for _ in 1..STATUS_ERRORS {
+            manager.update_jobs();
+            assert_eq!(manager.tracking_jobs.get(&sync_data), Some(&running));
+        }
+        manager.update_jobs();
+        assert!(matches!(
+            manager.tracking_jobs.get(&sync_data),
+            Some(JobState::Failed(_))
+        ));
+        assert!(manager.status_errors.is_empty());
+    }
+
+    #[test]
+    fn test_quarantined_remote_is_not_retried() {
+        let rclone = Rclone::with_backend(
+            MockRclone::new()
+                .respond(
+                    "job/status",
+                    Ok(json!({ "id": 1, "finished": true, "error": "connection reset" })),
+                )
+                .respond("core/stats", Ok(json!({}))),
+        );
+        let config = GalionConfig {
+            remote_configurations: vec![test_remote()],
+            quarantine_after: 1,
+            ..test_config()
+        };
+        let mut manager = JobManager::new(&rclone, &config);
+        let sync_data = running_sync(1, None);
+        manager.tracking_jobs.insert(
+            sync_data,
+            JobState::Pending(RcJobStatus::default(), RcStats::default()),
+        );
+        manager.update_jobs();
+        assert!(manager.is_quarantined("photos"));
+        assert_eq!(manager.tracking_jobs.len(), 1, "no retry is queued");
+        let [record] = &manager.records[..] else {
+            panic!("one sync finished");
+        };
+        assert_eq!((record.failures, record.quarantined), (1, true));
+    }
+
+    #[test]
+    fn test_attached_job_keeps_the_remote_config() {
+        let rclone = Rclone::with_backend(
+            MockRclone::new()
+                .respond("job/list", Ok(json!({ "jobids": [1, 2], "runningIds": [1], "finishedIds": [2] })))
+                .respond(
+                    "job/status",
+                    Ok(json!({ "id": 1, "finished": false, "group": "galion/1/photos", "duration": 10.0 })),
+                )
+                .respond(
+                    "job/status",
+                    Ok(json!({ "id": 2, "finished": true, "group": "galion/2/gone" })),
+                ),
+        );
+        let config = GalionConfig {
+            remote_configurations: vec![RemoteConfiguration {
+                filters: vec!["- *.tmp".to_string()],
+                max_duration: Some("1h".to_string()),
+                ..test_remote()
+            }],
+            ..test_config()
+        };
+        let manager = JobManager::new(&rclone, &config);
+        let [(job_data, JobState::Pending(..))] =
+            &manager.tracking_jobs.iter().collect::<Vec<_>>()[..]
+        else {
+            panic!("one running job is attached: {:?}", manager.warnings);
+        };
+        assert_eq!(job_data.job_id, 1);
+        assert_eq!(job_data.filters, ["- *.tmp"]);
+        assert_eq!(job_data.max_duration, Some(Duration::from_hours(1)));
+        assert!(manager.deadlines.contains_key(&1));
+        assert_eq!(
+            manager.warnings.len(),
+            1,
+            "the job of an unknown remote is skipped"
+        );
+    }
+
+    #[test]
+    fn test_attach_without_job_list() {
+        let rclone = Rclone::with_backend(
+            MockRclone::new().respond("job/list", Err(json!({ "error": "not found" }))),
+        );
+        let config = test_config();
+        let manager = JobManager::new(&rclone, &config);
+        assert!(manager.tracking_jobs.is_empty());
+        assert!(
+            manager
+                .warnings
+                .iter()
+                .any(|warning| warning.contains("not followed"))
+        );
+    }
+
+    #[test]
+    fn test_queue_by_priority_then_submission() {
+        let rclone = Rclone::with_backend(
+            MockRclone::new()
+                .respond("sync/copy", Ok(json!({ "jobid": 1 })))
+                .respond("sync/copy", Ok(json!({ "jobid": 2 }))),
+        );
+        let config = GalionConfig {
+            max_running_jobs: 1,
+            ..test_config()
+        };
+        let mut manager = JobManager::new(&rclone, &config);
+        let copy = |name: &str, priority| SyncJobData {
+            name: name.to_string(),
+            // the same paths are queued once
+            src: format!("/tmp/{name}"),
+            kind: JobKind::Copy,
+            priority,
+            ..running_sync(0, None)
+        };
+        let low = manager.enqueue(copy("low", Priority::Low));
+        manager.enqueue(copy("first", Priority::Normal));
+        let second = manager.enqueue(copy("second", Priority::Normal));
+        let high = manager.enqueue(copy("high", Priority::High));
+        let launched = |manager: &JobManager<'_>| {
+            manager
+                .tracking_jobs
+                .iter()
+                .filter(|(_, job_state)| job_state.is_running())
+                .map(|(job_data, _)| job_data.name.clone())
+                .collect::<Vec<_>>()
+        };
+        manager.dispatch();
+        assert_eq!(launched(&manager), ["high"], "one running job at most");
+        manager
+            .tracking_jobs
+            .retain(|job_data, _| job_data.name != "high");
+        manager.dispatch();
+        assert_eq!(launched(&manager), ["first"]);
+        for job_data in [&low, &second] {
+            assert_eq!(manager.tracking_jobs.get(job_data), Some(&JobState::Queued));
+        }
+        assert!(high.submission > low.submission);
+    }
+
+    #[test]
+    fn test_health_of_every_remote_destination() {
+        let rclone = Rclone::with_backend(
+            MockRclone::new().respond("operations/stat", Ok(json!({ "item": null }))),
+        );
+        let remote = |name: &str, dest: &str| RemoteConfiguration {
+            remote_name: name.to_string(),
+            remote_dest: Some(dest.to_string()),
+            ..test_remote()
+        };
+        let config = GalionConfig {
+            remote_configurations: vec![
+                remote("photos", "nas:photos"),
+                remote("music", "nas:music"),
+                remote("local", "/backup"),
+            ],
+            ..test_config()
+        };
+        let mut manager = JobManager::new(&rclone, &config);
+        let (tx_to_ui, rx_events) = mpsc::channel();
+        assert!(manager.send_health(&tx_to_ui, None));
+        drop(tx_to_ui);
+        let mut probed = rx_events
+            .try_iter()
+            .filter_map(|event| match event {
+                UiEvent::Job(ResultJob::Health(name, health)) => Some((name, health)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        probed.sort_by(|a, b| a.0.cmp(&b.0));
+        // a local destination is not probed
+        assert_eq!(
+            probed,
+            [
+                ("music".to_string(), Health::Healthy),
+                ("photos".to_string(), Health::Healthy)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dry_run_is_followed_then_checked() {
+        let rclone = Rclone::with_backend(
+            MockRclone::new()
+                .respond("job/status", Ok(json!({ "id": 1, "finished": false })))
+                .respond(
+                    "job/status",
+                    Ok(json!({ "id": 1, "finished": true, "success": true })),
+                )
+                .respond(
+                    "job/status",
+                    Ok(json!({
+                        "id": 2,
+                        "finished": true,
+                        "error": "2 differences found",
+                        "output": { "combined": ["= same.txt", "* changed.txt", "+ old.txt"] },
+                    })),
+                )
+                .respond("core/stats", Ok(json!({ "bytes": 42 })))
+                .respond("operations/check", Ok(json!({ "jobid": 2 }))),
+        );
+        let config = test_config();
+        let mut manager = JobManager::new(&rclone, &config);
+        manager.dry_runs.push(PendingDryRun {
+            sync_data: running_sync(1, None),
+            group: "galion/1/photos".to_string(),
+            filter: None,
+            check: None,
+        });
+        assert!(manager.is_jobs_waiting());
+        manager.update_dry_runs();
+        assert_eq!(manager.dry_runs.len(), 1, "the dry run sync is running");
+        manager.update_dry_runs();
+        assert_eq!(manager.dry_runs.first().map(PendingDryRun::job_id), Some(2));
+        assert!(manager.dry_run_results.is_empty());
+        manager.update_dry_runs();
+        assert!(!manager.is_jobs_waiting());
+        let [(name, Ok(dry_run))] = &manager.dry_run_results[..] else {
+            panic!("one dry run succeeded: {:?}", manager.dry_run_results);
+        };
+        assert_eq!(name, "photos");
+        assert_eq!(dry_run.stats.bytes, 42);
+        assert_eq!(dry_run.copied, ["changed.txt"]);
+        assert_eq!(dry_run.deleted, ["old.txt"]);
+    }
+}
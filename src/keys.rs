@@ -0,0 +1,123 @@
+//! Key bindings of the ui, shown in the bottom bar and in the cheat sheet
+
+use std::fmt::Write;
+
+use crate::i18n::tr;
+
+/// Keys of the remotes table
+pub(crate) const NORMAL_KEYS: &[(&str, &str)] = &[
+    ("(esc)", "hint.leave"),
+    ("(arrow_up/arrow_down)", "hint.select"),
+    ("(arrow_right)", "hint.launch_job"),
+    ("(shift+arrow_right)", "hint.dry_run"),
+    ("(A)", "hint.run_all"),
+    ("(G)", "hint.run_group"),
+    ("(r)", "hint.remove"),
+    ("(e)", "hint.edit"),
+    ("(F)", "hint.filters"),
+    ("(d)", "hint.duplicate"),
+    ("(i)", "hint.job_details"),
+    ("(x)", "hint.cancel_job"),
+    ("(s)", "hint.stat_dest"),
+    ("(b)", "hint.browse_dest"),
+    ("(/)", "hint.search_dest"),
+    ("(v)", "hint.versions"),
+    ("(U)", "hint.new_union"),
+    ("(W)", "hint.new_remote"),
+    ("(O)", "hint.obscure"),
+    ("(S)", "hint.serve_sftp"),
+    ("(N)", "hint.serve_nfs"),
+    ("(C)", "hint.compare_report"),
+    ("(P)", "hint.pause_schedule"),
+    ("(T)", "hint.launch_template"),
+    ("(M)", "hint.statistics"),
+    ("(h)", "hint.history"),
+    ("(H)", "hint.health_check"),
+    ("(B)", "hint.benchmark"),
+    ("(R)", "hint.refresh_remotes"),
+    ("(,)", "hint.settings"),
+    ("(?)", "hint.cheat_sheet"),
+];
+
+/// Keys of the tree explorer, `R` only when the explorer shows backups
+pub(crate) const BROWSE_KEYS: &[(&str, &str)] = &[
+    ("(esc)", "hint.close"),
+    ("(arrow_up/arrow_down)", "hint.select"),
+    ("(arrow_right/arrow_left)", "hint.expand_collapse"),
+    ("(backspace)", "hint.parent_dir"),
+    ("(R)", "hint.restore_to_source"),
+    ("(s)", "hint.stat"),
+    ("(r)", "hint.rename"),
+    ("(d)", "hint.delete"),
+    ("(g)", "hint.download"),
+    ("(u)", "hint.upload"),
+    ("(p)", "hint.preview"),
+    ("(a)", "hint.create_alias"),
+];
+
+/// Keys of the edit form
+pub(crate) const EDIT_KEYS: &[(&str, &str)] = &[
+    ("(esc)", "hint.leave"),
+    ("(arrow_up/arrow_down)", "hint.select"),
+    ("(F2)", "hint.rclone_options"),
+    ("(enter)", "hint.save"),
+];
+
+/// Keys of the filter rules editor
+pub(crate) const FILTER_KEYS: &[(&str, &str)] = &[
+    ("(esc)", "hint.save_and_close"),
+    ("(arrow_up/arrow_down)", "hint.select"),
+    ("(enter)", "hint.add_rule"),
+    ("(delete)", "hint.remove_rule"),
+];
+
+/// Keys of the statistics
+pub(crate) const STATS_KEYS: &[(&str, &str)] = &[
+    ("(esc)", "hint.close"),
+    ("(tab)", "hint.next_window"),
+    ("(x)", "hint.export_csv"),
+];
+
+/// Keys of the conflicted files of a bisync
+pub(crate) const BISYNC_KEYS: &[(&str, &str)] = &[
+    ("(esc)", "hint.close"),
+    ("(arrow_up/arrow_down)", "hint.select"),
+    ("(arrow_left/arrow_right)", "hint.change_resolution"),
+    ("(tab)", "hint.strategies"),
+    ("(enter)", "hint.resolve_files"),
+];
+
+/// Sections of the cheat sheet
+const SECTIONS: [(&str, &[(&str, &str)]); 6] = [
+    ("Remotes", NORMAL_KEYS),
+    ("Tree explorer", BROWSE_KEYS),
+    ("Edit form", EDIT_KEYS),
+    ("Filter rules", FILTER_KEYS),
+    ("Statistics", STATS_KEYS),
+    ("Bisync conflicts", BISYNC_KEYS),
+];
+
+/// Cheat sheet of the keys, as Markdown tables or as plain text
+pub(crate) fn cheat_sheet(markdown: bool) -> String {
+    let mut sheet = String::new();
+    for (idx, (title, keys)) in SECTIONS.iter().enumerate() {
+        if idx > 0 {
+            sheet.push('\n');
+        }
+        let keys = keys
+            .iter()
+            .map(|(key, action)| (key.trim_matches(['(', ')']), tr(action)));
+        if markdown {
+            let _ = writeln!(sheet, "## {title}\n\n| Key | Action |\n| --- | --- |");
+            for (key, action) in keys {
+                let _ = writeln!(sheet, "| `{key}` | {action} |");
+            }
+        } else {
+            let _ = writeln!(sheet, "{title}");
+            for (key, action) in keys {
+                let _ = writeln!(sheet, "  {key:<26}{action}");
+            }
+        }
+    }
+    sheet
+}
@@ -29,13 +29,43 @@
 #![warn(clippy::multiple_crate_versions)]
 
 mod app;
+mod bench;
+mod bisync;
+mod browser;
+mod builder;
+mod commands;
+mod config_format;
+mod dryrun;
 mod errors;
+mod filters;
+mod headless;
+mod health;
+mod history;
+mod hooks;
+mod i18n;
+mod input;
+mod jobs;
+mod keys;
 pub mod librclone;
+mod logs;
+mod notify;
+mod options;
 mod remote;
+mod report;
+mod schedule;
+mod serve;
+mod session;
+mod settings;
+mod stats;
+mod system;
+mod template;
 mod ui;
+mod unlock;
+mod watch;
 
 pub use app::GalionApp;
 pub use app::GalionArgs;
+pub use commands::GalionCommand;
 pub use errors::GalionError;
 
 /// Main galion CLI
@@ -44,8 +74,11 @@ pub use errors::GalionError;
 pub fn galion_main() -> Result<(), GalionError> {
     use clap::Parser;
     let args: Vec<String> = std::env::args().collect();
-    let galion_args =
+    let mut galion_args =
         GalionArgs::try_parse_from(args).map_err(|e| e.to_string().trim_end().to_string())?;
+    if let Some(command) = galion_args.command.take() {
+        return command.run(galion_args);
+    }
     let app = GalionApp::try_from_galion_args(galion_args)?;
     app.run_tui()?;
     Ok(())
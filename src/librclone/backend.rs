@@ -0,0 +1,56 @@
+//! Backends answering the rclone RPC calls
+
+use serde_json::Value;
+#[cfg(feature = "librclone")]
+use std::ffi::{CStr, CString, c_char};
+use std::fmt::Debug;
+
+#[cfg(feature = "librclone")]
+use crate::librclone::bindings as librclone_bindings;
+
+/// Backend answering the RPC calls of [`crate::librclone::Rclone`], with the JSON output of rclone
+pub trait RcloneBackend: Debug + Send + Sync {
+    /// RPC call, the error is the JSON error of rclone
+    /// # Errors
+    /// Errors if RPC call fails
+    fn rpc(&self, method: &str, input: &Value) -> Result<String, String>;
+
+    /// Name of the backend, shown in the ui, `None` for librclone
+    fn name(&self) -> Option<&str> {
+        None
+    }
+}
+
+/// librclone, called through FFI, initialized by [`crate::librclone::Rclone`]
+#[cfg(feature = "librclone")]
+#[derive(Debug, Default)]
+pub(crate) struct LibRclone;
+
+#[cfg(feature = "librclone")]
+impl RcloneBackend for LibRclone {
+    fn rpc(&self, method: &str, input: &Value) -> Result<String, String> {
+        let method_c_string = CString::new(method)
+            .map_err(|e| format!("Error encoding the rclone RPC method: {e}"))?;
+        // serialize straight to bytes, without an intermediate String
+        let input_bytes = serde_json::to_vec(input)
+            .map_err(|e| format!("Error encoding the rclone RPC input: {e}"))?;
+        let input_c_string = CString::new(input_bytes)
+            .map_err(|e| format!("Error encoding the rclone RPC input: {e}"))?;
+        // librclone only reads these strings, so they are not mutated
+        let method_mut_ptr: *mut c_char = method_c_string.as_ptr().cast_mut();
+        let input_mut_ptr: *mut c_char = input_c_string.as_ptr().cast_mut();
+
+        let result = unsafe { librclone_bindings::RcloneRPC(method_mut_ptr, input_mut_ptr) };
+        let output_c_str: &CStr = unsafe { CStr::from_ptr(result.Output) };
+        let output = output_c_str.to_str().map(str::to_owned);
+        // free the output before returning, even if decoding failed
+        unsafe { librclone_bindings::RcloneFreeString(result.Output) };
+        let output: String =
+            output.map_err(|e| format!("Error decoding the rclone RPC output: {e}"))?;
+
+        match result.Status {
+            200 => Ok(output),
+            _ => Err(output),
+        }
+    }
+}
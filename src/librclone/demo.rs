@@ -0,0 +1,360 @@
+//! Fake rclone backend of the demo mode, answering the RPC calls with fake remotes and jobs progressing over time
+
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+use crate::librclone::backend::RcloneBackend;
+
+/// Remotes of the fake rclone config: name, backend type and description
+const REMOTES: [(&str, &str, &str); 3] = [
+    ("gdrive", "drive", "Shared family drive"),
+    ("nas", "sftp", "Storage at home"),
+    ("archive", "s3", "Cold storage"),
+];
+
+/// Methods started as a job when called with `_async`
+const JOB_METHODS: [&str; 8] = [
+    "sync/sync",
+    "sync/bisync",
+    "sync/copy",
+    "sync/move",
+    "operations/check",
+    "operations/copyfile",
+    "operations/movefile",
+    "operations/deletefile",
+];
+
+/// Every n-th sync fails, to show the failed jobs
+const FAILING_JOB: u64 = 4;
+
+/// Size of the fake files
+const FILE_SIZE: u64 = 2_000_000;
+
+/// A fake job
+#[derive(Debug)]
+struct DemoJob {
+    /// rclone method of the job
+    method: String,
+    /// stats group
+    group: String,
+    /// start of the job
+    start: Instant,
+    /// start time, RFC 3339
+    start_time: OffsetDateTime,
+    /// time to finish
+    duration: Duration,
+    /// bytes transferred when finished
+    total_bytes: u64,
+    /// error at the end of the job
+    error: Option<String>,
+    /// stopped by `job/stop`
+    stopped: bool,
+}
+
+impl DemoJob {
+    /// Fake job, its length and its size depend on its id
+    fn new(id: u64, method: &str, input: &Value) -> Self {
+        let is_transfer = method.starts_with("sync/");
+        Self {
+            method: method.to_string(),
+            group: input
+                .get("_group")
+                .and_then(Value::as_str)
+                .map_or_else(|| format!("job/{id}"), String::from),
+            start: Instant::now(),
+            start_time: OffsetDateTime::now_utc(),
+            duration: Duration::from_secs(if is_transfer { 6 + id * 7 % 15 } else { 2 }),
+            total_bytes: if is_transfer {
+                (10 + id * 37 % 90) * 1_000_000
+            } else {
+                0
+            },
+            error: (is_transfer && id.is_multiple_of(FAILING_JOB))
+                .then(|| "demo: failed to copy: connection reset by peer".to_string()),
+            stopped: false,
+        }
+    }
+
+    /// Is the job finished
+    fn finished(&self) -> bool {
+        self.stopped || self.start.elapsed() >= self.duration
+    }
+
+    /// Progress of the job, from 0 to 1
+    fn progress(&self) -> f64 {
+        (self.start.elapsed().as_secs_f64() / self.duration.as_secs_f64()).min(1.0)
+    }
+
+    /// Transferred bytes
+    #[allow(clippy::cast_precision_loss)] // the fake sizes are small
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // the progress is between 0 and 1
+    fn bytes(&self) -> u64 {
+        (self.total_bytes as f64 * self.progress()) as u64
+    }
+
+    /// Response of `job/status`
+    fn status(&self, id: u64) -> Value {
+        let finished = self.finished();
+        let error = match &self.error {
+            _ if self.stopped => "context canceled",
+            Some(error) if finished => error.as_str(),
+            _ => "",
+        };
+        let end_time = if finished {
+            (self.start_time + self.start.elapsed().min(self.duration))
+                .format(&Rfc3339)
+                .unwrap_or_default()
+        } else {
+            String::new()
+        };
+        let mut status = json!({
+            "id": id,
+            "finished": finished,
+            "success": finished && error.is_empty(),
+            "duration": self.start.elapsed().min(self.duration).as_secs_f64(),
+            "error": error,
+            "startTime": self.start_time.format(&Rfc3339).unwrap_or_default(),
+            "endTime": end_time,
+            "group": self.group,
+            "output": {},
+        });
+        if self.method == "operations/check" && finished {
+            status["output"] = check();
+        }
+        status
+    }
+}
+
+/// Response of `operations/check`, the files are identical
+fn check() -> Value {
+    json!({
+        "success": true,
+        "status": "",
+        "hashType": "md5",
+        "combined": ["= notes.txt", "= report.pdf"],
+    })
+}
+
+/// Response of `config/providers`, a few backend types
+fn providers() -> Value {
+    let option = |name: &str, help: &str, required: bool, is_password: bool| {
+        json!({
+            "Name": name,
+            "Help": help,
+            "DefaultStr": "",
+            "Required": required,
+            "IsPassword": is_password,
+            "Advanced": false,
+            "Hide": 0,
+        })
+    };
+    json!({
+        "providers": [
+            {
+                "Name": "alias",
+                "Description": "Alias for an existing remote",
+                "Options": [option("remote", "Remote or path to alias.", true, false)],
+            },
+            {
+                "Name": "sftp",
+                "Description": "SSH/SFTP",
+                "Options": [
+                    option("host", "SSH host to connect to.", true, false),
+                    option("user", "SSH username.", false, false),
+                    option("pass", "SSH password, leave blank to use ssh-agent.", false, true),
+                ],
+            },
+            {
+                "Name": "s3",
+                "Description": "Amazon S3 Compliant Storage Providers",
+                "Options": [
+                    option("access_key_id", "AWS Access Key ID.", false, false),
+                    option("secret_access_key", "AWS Secret Access Key (password).", false, true),
+                    option("region", "Region to connect to.", false, false),
+                ],
+            },
+        ],
+    })
+}
+
+/// Response of `operations/list`
+fn list(remote: &str) -> Value {
+    let now = OffsetDateTime::now_utc()
+        .format(&Rfc3339)
+        .unwrap_or_default();
+    let items = [
+        ("Documents", -1, true),
+        ("Photos", -1, true),
+        ("notes.txt", 1_432, false),
+        ("report.pdf", 284_311, false),
+    ];
+    let list = items
+        .iter()
+        .map(|(name, size, is_dir)| {
+            let path = if remote.is_empty() {
+                (*name).to_string()
+            } else {
+                format!("{}/{name}", remote.trim_end_matches('/'))
+            };
+            json!({
+                "Path": path,
+                "Name": name,
+                "Size": size,
+                "MimeType": if *is_dir { "inode/directory" } else { "application/octet-stream" },
+                "ModTime": now,
+                "IsDir": is_dir,
+            })
+        })
+        .collect::<Vec<Value>>();
+    json!({ "list": list })
+}
+
+/// Response of `core/stats`, for the jobs of a stats group
+fn stats(jobs: &BTreeMap<u64, DemoJob>, group: &str) -> Value {
+    let group_jobs = jobs
+        .values()
+        .filter(|job| job.group == group)
+        .collect::<Vec<&DemoJob>>();
+    let bytes = group_jobs.iter().map(|job| job.bytes()).sum::<u64>();
+    let total_bytes = group_jobs.iter().map(|job| job.total_bytes).sum::<u64>();
+    let running = group_jobs.iter().filter(|job| !job.finished());
+    #[allow(clippy::cast_precision_loss)] // the fake sizes are small
+    let speed = running
+        .clone()
+        .map(|job| job.total_bytes as f64 / job.duration.as_secs_f64())
+        .sum::<f64>();
+    let eta = running
+        .map(|job| {
+            job.duration
+                .saturating_sub(job.start.elapsed())
+                .as_secs_f64()
+        })
+        .reduce(f64::max);
+    let transferring = group_jobs
+        .iter()
+        .filter(|job| !job.finished())
+        .map(|job| {
+            let progress = job.bytes() % FILE_SIZE;
+            json!({
+                "name": format!("Photos/IMG_{:04}.jpg", job.bytes() / FILE_SIZE),
+                "size": FILE_SIZE,
+                "bytes": progress,
+                "percentage": progress * 100 / FILE_SIZE,
+                "speed": speed,
+            })
+        })
+        .collect::<Vec<Value>>();
+    json!({
+        "bytes": bytes,
+        "totalBytes": total_bytes,
+        "speed": speed,
+        "transfers": bytes / FILE_SIZE,
+        "eta": eta,
+        "transferring": transferring,
+    })
+}
+
+/// Fake rclone backend
+#[derive(Debug, Default)]
+pub(crate) struct DemoBackend {
+    /// jobs by id
+    jobs: Mutex<BTreeMap<u64, DemoJob>>,
+}
+
+impl RcloneBackend for DemoBackend {
+    /// Answer a RPC call like librclone
+    /// # Errors
+    /// Fails if the method is not faked, with a rclone like JSON error
+    fn rpc(&self, method: &str, input: &Value) -> Result<String, String> {
+        let error = |msg: &str| json!({ "error": msg, "input": input, "path": method }).to_string();
+        let mut jobs = self
+            .jobs
+            .lock()
+            .map_err(|_| error("demo jobs lock poisoned"))?;
+        let str_input = |key: &str| input.get(key).and_then(Value::as_str).unwrap_or_default();
+        let output = match method {
+            _ if JOB_METHODS.contains(&method) && input.get("_async") == Some(&json!(true)) => {
+                let id = jobs.keys().next_back().map_or(1, |id| id + 1);
+                jobs.insert(id, DemoJob::new(id, method, input));
+                json!({ "jobid": id })
+            }
+            "config/dump" => REMOTES
+                .iter()
+                .map(|(name, backend_type, description)| {
+                    (
+                        (*name).to_string(),
+                        json!({ "type": backend_type, "description": description }),
+                    )
+                })
+                .collect(),
+            "config/get" => REMOTES
+                .iter()
+                .find(|(name, _, _)| *name == str_input("name"))
+                .map_or_else(
+                    || json!({}),
+                    |(_, backend_type, description)| {
+                        json!({ "type": backend_type, "description": description })
+                    },
+                ),
+            "config/listremotes" => json!({ "remotes": REMOTES.map(|(name, _, _)| name) }),
+            "config/paths" => json!({ "config": "demo" }),
+            "config/providers" => providers(),
+            "config/setpath" | "config/create" | "config/delete" | "options/get" | "options/set" | "core/gc" | "core/bwlimit" => {
+                json!({})
+            }
+            "rc/noop" => input.clone(),
+            "core/stats" => stats(&jobs, str_input("group")),
+            "job/list" => {
+                let (finished, running) = jobs.iter().partition::<Vec<_>, _>(|(_, job)| job.finished());
+                json!({
+                    "jobids": jobs.keys().collect::<Vec<&u64>>(),
+                    "runningIds": running.iter().map(|(id, _)| id).collect::<Vec<_>>(),
+                    "finishedIds": finished.iter().map(|(id, _)| id).collect::<Vec<_>>(),
+                })
+            }
+            "job/status" => {
+                let id = input.get("jobid").and_then(Value::as_u64).unwrap_or_default();
+                let job = jobs.get(&id).ok_or_else(|| error("job not found"))?;
+                job.status(id)
+            }
+            "job/stop" => {
+                let id = input.get("jobid").and_then(Value::as_u64).unwrap_or_default();
+                let job = jobs.get_mut(&id).ok_or_else(|| error("job not found"))?;
+                if !job.finished() {
+                    job.stopped = true;
+                }
+                json!({})
+            }
+            "operations/check" => check(),
+            "operations/list" => list(str_input("remote")),
+            "operations/stat" => {
+                let remote = str_input("remote");
+                json!({
+                    "item": {
+                        "Path": remote,
+                        "Name": remote.rsplit('/').next().unwrap_or_default(),
+                        "Size": if remote.contains('.') { 1_432 } else { -1 },
+                        "ModTime": OffsetDateTime::now_utc().format(&Rfc3339).unwrap_or_default(),
+                        "IsDir": !remote.contains('.'),
+                    },
+                })
+            }
+            "operations/size" => json!({ "count": 1_284, "bytes": 3_412_000_000_u64, "sizeless": 0 }),
+            "operations/about" => json!({
+                "total": 2_000_000_000_000_u64,
+                "used": 812_000_000_000_u64,
+                "free": 1_188_000_000_000_u64,
+            }),
+            _ if JOB_METHODS.contains(&method) => json!({}),
+            _ => return Err(error(&format!("{method} is not available in the demo mode"))),
+        };
+        Ok(output.to_string())
+    }
+    fn name(&self) -> Option<&str> {
+        Some("demo")
+    }
+}
@@ -0,0 +1,256 @@
+//! Remote rclone `rcd` hosts, driven through their HTTP rc API instead of librclone
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use std::time::Duration;
+use ureq::tls::{Certificate, PemItem, RootCerts, TlsConfig, parse_pem};
+
+use crate::errors::GalionError;
+use crate::librclone::backend::RcloneBackend;
+use crate::remote::expand_path;
+
+/// Standard base64 of the `Basic` credentials, with padding
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let bits = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for idx in 0..4 {
+            if idx <= chunk.len() {
+                encoded.push(char::from(
+                    ALPHABET[(bits >> (18 - 6 * idx)) as usize & 0x3F],
+                ));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// A rclone started with `rclone rcd`, like a NAS or a VPS
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RcHost {
+    /// name of the host, selected with `--rc-host`
+    pub(crate) name: String,
+    /// url of the rc API, like `https://nas:5572`
+    pub(crate) url: String,
+    /// `--rc-user` of the rcd
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) user: Option<String>,
+    /// `--rc-pass` of the rcd, `$NAME` reads it from an environment variable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) pass: Option<String>,
+    /// bearer token sent instead of the user and the password, `$NAME` reads it from an environment variable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) token: Option<String>,
+    /// PEM file of the certificate authority of the rcd, for a self-signed certificate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) ca_cert: Option<PathBuf>,
+    /// don't verify the certificate of the rcd
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) insecure: bool,
+}
+
+impl RcHost {
+    /// Value of a secret, read from the environment variable if it is `$NAME` or `${NAME}`
+    /// # Errors
+    /// Fails if the environment variable is not set
+    fn secret(&self, value: &str) -> Result<String, GalionError> {
+        let Some(name) = value.strip_prefix('$') else {
+            return Ok(value.to_string());
+        };
+        let name = name
+            .strip_prefix('{')
+            .and_then(|name| name.strip_suffix('}'))
+            .unwrap_or(name);
+        std::env::var(name).map_err(|_| {
+            format!(
+                "the environment variable {name} of the rc host '{}' is not set",
+                self.name
+            )
+            .into()
+        })
+    }
+
+    /// `Authorization` header of the calls, `None` without credentials
+    /// # Errors
+    /// Fails if a secret cannot be read
+    fn authorization(&self) -> Result<Option<String>, GalionError> {
+        if let Some(token) = &self.token {
+            return Ok(Some(format!("Bearer {}", self.secret(token)?)));
+        }
+        let Some(user) = &self.user else {
+            return Ok(None);
+        };
+        let pass = match &self.pass {
+            Some(pass) => self.secret(pass)?,
+            None => String::new(),
+        };
+        Ok(Some(format!(
+            "Basic {}",
+            base64(format!("{user}:{pass}").as_bytes())
+        )))
+    }
+
+    /// TLS settings of the calls
+    /// # Errors
+    /// Fails if the certificate authority cannot be read
+    fn tls_config(&self) -> Result<TlsConfig, GalionError> {
+        let mut tls_config = TlsConfig::builder().disable_verification(self.insecure);
+        if let Some(ca_cert) = &self.ca_cert {
+            let path = expand_path(&ca_cert.to_string_lossy());
+            let pem = std::fs::read(&path)
+                .map_err(|e| format!("Cannot read the certificate authority {path}: {e}"))?;
+            let certs = parse_pem(&pem)
+                .filter_map(|item| match item {
+                    Ok(PemItem::Certificate(cert)) => Some(Ok(cert)),
+                    Ok(_) => None,
+                    Err(e) => Some(Err(e)),
+                })
+                .collect::<Result<Vec<Certificate<'static>>, ureq::Error>>()
+                .map_err(|e| format!("Invalid certificate authority {path}: {e}"))?;
+            if certs.is_empty() {
+                return Err(format!("No certificate in {path}").into());
+            }
+            tls_config = tls_config.root_certs(RootCerts::new_with_certs(&certs));
+        }
+        Ok(tls_config.build())
+    }
+}
+
+/// Backend sending the rpc calls to a rc host
+#[derive(Debug)]
+pub(crate) struct HttpBackend {
+    /// host of the calls
+    pub(crate) host: RcHost,
+    /// agent, keeping the connections open between the calls
+    agent: ureq::Agent,
+    /// `Authorization` header of the calls
+    authorization: Option<String>,
+}
+
+impl HttpBackend {
+    /// Timeout of the connection to the host, the calls themselves can run as long as a sync
+    const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Backend of a host
+    /// # Errors
+    /// Fails if the credentials or the certificate authority of the host cannot be read
+    pub fn new(host: RcHost) -> Result<Self, GalionError> {
+        let agent = ureq::Agent::config_builder()
+            .timeout_connect(Some(Self::CONNECT_TIMEOUT))
+            .http_status_as_error(false)
+            .tls_config(host.tls_config()?)
+            .build()
+            .new_agent();
+        let authorization = host.authorization()?;
+        Ok(Self {
+            host,
+            agent,
+            authorization,
+        })
+    }
+}
+
+impl RcloneBackend for HttpBackend {
+    /// Send a rpc call, the errors of rclone are JSON like the ones of librclone
+    /// # Errors
+    /// Fails if the host cannot be reached or if rclone fails
+    fn rpc(&self, method: &str, input: &Value) -> Result<String, String> {
+        let url = format!("{}/{method}", self.host.url.trim_end_matches('/'));
+        let mut request = self.agent.post(&url);
+        if let Some(authorization) = &self.authorization {
+            request = request.header("Authorization", authorization);
+        }
+        let mut response = request.send_json(input).map_err(|e| {
+            json!({ "error": format!("rc host {} failed: {e}", self.host.name) }).to_string()
+        })?;
+        let output = response
+            .body_mut()
+            .with_config()
+            .limit(u64::MAX)
+            .read_to_string()
+            .map_err(|e| {
+                json!({ "error": format!("Error decoding the response of {}: {e}", self.host.name) })
+                    .to_string()
+            })?;
+        if response.status().is_success() {
+            Ok(output)
+        } else {
+            Err(output)
+        }
+    }
+    fn name(&self) -> Option<&str> {
+        Some(&self.host.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::ErrorClass;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_unreachable_host_error_is_json() {
+        // a port just released, nothing listens on it
+        let port = TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .map_or(9, |addr| addr.port());
+        let Ok(backend) = HttpBackend::new(RcHost {
+            name: "nas".to_string(),
+            url: format!("http://127.0.0.1:{port}"),
+            ..RcHost::default()
+        }) else {
+            panic!("the host has no credentials to read");
+        };
+        let Err(error) = backend.rpc("core/version", &json!({})) else {
+            panic!("nothing listens on port {port}");
+        };
+        let error = serde_json::from_str::<Value>(&error).unwrap_or_default();
+        let message = error["error"].as_str().unwrap_or_default();
+        assert!(message.starts_with("rc host nas failed"), "{error}");
+        assert_eq!(ErrorClass::classify(message), ErrorClass::Retryable);
+    }
+
+    #[test]
+    fn test_base64() {
+        for (data, encoded) in [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("user:pässword", "dXNlcjpww6Rzc3dvcmQ="),
+        ] {
+            assert_eq!(base64(data.as_bytes()), encoded);
+        }
+    }
+
+    #[test]
+    fn test_authorization() {
+        let host = RcHost {
+            name: "nas".to_string(),
+            user: Some("galion".to_string()),
+            pass: Some("secret".to_string()),
+            ..RcHost::default()
+        };
+        assert_eq!(
+            host.authorization().ok().flatten().as_deref(),
+            Some("Basic Z2FsaW9uOnNlY3JldA==")
+        );
+        let host = RcHost {
+            token: Some("${GALION_TEST_UNSET_TOKEN}".to_string()),
+            ..host
+        };
+        assert!(host.authorization().is_err());
+        assert_eq!(RcHost::default().authorization().ok(), Some(None));
+    }
+}
@@ -0,0 +1,82 @@
+//! Mock rclone backend of the tests, answering the RPC calls with canned responses
+
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+
+use crate::librclone::backend::RcloneBackend;
+
+/// Canned responses and recorded calls
+#[derive(Debug, Default)]
+struct MockState {
+    /// responses of the methods, in the order of the calls, the last one is repeated
+    responses: BTreeMap<String, VecDeque<Result<Value, Value>>>,
+    /// calls received: method and input
+    calls: Vec<(String, Value)>,
+}
+
+/// Backend answering the RPC calls with the responses given by the test, enabled with the `mock` feature
+#[derive(Debug, Default)]
+pub struct MockRclone {
+    /// state, shared with the threads of the ui
+    state: Mutex<MockState>,
+}
+
+impl MockRclone {
+    /// Mock without response, every call fails
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a response of a method, the responses are given in order and the last one is repeated
+    #[must_use]
+    pub fn respond(self, method: &str, response: Result<Value, Value>) -> Self {
+        if let Ok(mut state) = self.state.lock() {
+            state
+                .responses
+                .entry(method.to_string())
+                .or_default()
+                .push_back(response);
+        }
+        self
+    }
+
+    /// Calls received, method and input
+    pub fn calls(&self) -> Vec<(String, Value)> {
+        self.state
+            .lock()
+            .map(|state| state.calls.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl RcloneBackend for MockRclone {
+    fn rpc(&self, method: &str, input: &Value) -> Result<String, String> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| json!({ "error": "mock lock poisoned" }).to_string())?;
+        state.calls.push((method.to_string(), input.clone()));
+        let responses = state.responses.get_mut(method);
+        let response = match responses {
+            Some(responses) if responses.len() > 1 => responses.pop_front(),
+            Some(responses) => responses.front().cloned(),
+            None => None,
+        };
+        match response {
+            Some(Ok(output)) => Ok(output.to_string()),
+            Some(Err(error)) => Err(error.to_string()),
+            None => Err(json!({
+                "error": format!("no mock response for {method}"),
+                "input": input,
+                "path": method,
+            })
+            .to_string()),
+        }
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some("mock")
+    }
+}
@@ -8,10 +8,20 @@
 #[allow(non_snake_case)]
 #[allow(clippy::all)]
 #[allow(clippy::missing_docs_in_private_items)]
+#[cfg(feature = "librclone")]
 mod bindings {
     //! Bindings generated by build.rs
     #[cfg(not(docsrs))]
     include!(concat!(env!("OUT_DIR"), "/librclone/bindings.rs"));
 }
+mod backend;
+mod demo;
+mod http;
+#[cfg(any(test, feature = "mock"))]
+mod mock;
 pub mod rclone;
+pub use backend::RcloneBackend;
+pub use http::RcHost;
+#[cfg(any(test, feature = "mock"))]
+pub use mock::MockRclone;
 pub use rclone::Rclone;
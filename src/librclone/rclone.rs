@@ -1,16 +1,46 @@
 //! Wrapper calls around librclone bindings
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::{Value, json};
-use std::ffi::{CStr, c_char};
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard};
 
-use crate::{errors::GalionError, librclone::bindings as librclone_bindings};
+use crate::errors::GalionError;
+#[cfg(feature = "librclone")]
+use crate::librclone::backend::LibRclone;
+use crate::librclone::backend::RcloneBackend;
+#[cfg(feature = "librclone")]
+use crate::librclone::bindings as librclone_bindings;
+use crate::librclone::demo::DemoBackend;
+use crate::librclone::http::{HttpBackend, RcHost};
+use crate::remote::RclonePath;
+
+/// Rclone config files, the remotes of the extra files are reached by switching the config path
+#[derive(Debug, Default)]
+struct ConfigFiles {
+    /// main config file, set when the first extra file is added
+    main: Option<String>,
+    /// config file in use
+    current: Option<String>,
+    /// extra config files
+    extra: Vec<String>,
+    /// extra config file of each remote
+    remotes: BTreeMap<String, String>,
+}
+
+/// Rpc inputs holding a rclone path
+const FS_KEYS: [&str; 5] = ["fs", "srcFs", "dstFs", "path1", "path2"];
 
 /// Rclone wrapper
 #[derive(Debug, Default)]
 pub struct Rclone {
     /// Is lib rclone init
+    #[cfg(feature = "librclone")]
     librclone_is_initialized: bool,
+    /// config files, locked while a call uses them
+    config_files: Mutex<ConfigFiles>,
+    /// backend answering the calls instead of librclone
+    backend: Option<Box<dyn RcloneBackend>>,
 }
 
 impl Drop for Rclone {
@@ -28,8 +58,39 @@ impl Rclone {
         rclone
     }
 
-    /// initialize lib
+    /// Create a rclone instance answered by another backend, librclone is not initialized
+    #[must_use]
+    pub fn with_backend(backend: impl RcloneBackend + 'static) -> Self {
+        Self {
+            #[cfg(feature = "librclone")]
+            librclone_is_initialized: false,
+            config_files: Mutex::default(),
+            backend: Some(Box::new(backend)),
+        }
+    }
+
+    /// Create a rclone instance answered by a fake backend
+    #[must_use]
+    pub fn demo() -> Self {
+        Self::with_backend(DemoBackend::default())
+    }
+
+    /// Create a rclone instance driving a rclone rcd through its HTTP rc API
+    /// # Errors
+    /// Fails if the credentials or the certificate authority of the host cannot be read
+    pub fn http(host: RcHost) -> Result<Self, GalionError> {
+        Ok(Self::with_backend(HttpBackend::new(host)?))
+    }
+
+    /// Name of the backend when it is not librclone, `demo` or the name of the rc host
+    #[must_use]
+    pub fn backend_name(&self) -> Option<&str> {
+        self.backend.as_ref().and_then(|backend| backend.name())
+    }
+
+    /// initialize lib, nothing to do without the `librclone` feature
     pub fn initialize(&mut self) {
+        #[cfg(feature = "librclone")]
         if !self.librclone_is_initialized {
             unsafe { librclone_bindings::RcloneInitialize() };
             self.librclone_is_initialized = true;
@@ -38,6 +99,7 @@ impl Rclone {
 
     /// finalize lib
     pub fn finalize(&mut self) {
+        #[cfg(feature = "librclone")]
         if self.librclone_is_initialized {
             unsafe { librclone_bindings::RcloneFinalize() }
             self.librclone_is_initialized = false;
@@ -48,43 +110,138 @@ impl Rclone {
     /// # Errors
     /// Errors if RPC call fails
     pub fn rpc(&self, method: &str, input: &Value) -> Result<String, String> {
-        let method_bytes = method.as_bytes();
-        let mut method_c_chars: Vec<c_char> = method_bytes
+        match &self.backend {
+            Some(backend) => backend.rpc(method, input),
+            #[cfg(feature = "librclone")]
+            None => LibRclone.rpc(method, input),
+            #[cfg(not(feature = "librclone"))]
+            None => Err(json!({
+                "error": "galion is built without librclone, use --demo or --rc-host",
+            })
+            .to_string()),
+        }
+    }
+
+    /// Lock the config files, with the config file of the remotes of `paths` in use
+    /// # Errors
+    /// Fails if the remotes are in different config files or if the config path cannot be set
+    fn select_config(&self, paths: &[&str]) -> Result<MutexGuard<'_, ConfigFiles>, GalionError> {
+        let mut files = self
+            .config_files
+            .lock()
+            .map_err(|_| "rclone config files lock poisoned")?;
+        let Some(main) = files.main.clone() else {
+            return Ok(files);
+        };
+        let mut needed = paths
             .iter()
-            .map(|c| (*c).cast_signed())
-            .collect::<Vec<c_char>>();
-        method_c_chars.push(0); // null terminator
-        let method_mut_ptr: *mut c_char = method_c_chars.as_mut_ptr();
+            .filter_map(|path| match RclonePath::parse(path) {
+                RclonePath::Remote(name, _) => Some(files.remotes.get(name).unwrap_or(&main)),
+                _ => None,
+            })
+            .collect::<Vec<&String>>();
+        needed.dedup();
+        let target = match needed[..] {
+            [] => main.clone(),
+            [target] => target.clone(),
+            _ => return Err("the remotes are in different rclone config files".into()),
+        };
+        if files.current.as_ref() != Some(&target) {
+            self.set_config_path(&target)?;
+            files.current = Some(target);
+        }
+        Ok(files)
+    }
 
-        let input_bytes: Vec<u8> = input.to_string().into_bytes();
-        let mut input_c_chars: Vec<c_char> = input_bytes
+    /// RPC call on rclone paths, with the config file of their remotes
+    ///
+    /// rclone resolves the paths of an `_async` job after the call returns, when another call
+    /// may have switched the config file: with extra config files, the paths are resolved before
+    /// the call, in the Fs cache of rclone, and the job finds them there. The files are released
+    /// once rclone returns the job id, a job starting after the expiry of the cache
+    /// (`--fs-cache-expire-duration`) is not covered.
+    /// # Errors
+    /// Errors if RPC call fails
+    fn fs_rpc(&self, method: &str, input: &Value) -> Result<String, String> {
+        let paths = FS_KEYS
             .iter()
-            .map(|c| (*c).cast_signed())
-            .collect::<Vec<c_char>>();
-        input_c_chars.push(0); // null terminator
-        let input_mut_ptr: *mut c_char = input_c_chars.as_mut_ptr();
+            .filter_map(|key| input.get(key).and_then(Value::as_str))
+            .collect::<Vec<&str>>();
+        let files = self
+            .select_config(&paths)
+            .map_err(|e| json!({ "error": e.to_string() }).to_string())?;
+        let is_async = input.get("_async").and_then(Value::as_bool) == Some(true);
+        if files.main.is_none() || !is_async {
+            return self.rpc(method, input);
+        }
+        for path in &paths {
+            if let RclonePath::Remote(..) = RclonePath::parse(path) {
+                // an invalid path is reported by the job itself
+                let _ = self.rpc("operations/fsinfo", &json!({ "fs": path }));
+            }
+        }
+        let res = self.rpc(method, input);
+        drop(files);
+        res
+    }
 
-        let result = unsafe { librclone_bindings::RcloneRPC(method_mut_ptr, input_mut_ptr) };
-        let output_c_str: &CStr = unsafe { CStr::from_ptr(result.Output) };
-        let output_slice: &str = output_c_str
-            .to_str()
-            .map_err(|e| format!("Error decoding the rclone RPC output: {e}"))?;
-        let output: String = output_slice.to_owned();
-        unsafe { librclone_bindings::RcloneFreeString(result.Output) };
+    /// Add a rclone config file, its remotes are listed after the ones of the main config file
+    /// # Errors
+    /// Fails if the path of the main config file cannot be read
+    pub fn add_config_file(&self, path: &str) -> Result<(), GalionError> {
+        let main = self.config_path()?;
+        let mut files = self
+            .config_files
+            .lock()
+            .map_err(|_| "rclone config files lock poisoned")?;
+        if files.main.is_none() {
+            files.current = Some(main.clone());
+            files.main = Some(main);
+        }
+        files.extra.push(path.to_string());
+        Ok(())
+    }
 
-        match result.Status {
-            200 => Ok(output),
-            _ => Err(output),
+    /// Config file of a remote, the main config file for `None` or a remote of the main file
+    /// # Errors
+    /// Fails if the path of the main config file cannot be read
+    pub fn remote_config_file(&self, remote_name: Option<&str>) -> Result<String, GalionError> {
+        {
+            let files = self
+                .config_files
+                .lock()
+                .map_err(|_| "rclone config files lock poisoned")?;
+            if let Some(main) = &files.main {
+                return Ok(remote_name
+                    .and_then(|name| files.remotes.get(name))
+                    .unwrap_or(main)
+                    .clone());
+            }
         }
+        self.config_path()
+    }
+
+    /// Extra rclone config files
+    pub fn extra_config_files(&self) -> Vec<String> {
+        self.config_files
+            .lock()
+            .map(|files| files.extra.clone())
+            .unwrap_or_default()
+    }
+
+    /// RPC call returning the raw JSON response, for the methods without a typed wrapper
+    /// # Errors
+    /// Fails if error with lib
+    pub fn rpc_raw(&self, method: &str, input: &Value) -> Result<Value, GalionError> {
+        let res = self.rpc(method, input)?;
+        Ok(serde_json::from_str::<Value>(&res)?)
     }
 
     /// rclone noop test
     /// # Errors
     /// Fails if error with lib
     pub fn rc_noop(&self, value: &Value) -> Result<Value, GalionError> {
-        let res = self.rpc("rc/noop", value)?;
-        let value = serde_json::from_str::<Value>(&res)?;
-        Ok(value)
+        self.rpc_raw("rc/noop", value)
     }
 
     /// rclone gc
@@ -99,18 +256,14 @@ impl Rclone {
     /// # Errors
     /// Fails if error with lib
     pub fn get_rpc_config(&self) -> Result<Value, GalionError> {
-        let res = self.rpc("options/get", &json!({}))?;
-        let value = serde_json::from_str::<Value>(&res)?;
-        Ok(value)
+        self.rpc_raw("options/get", &json!({}))
     }
 
     /// Set the rpc config
     /// # Errors
     /// Fails if error with lib
     pub fn set_config_options(&self, conf: &Value) -> Result<Value, GalionError> {
-        let res = self.rpc("options/set", conf)?;
-        let value = serde_json::from_str::<Value>(&res)?;
-        Ok(value)
+        self.rpc_raw("options/set", conf)
     }
 
     /// Set the rclone config path
@@ -125,19 +278,45 @@ impl Rclone {
         Ok(value)
     }
 
+    /// Get the path of the rclone config file
+    /// # Errors
+    /// Fails if error with lib
+    pub fn config_path(&self) -> Result<String, GalionError> {
+        let res = self.rpc("config/paths", &json!({}))?;
+        let value = serde_json::from_str::<Value>(&res)?;
+        match value.get("config") {
+            Some(Value::String(path)) => Ok(path.clone()),
+            _ => Err("Bad response - no config path".into()),
+        }
+    }
+
+    /// Unlock an encrypted rclone config with its password
+    ///
+    /// `config/setpassword` would encrypt the config again with the typed password, and
+    /// `RCLONE_CONFIG_PASS` is only read when rclone loads the config: `config/unlock` checks the
+    /// password of the config already loaded by librclone or by a rc host
+    /// # Errors
+    /// Fails if error with lib, like a wrong password
+    pub fn unlock_config(&self, password: &str) -> Result<(), GalionError> {
+        self.rpc("config/unlock", &json!({ "config_password": password }))?;
+        Ok(())
+    }
+
     /// Dump the rclone config
     /// # Errors
     /// Fails if error with lib
-    pub fn dump_config(&self) -> Result<Value, GalionError> {
+    pub fn dump_config(&self) -> Result<BTreeMap<String, RcRemoteConfig>, GalionError> {
         let res = self.rpc("config/dump", &json!({}))?;
-        let value = serde_json::from_str::<Value>(&res)?;
-        Ok(value)
+        Ok(serde_json::from_str::<BTreeMap<String, RcRemoteConfig>>(
+            &res,
+        )?)
     }
 
     /// List the remotes
     /// # Errors
     /// Fails if error with lib
     pub fn list_remotes(&self) -> Result<Vec<String>, GalionError> {
+        let files = self.select_config(&[])?;
         let res = self.rpc("config/listremotes", &json!({}))?;
         let value = serde_json::from_str::<Value>(&res)?;
         match value {
@@ -149,6 +328,8 @@ impl Rclone {
                             remotes.push(remote_name.clone());
                         }
                     }
+                    // the remotes of the extra config files, as of the last listing
+                    remotes.extend(files.remotes.keys().cloned());
                     Ok(remotes)
                 }
                 _ => Ok(vec![]),
@@ -157,16 +338,321 @@ impl Rclone {
         }
     }
 
+    /// List the remotes with their backend type and description, from the config dump
+    /// # Errors
+    /// Fails if error with lib
+    ///
+    /// The remotes of the extra config files are listed after the ones of the main one, and
+    /// a remote named like a remote already listed is skipped
+    pub fn list_remotes_long(&self) -> Result<Vec<RcRemote>, GalionError> {
+        let mut files = self.select_config(&[])?;
+        let mut remotes = self.dump_remotes(None)?;
+        let Some(main) = files.main.clone() else {
+            return Ok(remotes);
+        };
+        files.remotes.clear();
+        for extra in files.extra.clone() {
+            self.set_config_path(&extra)?;
+            files.current = Some(extra.clone());
+            for remote in self.dump_remotes(Some(&extra))? {
+                if remotes.iter().all(|r| r.name != remote.name) {
+                    files.remotes.insert(remote.name.clone(), extra.clone());
+                    remotes.push(remote);
+                }
+            }
+        }
+        self.set_config_path(&main)?;
+        files.current = Some(main);
+        Ok(remotes)
+    }
+
+    /// Remotes of the config file in use, from the config dump
+    fn dump_remotes(&self, config_file: Option<&str>) -> Result<Vec<RcRemote>, GalionError> {
+        Ok(self
+            .dump_config()?
+            .into_iter()
+            .map(|(name, remote)| RcRemote {
+                name,
+                backend_type: remote.backend_type,
+                description: remote.description,
+                config_file: config_file.map(String::from),
+            })
+            .collect())
+    }
+
     /// Get on remote
     /// # Errors
     /// Fails if error with lib
-    pub fn get_remote(&self, remote_name: &str) -> Result<Value, GalionError> {
+    pub fn get_remote(&self, remote_name: &str) -> Result<RcRemoteConfig, GalionError> {
+        let _files = self.select_config(&[&format!("{remote_name}:")])?;
         let res = self.rpc("config/get", &json!({"name": remote_name}))?;
+        Ok(serde_json::from_str::<RcRemoteConfig>(&res)?)
+    }
+
+    /// Obscure a password for the rclone config
+    /// # Errors
+    /// Fails if error with lib
+    pub fn obscure(&self, clear: &str) -> Result<String, GalionError> {
+        let res = self.rpc("core/obscure", &json!({ "clear": clear }))?;
+        let value = serde_json::from_str::<Value>(&res)?;
+        match value.get("obscured") {
+            Some(Value::String(obscured)) => Ok(obscured.clone()),
+            _ => Err("Bad response - no obscured password".into()),
+        }
+    }
+
+    /// Start a server of a remote, like `sftp`
+    /// # Errors
+    /// Fails if error with lib
+    pub fn serve_start(
+        &self,
+        serve_type: &str,
+        fs: &str,
+        options: &Value,
+    ) -> Result<RcServe, GalionError> {
+        let mut input = options.clone();
+        input["type"] = json!(serve_type);
+        input["fs"] = json!(fs);
+        let res = self.fs_rpc("serve/start", &input)?;
+        Ok(serde_json::from_str::<RcServe>(&res)?)
+    }
+
+    /// Stop a server started with [`Rclone::serve_start`]
+    /// # Errors
+    /// Fails if error with lib
+    pub fn serve_stop(&self, id: &str) -> Result<(), GalionError> {
+        self.rpc("serve/stop", &json!({ "id": id }))?;
+        Ok(())
+    }
+
+    /// List the backend types and their options
+    /// # Errors
+    /// Fails if error with lib
+    pub fn providers(&self) -> Result<Vec<RcProvider>, GalionError> {
+        let res = self.rpc("config/providers", &json!({}))?;
+        let mut value = serde_json::from_str::<Value>(&res)?;
+        match value.get_mut("providers").map(Value::take) {
+            Some(providers) => Ok(serde_json::from_value(providers)?),
+            None => Err("Bad response - no providers".into()),
+        }
+    }
+
+    /// Create a remote in the rclone config, the passwords are given in clear and obscured by rclone
+    /// # Errors
+    /// Fails if error with lib
+    pub fn create_remote(
+        &self,
+        remote_name: &str,
+        backend_type: &str,
+        parameters: &Value,
+    ) -> Result<Value, GalionError> {
+        // the remotes are created in the main config file
+        let _files = self.select_config(&[])?;
+        let res = self.rpc(
+            "config/create",
+            &json!({
+                "name": remote_name,
+                "type": backend_type,
+                "parameters": parameters,
+                "opt": { "nonInteractive": true, "obscure": true },
+            }),
+        )?;
         let value = serde_json::from_str::<Value>(&res)?;
         Ok(value)
     }
 
-    /// Trigger a sync job
+    /// Delete a remote from its rclone config file
+    /// # Errors
+    /// Fails if error with lib
+    pub fn delete_remote(&self, remote_name: &str) -> Result<(), GalionError> {
+        let _files = self.select_config(&[&format!("{remote_name}:")])?;
+        self.rpc("config/delete", &json!({ "name": remote_name }))?;
+        Ok(())
+    }
+
+    /// Stat a file or a directory, `None` if it doesn't exist
+    /// # Errors
+    /// Fails if error with lib
+    pub fn stat(
+        &self,
+        fs: &str,
+        remote: &str,
+        show_hash: bool,
+    ) -> Result<Option<Value>, GalionError> {
+        let res = self.fs_rpc(
+            "operations/stat",
+            &json!({ "fs": fs, "remote": remote, "opt": { "showHash": show_hash } }),
+        )?;
+        let mut value = serde_json::from_str::<Value>(&res)?;
+        match value.get_mut("item").map(Value::take) {
+            Some(Value::Null) | None => Ok(None),
+            Some(item) => Ok(Some(item)),
+        }
+    }
+
+    /// List a directory, `remote` is relative to `fs`
+    /// # Errors
+    /// Fails if error with lib
+    pub fn list(
+        &self,
+        fs: &str,
+        remote: &str,
+        opt: &Value,
+    ) -> Result<Vec<RcListItem>, GalionError> {
+        let res = self.fs_rpc(
+            "operations/list",
+            &json!({ "fs": fs, "remote": remote, "opt": opt }),
+        )?;
+        Self::parse_list(&res)
+    }
+
+    /// Recursively list the files matching an include glob
+    /// # Errors
+    /// Fails if error with lib
+    pub fn search(&self, fs: &str, include: &str) -> Result<Vec<RcListItem>, GalionError> {
+        let res = self.fs_rpc(
+            "operations/list",
+            &json!({
+                "fs": fs,
+                "remote": "",
+                "opt": { "recurse": true, "filesOnly": true },
+                "_filter": { "IncludeRule": [include] },
+            }),
+        )?;
+        Self::parse_list(&res)
+    }
+
+    /// Compare the files of the source and of the destination, `filter` are the rclone filter rules
+    /// # Errors
+    /// Fails if error with lib
+    pub fn check(
+        &self,
+        src_fs: &str,
+        dst_fs: &str,
+        filter: Option<&Value>,
+    ) -> Result<RcCheck, GalionError> {
+        let mut input = json!({ "srcFs": src_fs, "dstFs": dst_fs, "combined": true });
+        if let Some(filter) = filter {
+            input["_filter"] = filter.clone();
+        }
+        let res = self.fs_rpc("operations/check", &input)?;
+        Ok(serde_json::from_str::<RcCheck>(&res)?)
+    }
+
+    /// Start a check of the files of a sync, their output is the one of [`Rclone::check`]
+    /// # Errors
+    /// Fails if error with lib
+    pub fn check_job(
+        &self,
+        src_fs: &str,
+        dst_fs: &str,
+        group: Option<&str>,
+        filter: Option<&Value>,
+    ) -> Result<RcJobStart, GalionError> {
+        let mut input = json!({
+            "srcFs": src_fs,
+            "dstFs": dst_fs,
+            "combined": true,
+            "_async": true,
+        });
+        if let Some(group) = group {
+            input["_group"] = json!(group);
+        }
+        if let Some(filter) = filter {
+            input["_filter"] = filter.clone();
+        }
+        let res = self.fs_rpc("operations/check", &input)?;
+        Ok(serde_json::from_str::<RcJobStart>(&res)?)
+    }
+
+    /// Count the files and their size in a rclone path
+    /// # Errors
+    /// Fails if error with lib
+    pub fn size(&self, fs: &str) -> Result<RcSize, GalionError> {
+        let res = self.fs_rpc("operations/size", &json!({ "fs": fs }))?;
+        Ok(serde_json::from_str::<RcSize>(&res)?)
+    }
+
+    /// Get the quota of a remote
+    /// # Errors
+    /// Fails if error with lib, or if the remote has no quota
+    pub fn about(&self, fs: &str) -> Result<RcAbout, GalionError> {
+        let res = self.fs_rpc("operations/about", &json!({ "fs": fs }))?;
+        Ok(serde_json::from_str::<RcAbout>(&res)?)
+    }
+
+    /// Parse an `operations/list` response
+    fn parse_list(res: &str) -> Result<Vec<RcListItem>, GalionError> {
+        let mut value = serde_json::from_str::<Value>(res)?;
+        match value.get_mut("list").map(Value::take) {
+            Some(list) => Ok(serde_json::from_value(list)?),
+            None => Err("Bad response - no list".into()),
+        }
+    }
+
+    /// Copy a file
+    /// # Errors
+    /// Fails if error with lib
+    pub fn copy_file(
+        &self,
+        (src_fs, src_remote): (&str, &str),
+        (dst_fs, dst_remote): (&str, &str),
+        is_async: bool,
+        group: Option<&str>,
+    ) -> Result<RcJobStart, GalionError> {
+        let input = json!({
+            "srcFs": src_fs,
+            "srcRemote": src_remote,
+            "dstFs": dst_fs,
+            "dstRemote": dst_remote,
+        });
+        self.job("operations/copyfile", input, is_async, group)
+    }
+
+    /// Get the transfer stats of a rclone stats group
+    /// # Errors
+    /// Fails if error with lib
+    pub fn core_stats(&self, group: &str) -> Result<RcStats, GalionError> {
+        let res = self.rpc("core/stats", &json!({ "group": group }))?;
+        let stats = serde_json::from_str::<RcStats>(&res)?;
+        Ok(stats)
+    }
+
+    /// Move a file, server side when the backend supports it
+    /// # Errors
+    /// Fails if error with lib
+    pub fn move_file(
+        &self,
+        src_fs: &str,
+        src_remote: &str,
+        dst_fs: &str,
+        dst_remote: &str,
+    ) -> Result<(), GalionError> {
+        self.fs_rpc(
+            "operations/movefile",
+            &json!({
+                "srcFs": src_fs,
+                "srcRemote": src_remote,
+                "dstFs": dst_fs,
+                "dstRemote": dst_remote,
+            }),
+        )?;
+        Ok(())
+    }
+
+    /// Move a directory, server side when the backend supports it
+    /// # Errors
+    /// Fails if error with lib
+    pub fn move_dir(&self, src_fs: &str, dst_fs: &str) -> Result<(), GalionError> {
+        self.fs_rpc(
+            "sync/move",
+            &json!({ "srcFs": src_fs, "dstFs": dst_fs, "deleteEmptySrcDirs": true }),
+        )?;
+        Ok(())
+    }
+
+    /// Trigger a sync job, `group` is the rclone stats group of the job and `params` the sync options
+    /// like `createEmptySrcDirs`, with the rclone options in `_config` and the filter rules in `_filter`
     /// # Errors
     /// Fails if error with lib
     pub fn sync<Src: AsRef<str>, Dest: AsRef<str>>(
@@ -174,26 +660,112 @@ impl Rclone {
         src_fs: Src,
         dest_fs: Dest,
         is_async: bool,
-    ) -> Result<Value, GalionError> {
-        match self.rpc(
-            "sync/sync",
-            &json!({
-                "srcFs": src_fs.as_ref(),
-                "dstFs": dest_fs.as_ref(),
-                "_async": is_async,
-            }),
-        ) {
-            Ok(res) => {
-                let value = serde_json::from_str::<Value>(&res)?;
-                Ok(value)
-            }
+        group: Option<&str>,
+        params: &Value,
+    ) -> Result<RcJobStart, GalionError> {
+        let mut input = params.clone();
+        input["srcFs"] = json!(src_fs.as_ref());
+        input["dstFs"] = json!(dest_fs.as_ref());
+        self.job("sync/sync", input, is_async, group)
+    }
+
+    /// Trigger a bisync job, `params` are the bisync options like `resync`
+    /// # Errors
+    /// Fails if error with lib
+    pub fn bisync(
+        &self,
+        path1: &str,
+        path2: &str,
+        is_async: bool,
+        group: Option<&str>,
+        params: &Value,
+    ) -> Result<RcJobStart, GalionError> {
+        let mut input = params.clone();
+        input["path1"] = json!(path1);
+        input["path2"] = json!(path2);
+        self.job("sync/bisync", input, is_async, group)
+    }
+
+    /// Trigger a copy job, `group` is the rclone stats group of the job and `params` the options like for [`Rclone::sync`]
+    /// # Errors
+    /// Fails if error with lib
+    pub fn copy(
+        &self,
+        src_fs: &str,
+        dest_fs: &str,
+        is_async: bool,
+        group: Option<&str>,
+        params: &Value,
+    ) -> Result<RcJobStart, GalionError> {
+        let mut input = params.clone();
+        input["srcFs"] = json!(src_fs);
+        input["dstFs"] = json!(dest_fs);
+        self.job("sync/copy", input, is_async, group)
+    }
+
+    /// Trigger a move job, the source files are deleted once transferred, `params` are the options like for [`Rclone::sync`]
+    /// # Errors
+    /// Fails if error with lib
+    pub fn move_files(
+        &self,
+        src_fs: &str,
+        dest_fs: &str,
+        is_async: bool,
+        group: Option<&str>,
+        params: &Value,
+    ) -> Result<RcJobStart, GalionError> {
+        let mut input = params.clone();
+        input["srcFs"] = json!(src_fs);
+        input["dstFs"] = json!(dest_fs);
+        self.job("sync/move", input, is_async, group)
+    }
+
+    /// Delete a file, `group` is the rclone stats group of the job
+    /// # Errors
+    /// Fails if error with lib
+    pub fn delete_file(
+        &self,
+        fs: &str,
+        remote: &str,
+        is_async: bool,
+        group: Option<&str>,
+    ) -> Result<RcJobStart, GalionError> {
+        let input = json!({ "fs": fs, "remote": remote });
+        self.job("operations/deletefile", input, is_async, group)
+    }
+
+    /// Call a method that can run as a rclone job
+    /// # Errors
+    /// Fails if error with lib
+    fn job(
+        &self,
+        method: &str,
+        mut input: Value,
+        is_async: bool,
+        group: Option<&str>,
+    ) -> Result<RcJobStart, GalionError> {
+        input["_async"] = json!(is_async);
+        if let Some(group) = group {
+            input["_group"] = json!(group);
+        }
+        match self.fs_rpc(method, &input) {
+            Ok(res) => Ok(serde_json::from_str::<RcJobStart>(&res)?),
             Err(e) => {
-                let value = serde_json::from_str::<Value>(&e)?;
+                let value =
+                    serde_json::from_str::<Value>(&e).unwrap_or_else(|_| json!({ "error": e }));
                 Err(value.into())
             }
         }
     }
 
+    /// Set the bandwidth limit of all the transfers, `off` to remove it
+    /// # Errors
+    /// Fails if error with lib
+    pub fn bwlimit(&self, rate: &str) -> Result<(), GalionError> {
+        self.rpc("core/bwlimit", &json!({ "rate": rate }))?;
+        Ok(())
+    }
+
     /// List rclone jobs
     /// # Errors
     /// Fails if error with lib
@@ -203,16 +775,49 @@ impl Rclone {
         Ok(list)
     }
 
+    /// Stop a running job by id
+    /// # Errors
+    /// Fails if error with lib
+    pub fn job_stop(&self, job_id: u64) -> Result<(), GalionError> {
+        self.rpc("job/stop", &json!({ "jobid": job_id }))?;
+        Ok(())
+    }
+
     /// Get job status by id
     /// # Errors
     /// Fails if error with lib
-    pub fn job_status(&self, job_id: u64) -> Result<Value, GalionError> {
+    pub fn job_status(&self, job_id: u64) -> Result<RcJobStatus, GalionError> {
         let res = self.rpc("job/status", &json!({ "jobid": job_id }))?;
-        let value = serde_json::from_str::<Value>(&res)?;
-        Ok(value)
+        let raw = serde_json::from_str::<Value>(&res)?;
+        let mut status = serde_json::from_value::<RcJobStatus>(raw.clone())?;
+        status.raw = raw;
+        Ok(status)
     }
 }
 
+/// Response of a method that can run as a rclone job
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RcJobStart {
+    /// id of the job, only for the `_async` calls
+    #[serde(rename = "jobid", skip_serializing_if = "Option::is_none")]
+    pub job_id: Option<u64>,
+}
+
+/// Remote of the rclone config, from `config/get` and `config/dump`
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct RcRemoteConfig {
+    /// backend type
+    #[serde(rename = "type", default)]
+    pub backend_type: String,
+    /// description, empty if none
+    #[serde(default)]
+    pub description: String,
+    /// other parameters of the backend, like `remote` for an alias
+    #[serde(flatten)]
+    pub parameters: BTreeMap<String, Value>,
+}
+
 /// Job List struct
 #[derive(Debug, Deserialize, Serialize)]
 pub struct RcJobList {
@@ -226,3 +831,201 @@ pub struct RcJobList {
     #[serde(rename = "finishedIds")]
     pub finished_ids: Vec<u64>,
 }
+
+/// Job status struct
+///
+/// The fields differ between rclone versions, so every field is optional
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RcJobStatus {
+    /// is the job finished
+    #[serde(deserialize_with = "null_as_default")]
+    pub finished: bool,
+    /// success status
+    #[serde(deserialize_with = "null_as_default")]
+    pub success: bool,
+    /// duration in seconds
+    #[serde(deserialize_with = "null_as_default")]
+    pub duration: f64,
+    /// error
+    #[serde(deserialize_with = "null_as_default")]
+    pub error: String,
+    /// start time
+    #[serde(rename = "startTime", deserialize_with = "null_as_default")]
+    pub start_time: String,
+    /// end time, empty while running
+    #[serde(rename = "endTime", deserialize_with = "null_as_default")]
+    pub end_time: String,
+    /// stats group
+    #[serde(deserialize_with = "null_as_default")]
+    pub group: String,
+    /// unparsed response, for the detail view
+    #[serde(skip)]
+    pub raw: Value,
+}
+
+/// Remote of the rclone config
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RcRemote {
+    /// remote name
+    pub name: String,
+    /// backend type
+    pub backend_type: String,
+    /// description, empty if none
+    pub description: String,
+    /// extra config file of the remote, `None` for the main one
+    pub config_file: Option<String>,
+}
+
+/// Backend type of `config/providers`
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct RcProvider {
+    /// backend type, like `s3`
+    pub name: String,
+    /// description
+    pub description: String,
+    /// options of the backend
+    pub options: Vec<RcProviderOption>,
+}
+
+/// Option of a backend type
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct RcProviderOption {
+    /// option name, the key of the remote parameters
+    pub name: String,
+    /// help, its first line is a summary
+    pub help: String,
+    /// default value, as a string
+    pub default_str: String,
+    /// the remote needs it
+    pub required: bool,
+    /// obscured in the config
+    pub is_password: bool,
+    /// only shown in the advanced config of rclone
+    pub advanced: bool,
+    /// hidden from the config, non zero if hidden
+    pub hide: i64,
+}
+
+/// Server started by `serve/start`
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RcServe {
+    /// server id, used to stop it
+    pub id: String,
+    /// listening address
+    pub addr: String,
+}
+
+/// Transfer stats of a stats group
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RcStats {
+    /// transferred bytes
+    #[serde(deserialize_with = "null_as_default")]
+    pub bytes: u64,
+    /// total bytes to transfer
+    #[serde(deserialize_with = "null_as_default")]
+    pub total_bytes: u64,
+    /// speed in bytes per second
+    #[serde(deserialize_with = "null_as_default")]
+    pub speed: f64,
+    /// number of transferred files
+    #[serde(deserialize_with = "null_as_default")]
+    pub transfers: u64,
+    /// estimated time remaining in seconds
+    pub eta: Option<f64>,
+    /// files being transferred
+    #[serde(deserialize_with = "null_as_default")]
+    pub transferring: Vec<RcTransfer>,
+}
+
+/// File being transferred, from `core/stats`
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RcTransfer {
+    /// file name
+    pub name: String,
+    /// file size in bytes
+    #[serde(deserialize_with = "null_as_default")]
+    pub size: u64,
+    /// transferred bytes
+    #[serde(deserialize_with = "null_as_default")]
+    pub bytes: u64,
+    /// transferred percent
+    #[serde(deserialize_with = "null_as_default")]
+    pub percentage: u8,
+    /// speed in bytes per second
+    #[serde(deserialize_with = "null_as_default")]
+    pub speed: f64,
+}
+
+/// Item of a directory listing
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, rename_all = "PascalCase")]
+pub struct RcListItem {
+    /// path relative to the listed fs
+    pub path: String,
+    /// file name
+    pub name: String,
+    /// size in bytes, -1 if unknown
+    pub size: i64,
+    /// mime type
+    pub mime_type: String,
+    /// modification time
+    pub mod_time: String,
+    /// is a directory
+    pub is_dir: bool,
+    /// hashes by hash type, when listed with `showHash`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub hashes: BTreeMap<String, String>,
+}
+
+/// Result of `operations/check`
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RcCheck {
+    /// are the src and dest identical
+    pub success: bool,
+    /// error status of the check
+    pub status: String,
+    /// hash type used to compare the files
+    pub hash_type: String,
+    /// every file, prefixed with `=` (same), `-` (missing on dest), `+` (missing on src), `*` (differ) or `!` (error)
+    pub combined: Vec<String>,
+}
+
+/// Result of `operations/size`
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RcSize {
+    /// number of files
+    pub count: i64,
+    /// total size in bytes
+    pub bytes: i64,
+    /// number of files without a known size
+    pub sizeless: i64,
+}
+
+/// Result of `operations/about`, the fields unknown by the backend are missing
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RcAbout {
+    /// quota in bytes
+    pub total: Option<i64>,
+    /// used bytes
+    pub used: Option<i64>,
+    /// free bytes
+    pub free: Option<i64>,
+}
+
+/// Deserialize a `null` as the default value
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
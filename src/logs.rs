@@ -0,0 +1,83 @@
+//! Log files of the remotes, rotated when they grow
+
+use home::home_dir;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::errors::GalionError;
+
+/// Log file of a remote
+#[derive(Debug, Clone)]
+pub(crate) struct RemoteLog {
+    /// path of the current log file
+    path: PathBuf,
+}
+
+impl RemoteLog {
+    /// Size above which the log file is rotated
+    const MAX_SIZE: u64 = 1024 * 1024;
+
+    /// Number of rotated files kept, `photos.log.1` being the newest
+    const KEEP: usize = 3;
+
+    /// Default directory of the logs: `$XDG_STATE_HOME/galion/logs` or `~/.local/state/galion/logs`
+    pub fn default_dir() -> Option<PathBuf> {
+        let state_dir = std::env::var_os("XDG_STATE_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| home_dir().map(|home| home.join(".local").join("state")))?;
+        Some(state_dir.join("galion").join("logs"))
+    }
+
+    /// Log file of a remote, `path` if set, else `<remote>.log` in `dir` or in the default directory
+    pub fn new(remote_name: &str, path: Option<&str>, dir: Option<&Path>) -> Option<Self> {
+        if let Some(path) = path {
+            return Some(Self {
+                path: PathBuf::from(path),
+            });
+        }
+        let dir = dir.map(Path::to_path_buf).or_else(Self::default_dir)?;
+        let file_name = format!("{}.log", remote_name.replace(['/', '\\'], "_"));
+        Some(Self {
+            path: dir.join(file_name),
+        })
+    }
+
+    /// Path of a rotated file
+    fn rotated(&self, idx: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{idx}"));
+        PathBuf::from(path)
+    }
+
+    /// Shift the rotated files and start a new log file
+    fn rotate(&self) -> Result<(), GalionError> {
+        for idx in (1..Self::KEEP).rev() {
+            let from = self.rotated(idx);
+            if from.exists() {
+                std::fs::rename(from, self.rotated(idx + 1))?;
+            }
+        }
+        std::fs::rename(&self.path, self.rotated(1))?;
+        Ok(())
+    }
+
+    /// Append a line, rotating the file if it is too big
+    /// # Errors
+    /// Fails if the file cannot be written
+    pub fn append(&self, line: &str) -> Result<(), GalionError> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        if std::fs::metadata(&self.path).is_ok_and(|metadata| metadata.len() > Self::MAX_SIZE) {
+            self.rotate()?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{line}")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,633 @@
+//! Notifications of the job lifecycle events
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::app::GalionConfig;
+use crate::browser::format_size;
+use crate::errors::GalionError;
+use crate::jobs::{JobState, SyncJobData};
+
+/// Lifecycle event of a job
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct JobEvent {
+    /// `started`, `success` or `failure`
+    pub(crate) event: &'static str,
+    /// remote name
+    pub(crate) remote: String,
+    /// job src
+    pub(crate) src: String,
+    /// job dest
+    pub(crate) dest: String,
+    /// error, empty if none
+    pub(crate) error: String,
+    /// transferred bytes
+    pub(crate) bytes: u64,
+    /// transferred files
+    pub(crate) files: u64,
+    /// duration in seconds
+    pub(crate) duration: f64,
+    /// is it a sync of the remote, not an operation of the explorer
+    #[serde(skip)]
+    pub(crate) is_remote_sync: bool,
+}
+
+impl JobEvent {
+    /// Event of a job entering a state, `None` for the states without event
+    pub fn new(job_data: &SyncJobData, job_state: &JobState) -> Option<Self> {
+        let mut event = Self {
+            event: "started",
+            remote: job_data.name.clone(),
+            src: job_data.src.clone(),
+            dest: job_data.dest.clone(),
+            error: String::new(),
+            bytes: 0,
+            files: 0,
+            duration: 0.0,
+            is_remote_sync: job_data.is_sync(),
+        };
+        match job_state {
+            JobState::Sent => {}
+            JobState::Queued
+            | JobState::Offline(_)
+            | JobState::Retrying(_)
+            | JobState::Pending(_, _)
+            | JobState::Cancelled => return None,
+            JobState::Done(job_status, stats) => {
+                event.event = if job_status.success {
+                    "success"
+                } else {
+                    "failure"
+                };
+                event.error.clone_from(&job_status.error);
+                event.bytes = stats.bytes;
+                event.files = stats.transfers;
+                event.duration = job_status.duration;
+            }
+            JobState::Failed(_) | JobState::TimedOut(_) => {
+                event.event = "failure";
+                event.error = job_state.error();
+            }
+        }
+        Some(event)
+    }
+}
+
+/// MQTT broker settings, the events are published without TLS with `QoS` 0
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MqttConfig {
+    /// broker host
+    pub(crate) host: String,
+    /// broker port
+    #[serde(default = "MqttConfig::default_port")]
+    pub(crate) port: u16,
+    /// topic prefix, the events are published on `<topic>/<remote>`
+    #[serde(default = "MqttConfig::default_topic")]
+    pub(crate) topic: String,
+    /// client id
+    #[serde(default = "MqttConfig::default_client_id")]
+    pub(crate) client_id: String,
+    /// user name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) username: Option<String>,
+    /// password
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) password: Option<String>,
+    /// ask the broker to keep the last event of each topic
+    #[serde(default)]
+    pub(crate) retain: bool,
+}
+
+/// Append a MQTT string, prefixed with its length
+fn push_mqtt_string(packet: &mut Vec<u8>, value: &str) -> Result<(), GalionError> {
+    let len = u16::try_from(value.len()).map_err(|_| "MQTT string too long")?;
+    packet.extend(len.to_be_bytes());
+    packet.extend(value.as_bytes());
+    Ok(())
+}
+
+/// Build a MQTT packet from its first byte and its body
+fn mqtt_packet(header: u8, body: &[u8]) -> Vec<u8> {
+    let mut packet = vec![header];
+    // the remaining length is encoded 7 bits at a time
+    let mut len = body.len();
+    loop {
+        let mut byte = u8::try_from(len % 128).unwrap_or_default();
+        len /= 128;
+        if len > 0 {
+            byte |= 0x80;
+        }
+        packet.push(byte);
+        if len == 0 {
+            break;
+        }
+    }
+    packet.extend(body);
+    packet
+}
+
+impl MqttConfig {
+    /// Timeout of the connection to the broker
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// Default for [`MqttConfig::port`]
+    fn default_port() -> u16 {
+        1883
+    }
+
+    /// Default for [`MqttConfig::topic`]
+    fn default_topic() -> String {
+        "galion".to_string()
+    }
+
+    /// Default for [`MqttConfig::client_id`]
+    fn default_client_id() -> String {
+        "galion".to_string()
+    }
+
+    /// `CONNECT` packet
+    fn connect_packet(&self) -> Result<Vec<u8>, GalionError> {
+        let mut body = Vec::new();
+        push_mqtt_string(&mut body, "MQTT")?;
+        // protocol level 4 is MQTT 3.1.1
+        body.push(4);
+        let mut flags = 0x02; // clean session
+        if self.username.is_some() {
+            flags |= 0x80;
+        }
+        if self.password.is_some() {
+            flags |= 0x40;
+        }
+        body.push(flags);
+        body.extend(60u16.to_be_bytes()); // keep alive
+        push_mqtt_string(&mut body, &self.client_id)?;
+        if let Some(username) = &self.username {
+            push_mqtt_string(&mut body, username)?;
+        }
+        if let Some(password) = &self.password {
+            push_mqtt_string(&mut body, password)?;
+        }
+        Ok(mqtt_packet(0x10, &body))
+    }
+
+    /// Publish a job event on the topic of its remote
+    /// # Errors
+    /// Fails if the broker cannot be reached or refuses the connection
+    pub fn publish(&self, event: &JobEvent) -> Result<(), GalionError> {
+        let mut body = Vec::new();
+        push_mqtt_string(&mut body, &format!("{}/{}", self.topic, event.remote))?;
+        body.extend(serde_json::to_vec(event)?);
+        let publish = mqtt_packet(0x30 | u8::from(self.retain), &body);
+
+        let addr = (self.host.as_str(), self.port)
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| format!("cannot resolve the MQTT broker {}", self.host))?;
+        let mut stream = TcpStream::connect_timeout(&addr, Self::TIMEOUT)?;
+        stream.set_read_timeout(Some(Self::TIMEOUT))?;
+        stream.set_write_timeout(Some(Self::TIMEOUT))?;
+        stream.write_all(&self.connect_packet()?)?;
+        let mut connack = [0u8; 4];
+        stream.read_exact(&mut connack)?;
+        if connack[0] != 0x20 || connack[3] != 0 {
+            return Err(format!("MQTT connection refused (code {})", connack[3]).into());
+        }
+        stream.write_all(&publish)?;
+        stream.write_all(&mqtt_packet(0xE0, &[]))?; // disconnect
+        Ok(())
+    }
+}
+
+/// Format of the body of a webhook
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookKind {
+    /// the event as JSON
+    #[default]
+    Generic,
+    /// Slack message with a colored attachment
+    Slack,
+    /// Discord message with a colored embed
+    Discord,
+}
+
+/// Webhook receiving the job events
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WebhookConfig {
+    /// url receiving a POST request for each event
+    pub(crate) url: String,
+    /// format of the body
+    #[serde(default)]
+    pub(crate) kind: WebhookKind,
+    /// message of the Slack and Discord webhooks, with placeholders like `{remote}`, `{bytes}` or `{duration}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) template: Option<String>,
+}
+
+impl WebhookConfig {
+    /// Default message of the Slack and Discord webhooks
+    const DEFAULT_TEMPLATE: &str =
+        "galion {event}: {remote} ({src} -> {dest}) {bytes} in {duration} {error}";
+
+    /// Timeout of the requests
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Render the template of the webhook
+    fn message(&self, event: &JobEvent) -> String {
+        let template = self.template.as_deref().unwrap_or(Self::DEFAULT_TEMPLATE);
+        let placeholders = [
+            ("{event}", event.event.to_string()),
+            ("{remote}", event.remote.clone()),
+            ("{src}", event.src.clone()),
+            ("{dest}", event.dest.clone()),
+            ("{bytes}", format_size(event.bytes)),
+            ("{files}", event.files.to_string()),
+            ("{duration}", format!("{:.1}s", event.duration)),
+            ("{error}", event.error.clone()),
+        ];
+        placeholders
+            .iter()
+            .fold(template.to_string(), |message, (placeholder, value)| {
+                message.replace(placeholder, value)
+            })
+            .trim()
+            .to_string()
+    }
+
+    /// Body of the request
+    fn body(&self, event: &JobEvent) -> Result<Value, GalionError> {
+        // green, red or blue
+        let color = match event.event {
+            "success" => 0x002e_cc71,
+            "failure" => 0x00e7_4c3c,
+            _ => 0x0034_98db,
+        };
+        let message = self.message(event);
+        Ok(match self.kind {
+            WebhookKind::Generic => serde_json::to_value(event)?,
+            WebhookKind::Slack => json!({
+                "attachments": [{
+                    "color": format!("#{color:06x}"),
+                    "fallback": message,
+                    "text": message,
+                }],
+            }),
+            WebhookKind::Discord => json!({
+                "embeds": [{
+                    "description": message,
+                    "color": color,
+                }],
+            }),
+        })
+    }
+
+    /// Send a job event to the webhook
+    /// # Errors
+    /// Fails if the request fails
+    pub fn send(&self, agent: &ureq::Agent, event: &JobEvent) -> Result<(), GalionError> {
+        agent
+            .post(&self.url)
+            .send_json(self.body(event)?)
+            .map_err(|e| format!("webhook {} failed: {e}", self.url))?;
+        Ok(())
+    }
+}
+
+/// Ping a healthchecks.io style url: `/start` when a job starts, `/fail` when it fails
+/// # Errors
+/// Fails if the request fails
+fn ping(agent: &ureq::Agent, url: &str, event: &JobEvent) -> Result<(), GalionError> {
+    let url = url.trim_end_matches('/');
+    let url = match event.event {
+        "started" => format!("{url}/start"),
+        "failure" => format!("{url}/fail"),
+        _ => url.to_string(),
+    };
+    // the body is kept in the logs of the check
+    agent
+        .post(&url)
+        .send(event.error.as_str())
+        .map_err(|e| format!("ping {url} failed: {e}"))?;
+    Ok(())
+}
+
+/// Notifications of a job event, taken from the config when the event happens
+#[derive(Debug, Clone, Default)]
+struct Notifications {
+    /// MQTT broker
+    mqtt: Option<MqttConfig>,
+    /// webhooks
+    webhooks: Vec<WebhookConfig>,
+    /// healthchecks.io style url of the remote
+    ping_url: Option<String>,
+}
+
+impl Notifications {
+    /// Notifications of an event, only the syncs of the remotes are notified
+    fn new(config: &GalionConfig, event: &JobEvent) -> Self {
+        if !event.is_remote_sync {
+            return Self::default();
+        }
+        Self {
+            mqtt: config.mqtt.clone(),
+            webhooks: config.webhooks.clone(),
+            ping_url: config
+                .remotes()
+                .iter()
+                .find(|remote| remote.remote_name == event.remote)
+                .and_then(|remote| remote.ping_url.clone()),
+        }
+    }
+
+    /// Is there nothing to notify
+    fn is_empty(&self) -> bool {
+        self.mqtt.is_none() && self.webhooks.is_empty() && self.ping_url.is_none()
+    }
+
+    /// Send a job event to the notifications, returns the errors
+    fn send(&self, agent: &ureq::Agent, event: &JobEvent) -> Vec<String> {
+        let mut errors = Vec::new();
+        if let Some(mqtt) = &self.mqtt
+            && let Err(e) = mqtt.publish(event)
+        {
+            errors.push(format!("MQTT notification of {} failed: {e}", event.remote));
+        }
+        for webhook in &self.webhooks {
+            if let Err(e) = webhook.send(agent, event) {
+                errors.push(format!("Notification of {} failed: {e}", event.remote));
+            }
+        }
+        if let Some(ping_url) = &self.ping_url
+            && let Err(e) = ping(agent, ping_url, event)
+        {
+            errors.push(format!("Ping of {} failed: {e}", event.remote));
+        }
+        errors
+    }
+}
+
+/// Message of the notifier thread
+enum NotifierMessage {
+    /// job event to send
+    Event(Box<JobEvent>, Box<Notifications>),
+    /// answered once the previous events are sent
+    Flush(Sender<()>),
+}
+
+/// Thread sending the job events, a broker or a webhook that is down doesn't stall the jobs
+#[derive(Debug)]
+pub(crate) struct Notifier {
+    /// messages to the thread, closed when the notifier is dropped
+    tx: Option<Sender<NotifierMessage>>,
+    /// errors of the sent events
+    errors: Receiver<String>,
+    /// notifier thread, joined when the notifier is dropped
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Notifier {
+    /// Start the notifier thread
+    pub(crate) fn new() -> Self {
+        let (tx, rx) = mpsc::channel::<NotifierMessage>();
+        let (tx_errors, errors) = mpsc::channel();
+        let thread = thread::spawn(move || {
+            let agent = ureq::Agent::config_builder()
+                .timeout_global(Some(WebhookConfig::TIMEOUT))
+                .build()
+                .new_agent();
+            for message in rx {
+                match message {
+                    NotifierMessage::Event(event, notifications) => {
+                        for error in notifications.send(&agent, &event) {
+                            if tx_errors.send(error).is_err() {
+                                // the notifier is dropped
+                            }
+                        }
+                    }
+                    NotifierMessage::Flush(done) => {
+                        if done.send(()).is_err() {
+                            // nobody waits anymore
+                        }
+                    }
+                }
+            }
+        });
+        Self {
+            tx: Some(tx),
+            errors,
+            thread: Some(thread),
+        }
+    }
+
+    /// Queue a job event for the configured notifications
+    pub(crate) fn send(&self, config: &GalionConfig, event: &JobEvent) {
+        let notifications = Notifications::new(config, event);
+        if notifications.is_empty() {
+            return;
+        }
+        if let Some(tx) = &self.tx
+            && tx
+                .send(NotifierMessage::Event(
+                    Box::new(event.clone()),
+                    Box::new(notifications),
+                ))
+                .is_err()
+        {
+            // the thread panicked, the event is lost
+        }
+    }
+
+    /// Errors of the events sent since the last call
+    pub(crate) fn errors(&self) -> Vec<String> {
+        self.errors.try_iter().collect()
+    }
+
+    /// Wait for the queued events to be sent, returns the errors
+    pub(crate) fn flush(&self) -> Vec<String> {
+        let (done, wait) = mpsc::channel();
+        if let Some(tx) = &self.tx
+            && tx.send(NotifierMessage::Flush(done)).is_ok()
+            && wait.recv().is_err()
+        {
+            // the thread panicked
+        }
+        self.errors()
+    }
+}
+
+impl Drop for Notifier {
+    fn drop(&mut self) {
+        // the thread sends the queued events, then exits
+        self.tx = None;
+        if let Some(thread) = self.thread.take()
+            && thread.join().is_err()
+        {
+            // the thread panicked
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::remote::RemoteConfiguration;
+
+    /// Broker with credentials
+    fn mqtt_config(username: Option<&str>, password: Option<&str>) -> MqttConfig {
+        MqttConfig {
+            host: "localhost".to_string(),
+            port: MqttConfig::default_port(),
+            topic: MqttConfig::default_topic(),
+            client_id: "id".to_string(),
+            username: username.map(str::to_string),
+            password: password.map(str::to_string),
+            retain: false,
+        }
+    }
+
+    /// Finished sync of the `photos` remote
+    fn event(event: &'static str, is_remote_sync: bool) -> JobEvent {
+        JobEvent {
+            event,
+            remote: "photos".to_string(),
+            src: "/photos".to_string(),
+            dest: "nas:photos".to_string(),
+            error: String::new(),
+            bytes: 2048,
+            files: 3,
+            duration: 12.34,
+            is_remote_sync,
+        }
+    }
+
+    /// Webhook of a kind
+    fn webhook(kind: WebhookKind, template: Option<&str>) -> WebhookConfig {
+        WebhookConfig {
+            url: "http://localhost/hook".to_string(),
+            kind,
+            template: template.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_mqtt_remaining_length() {
+        // 7 bits per byte, the high bit tells that another byte follows
+        for (len, encoded) in [
+            (0, vec![0x00]),
+            (127, vec![0x7F]),
+            (128, vec![0x80, 0x01]),
+            (16_383, vec![0xFF, 0x7F]),
+            (16_384, vec![0x80, 0x80, 0x01]),
+        ] {
+            let packet = mqtt_packet(0x30, &vec![0; len]);
+            assert_eq!(packet[0], 0x30);
+            assert_eq!(packet[1..=encoded.len()], encoded, "length {len}");
+            assert_eq!(packet.len(), 1 + encoded.len() + len);
+        }
+    }
+
+    #[test]
+    fn test_mqtt_connect_flags() {
+        let flags = |config: &MqttConfig| {
+            let packet = config
+                .connect_packet()
+                .unwrap_or_else(|e| panic!("invalid packet: {e}"));
+            // header, length, "MQTT" with its length, protocol level
+            assert_eq!(
+                packet[..9],
+                [
+                    0x10,
+                    u8::try_from(packet.len() - 2).unwrap_or_default(),
+                    0,
+                    4,
+                    b'M',
+                    b'Q',
+                    b'T',
+                    b'T',
+                    4
+                ]
+            );
+            packet[9]
+        };
+        assert_eq!(flags(&mqtt_config(None, None)), 0x02);
+        assert_eq!(flags(&mqtt_config(Some("user"), None)), 0x82);
+        assert_eq!(flags(&mqtt_config(Some("user"), Some("pass"))), 0xC2);
+        let packet = mqtt_config(Some("user"), Some("pass"))
+            .connect_packet()
+            .unwrap_or_default();
+        assert!(packet.ends_with(&[
+            0, 2, b'i', b'd', 0, 4, b'u', b's', b'e', b'r', 0, 4, b'p', b'a', b's', b's'
+        ]));
+    }
+
+    #[test]
+    fn test_webhook_message() {
+        let mut failure = event("failure", true);
+        failure.error = "quota exceeded".to_string();
+        assert_eq!(
+            webhook(WebhookKind::Slack, None).message(&failure),
+            "galion failure: photos (/photos -> nas:photos) 2.0 KiB in 12.3s quota exceeded"
+        );
+        // no trailing space without an error
+        assert_eq!(
+            webhook(WebhookKind::Slack, None).message(&event("success", true)),
+            "galion success: photos (/photos -> nas:photos) 2.0 KiB in 12.3s"
+        );
+        assert_eq!(
+            webhook(
+                WebhookKind::Discord,
+                Some("{remote}: {files} files, {unknown}")
+            )
+            .message(&event("success", true)),
+            "photos: 3 files, {unknown}"
+        );
+    }
+
+    #[test]
+    fn test_webhook_body() {
+        let body = |kind| {
+            webhook(kind, None)
+                .body(&event("failure", true))
+                .unwrap_or_else(|e| panic!("invalid body: {e}"))
+        };
+        let generic = body(WebhookKind::Generic);
+        assert_eq!(generic["event"], "failure");
+        assert_eq!(generic["bytes"], 2048);
+        assert!(generic.get("is_remote_sync").is_none());
+        assert_eq!(
+            body(WebhookKind::Slack)["attachments"][0]["color"],
+            "#e74c3c"
+        );
+        assert_eq!(
+            body(WebhookKind::Discord)["embeds"][0]["color"],
+            0x00e7_4c3c
+        );
+    }
+
+    #[test]
+    fn test_only_the_remote_syncs_are_notified() {
+        let config = GalionConfig {
+            mqtt: Some(mqtt_config(None, None)),
+            webhooks: vec![webhook(WebhookKind::Generic, None)],
+            remote_configurations: vec![RemoteConfiguration {
+                remote_name: "photos".to_string(),
+                ping_url: Some("http://localhost/ping".to_string()),
+                ..RemoteConfiguration::default()
+            }],
+            ..GalionConfig::default()
+        };
+        let notifications = Notifications::new(&config, &event("started", true));
+        assert!(notifications.mqtt.is_some());
+        assert_eq!(notifications.webhooks.len(), 1);
+        assert_eq!(
+            notifications.ping_url.as_deref(),
+            Some("http://localhost/ping")
+        );
+        assert!(Notifications::new(&config, &event("started", false)).is_empty());
+    }
+}
@@ -0,0 +1,309 @@
+//! Rclone options of the syncs of a remote
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::errors::GalionError;
+
+/// Preset of transfer options
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TransferPreset {
+    /// more retries and longer timeouts, for unreliable connections
+    FlakyLink,
+    /// bigger chunks, for big files
+    LargeFiles,
+}
+
+impl TransferPreset {
+    /// Rclone options of the preset
+    fn config(self) -> Value {
+        match self {
+            Self::FlakyLink => json!({
+                "Retries": 10,
+                "LowLevelRetries": 20,
+                "Timeout": "10m",
+                "ConnectTimeout": "1m",
+                "MultiThreadChunkSize": "8Mi",
+                "PartialSuffix": ".partial",
+            }),
+            Self::LargeFiles => json!({
+                "Timeout": "30m",
+                "MultiThreadChunkSize": "64Mi",
+            }),
+        }
+    }
+}
+
+impl Display for TransferPreset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FlakyLink => write!(f, "flaky-link"),
+            Self::LargeFiles => write!(f, "large-files"),
+        }
+    }
+}
+
+impl FromStr for TransferPreset {
+    type Err = GalionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "flaky-link" => Ok(Self::FlakyLink),
+            "large-files" => Ok(Self::LargeFiles),
+            _ => Err(format!("unknown preset '{s}' - use flaky-link or large-files").into()),
+        }
+    }
+}
+
+/// Rclone options of the syncs of a remote, passed as `_config`
+///
+/// The options that are set override the ones of the presets
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SyncOptions {
+    /// transfer preset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preset: Option<TransferPreset>,
+    /// number of retries of a failed sync
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+    /// number of retries of a failed low level operation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub low_level_retries: Option<u32>,
+    /// IO idle timeout, like `5m`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<String>,
+    /// chunk size of the multi-thread transfers, like `64Mi`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multi_thread_chunk_size: Option<String>,
+    /// suffix of the partially transferred files
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub partial_suffix: Option<String>,
+    /// write the files in place, without a partial file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inplace: Option<bool>,
+    /// fast preset: 8 parallel streams for the files bigger than 64Mi
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fast: Option<bool>,
+    /// number of streams of the multi-thread transfers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multi_thread_streams: Option<u32>,
+    /// minimum size of the files to transfer with multiple threads, like `256Mi`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub multi_thread_cutoff: Option<String>,
+    /// number of parallel file transfers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transfers: Option<u32>,
+    /// compare the files by checksum instead of modification time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<bool>,
+    /// bandwidth limit, like `10M` or a timetable like `08:00,512k 19:00,off`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bwlimit: Option<String>,
+    /// only show what would be transferred
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dry_run: Option<bool>,
+    /// skip the files that already exist on the destination
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignore_existing: Option<bool>,
+    /// never modify the files of the destination, a changed file is an error
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub immutable: Option<bool>,
+    /// create the empty directories of the source on the destination, a sync parameter and not a `_config` option
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub create_empty_src_dirs: Option<bool>,
+    /// copy the metadata of the files, like the permissions and the owner
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<bool>,
+    /// don't update the modification time of the files already on the destination
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_update_modtime: Option<bool>,
+}
+
+/// Parse an optional form value
+fn parse_field<T: FromStr>(label: &str, value: &str) -> Result<Option<T>, GalionError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    value
+        .parse()
+        .map(Some)
+        .map_err(|_| format!("invalid value '{value}' for {label}").into())
+}
+
+/// Value of a form field
+fn display_field<T: Display>(value: Option<&T>) -> String {
+    value.map(ToString::to_string).unwrap_or_default()
+}
+
+impl SyncOptions {
+    /// Labels of the form fields
+    pub const FIELDS: [&str; 19] = [
+        "Transfer preset (flaky-link, large-files)",
+        "Retries",
+        "Low level retries",
+        "Timeout (like 5m)",
+        "Multi-thread chunk size (like 64Mi)",
+        "Partial suffix",
+        "In place (true/false)",
+        "Fast preset (true/false)",
+        "Multi-thread streams",
+        "Multi-thread cutoff (like 256Mi)",
+        "Transfers",
+        "Checksum (true/false)",
+        "Bandwidth limit (like 10M)",
+        "Dry run (true/false)",
+        "Ignore existing (true/false)",
+        "Immutable (true/false)",
+        "Create empty source dirs (true/false)",
+        "Copy metadata (true/false)",
+        "No modtime update (true/false)",
+    ];
+
+    /// Are all the options unset
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Values of the form fields
+    pub fn field_values(&self) -> [String; 19] {
+        [
+            display_field(self.preset.as_ref()),
+            display_field(self.retries.as_ref()),
+            display_field(self.low_level_retries.as_ref()),
+            display_field(self.timeout.as_ref()),
+            display_field(self.multi_thread_chunk_size.as_ref()),
+            display_field(self.partial_suffix.as_ref()),
+            display_field(self.inplace.as_ref()),
+            display_field(self.fast.as_ref()),
+            display_field(self.multi_thread_streams.as_ref()),
+            display_field(self.multi_thread_cutoff.as_ref()),
+            display_field(self.transfers.as_ref()),
+            display_field(self.checksum.as_ref()),
+            display_field(self.bwlimit.as_ref()),
+            display_field(self.dry_run.as_ref()),
+            display_field(self.ignore_existing.as_ref()),
+            display_field(self.immutable.as_ref()),
+            display_field(self.create_empty_src_dirs.as_ref()),
+            display_field(self.metadata.as_ref()),
+            display_field(self.no_update_modtime.as_ref()),
+        ]
+    }
+
+    /// Options from the form fields
+    /// # Errors
+    /// Fails if a value is invalid
+    pub fn from_fields(values: &[&str]) -> Result<Self, GalionError> {
+        let value = |idx: usize| values.get(idx).copied().unwrap_or_default();
+        let preset = match value(0).trim() {
+            "" => None,
+            preset => Some(preset.parse()?),
+        };
+        Ok(Self {
+            preset,
+            retries: parse_field(Self::FIELDS[1], value(1))?,
+            low_level_retries: parse_field(Self::FIELDS[2], value(2))?,
+            timeout: parse_field(Self::FIELDS[3], value(3))?,
+            multi_thread_chunk_size: parse_field(Self::FIELDS[4], value(4))?,
+            partial_suffix: parse_field(Self::FIELDS[5], value(5))?,
+            inplace: parse_field(Self::FIELDS[6], value(6))?,
+            fast: parse_field(Self::FIELDS[7], value(7))?,
+            multi_thread_streams: parse_field(Self::FIELDS[8], value(8))?,
+            multi_thread_cutoff: parse_field(Self::FIELDS[9], value(9))?,
+            transfers: parse_field(Self::FIELDS[10], value(10))?,
+            checksum: parse_field(Self::FIELDS[11], value(11))?,
+            bwlimit: parse_field(Self::FIELDS[12], value(12))?,
+            dry_run: parse_field(Self::FIELDS[13], value(13))?,
+            ignore_existing: parse_field(Self::FIELDS[14], value(14))?,
+            immutable: parse_field(Self::FIELDS[15], value(15))?,
+            create_empty_src_dirs: parse_field(Self::FIELDS[16], value(16))?,
+            metadata: parse_field(Self::FIELDS[17], value(17))?,
+            no_update_modtime: parse_field(Self::FIELDS[18], value(18))?,
+        })
+    }
+
+    /// Options with the unset ones taken from the defaults
+    pub fn with_defaults(&self, defaults: &Self) -> Self {
+        let or = |value: &Option<String>, default: &Option<String>| {
+            value.as_ref().or(default.as_ref()).cloned()
+        };
+        Self {
+            preset: self.preset.or(defaults.preset),
+            retries: self.retries.or(defaults.retries),
+            low_level_retries: self.low_level_retries.or(defaults.low_level_retries),
+            timeout: or(&self.timeout, &defaults.timeout),
+            multi_thread_chunk_size: or(
+                &self.multi_thread_chunk_size,
+                &defaults.multi_thread_chunk_size,
+            ),
+            partial_suffix: or(&self.partial_suffix, &defaults.partial_suffix),
+            inplace: self.inplace.or(defaults.inplace),
+            fast: self.fast.or(defaults.fast),
+            multi_thread_streams: self.multi_thread_streams.or(defaults.multi_thread_streams),
+            multi_thread_cutoff: or(&self.multi_thread_cutoff, &defaults.multi_thread_cutoff),
+            transfers: self.transfers.or(defaults.transfers),
+            checksum: self.checksum.or(defaults.checksum),
+            bwlimit: or(&self.bwlimit, &defaults.bwlimit),
+            dry_run: self.dry_run.or(defaults.dry_run),
+            ignore_existing: self.ignore_existing.or(defaults.ignore_existing),
+            immutable: self.immutable.or(defaults.immutable),
+            create_empty_src_dirs: self
+                .create_empty_src_dirs
+                .or(defaults.create_empty_src_dirs),
+            metadata: self.metadata.or(defaults.metadata),
+            no_update_modtime: self.no_update_modtime.or(defaults.no_update_modtime),
+        }
+    }
+
+    /// Rclone `_config` of the options, `None` if there is no option
+    pub fn to_config(&self) -> Option<Map<String, Value>> {
+        let mut config = match self.preset.map(TransferPreset::config) {
+            Some(Value::Object(config)) => config,
+            _ => Map::new(),
+        };
+        if self.fast == Some(true) {
+            config.insert("MultiThreadStreams".to_string(), json!(8));
+            config.insert("MultiThreadCutoff".to_string(), json!("64Mi"));
+        }
+        let options = [
+            ("Retries", self.retries.map(Value::from)),
+            ("LowLevelRetries", self.low_level_retries.map(Value::from)),
+            ("Timeout", self.timeout.clone().map(Value::from)),
+            (
+                "MultiThreadChunkSize",
+                self.multi_thread_chunk_size.clone().map(Value::from),
+            ),
+            (
+                "PartialSuffix",
+                self.partial_suffix.clone().map(Value::from),
+            ),
+            ("Inplace", self.inplace.map(Value::from)),
+            (
+                "MultiThreadStreams",
+                self.multi_thread_streams.map(Value::from),
+            ),
+            (
+                "MultiThreadCutoff",
+                self.multi_thread_cutoff.clone().map(Value::from),
+            ),
+            ("Transfers", self.transfers.map(Value::from)),
+            ("CheckSum", self.checksum.map(Value::from)),
+            ("BwLimit", self.bwlimit.clone().map(Value::from)),
+            ("DryRun", self.dry_run.map(Value::from)),
+            ("IgnoreExisting", self.ignore_existing.map(Value::from)),
+            ("Immutable", self.immutable.map(Value::from)),
+            ("Metadata", self.metadata.map(Value::from)),
+            ("NoUpdateModTime", self.no_update_modtime.map(Value::from)),
+        ];
+        for (name, value) in options {
+            if let Some(value) = value {
+                config.insert(name.to_string(), value);
+            }
+        }
+        Some(config).filter(|config| !config.is_empty())
+    }
+}
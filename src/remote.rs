@@ -1,8 +1,21 @@
 //! Remote configuration
 
 use std::fmt::Display;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
 
+use home::home_dir;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use time::{OffsetDateTime, macros::format_description};
+
+use crate::errors::GalionError;
+use crate::input::TextInput;
+use crate::librclone::rclone::{RcRemote, RcRemoteConfig};
+use crate::options::SyncOptions;
+use crate::report::report_date;
+use crate::schedule::Schedule;
 
 /// Config origin
 #[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq)]
@@ -12,6 +25,8 @@ pub enum ConfigOrigin {
     GalionConfig,
     /// from rclone config
     RcloneConfig,
+    /// galion config entry with the name of a rclone remote
+    Merged,
 }
 
 impl Display for ConfigOrigin {
@@ -19,145 +34,1101 @@ impl Display for ConfigOrigin {
         match self {
             Self::GalionConfig => write!(f, "galion config"),
             Self::RcloneConfig => write!(f, "rclone config"),
+            Self::Merged => write!(f, "galion + rclone config"),
+        }
+    }
+}
+
+/// Operation run by the jobs of a remote
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Operation {
+    /// one way sync of the source to the destination
+    #[default]
+    Sync,
+    /// copy of the source to the destination, nothing is deleted on the destination
+    Copy,
+    /// move of the source to the destination, the source files are deleted once transferred
+    Move,
+    /// two way sync between the source and the destination
+    Bisync,
+}
+
+impl Operation {
+    /// Is this the default operation
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sync => write!(f, "sync"),
+            Self::Copy => write!(f, "copy"),
+            Self::Move => write!(f, "move"),
+            Self::Bisync => write!(f, "bisync"),
+        }
+    }
+}
+
+impl FromStr for Operation {
+    type Err = GalionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "" | "sync" => Ok(Self::Sync),
+            "copy" => Ok(Self::Copy),
+            "move" => Ok(Self::Move),
+            "bisync" => Ok(Self::Bisync),
+            _ => Err(format!("unknown operation '{s}' - use sync, copy, move or bisync").into()),
+        }
+    }
+}
+
+/// Priority of the jobs of a remote in the queue
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    /// launched after the other jobs
+    Low,
+    /// default priority
+    #[default]
+    Normal,
+    /// launched before the other jobs, like the restores
+    High,
+}
+
+impl Priority {
+    /// Is this the default priority
+    #[allow(clippy::trivially_copy_pass_by_ref)] // signature of `skip_serializing_if`
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Low => write!(f, "low"),
+            Self::Normal => write!(f, "normal"),
+            Self::High => write!(f, "high"),
+        }
+    }
+}
+
+impl FromStr for Priority {
+    type Err = GalionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "low" => Ok(Self::Low),
+            "" | "normal" => Ok(Self::Normal),
+            "high" => Ok(Self::High),
+            _ => Err(format!("unknown priority '{s}' - use low, normal or high").into()),
+        }
+    }
+}
+
+/// Check of the free space of the destination before a sync
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpaceCheck {
+    /// no check
+    #[default]
+    Off,
+    /// warn when the destination would fill up
+    Warn,
+    /// refuse to launch the sync when the destination would fill up
+    Refuse,
+}
+
+impl SpaceCheck {
+    /// Is this the default check
+    #[allow(clippy::trivially_copy_pass_by_ref)] // signature of `skip_serializing_if`
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Display for SpaceCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Off => write!(f, "off"),
+            Self::Warn => write!(f, "warn"),
+            Self::Refuse => write!(f, "refuse"),
+        }
+    }
+}
+
+impl FromStr for SpaceCheck {
+    type Err = GalionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "" | "off" => Ok(Self::Off),
+            "warn" => Ok(Self::Warn),
+            "refuse" => Ok(Self::Refuse),
+            _ => Err(format!("unknown space check '{s}' - use off, warn or refuse").into()),
+        }
+    }
+}
+
+/// What to do when a sync of a remote is launched while another one is running
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OverlapPolicy {
+    /// wait in the queue for the running sync to finish
+    #[default]
+    Queue,
+    /// refuse the new sync
+    Refuse,
+    /// run both syncs at the same time
+    Allow,
+}
+
+impl OverlapPolicy {
+    /// Is this the default policy
+    #[allow(clippy::trivially_copy_pass_by_ref)] // signature of `skip_serializing_if`
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Display for OverlapPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Queue => write!(f, "queue"),
+            Self::Refuse => write!(f, "refuse"),
+            Self::Allow => write!(f, "allow"),
+        }
+    }
+}
+
+impl FromStr for OverlapPolicy {
+    type Err = GalionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "" | "queue" => Ok(Self::Queue),
+            "refuse" => Ok(Self::Refuse),
+            "allow" => Ok(Self::Allow),
+            _ => Err(format!("unknown overlap policy '{s}' - use queue, refuse or allow").into()),
+        }
+    }
+}
+
+/// Is a counter zero
+#[allow(clippy::trivially_copy_pass_by_ref)] // signature of `skip_serializing_if`
+fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
+/// Parse a duration like `90s`, `30m`, `2h` or `1h30m`, a number alone is in seconds
+/// # Errors
+/// Fails if the duration is invalid
+pub(crate) fn parse_duration(value: &str) -> Result<Duration, GalionError> {
+    let invalid = || GalionError::new(format!("invalid duration '{value}' - use like 1h30m"));
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Ok(Duration::from_secs(secs));
+    }
+    let mut secs = 0;
+    let mut number = String::new();
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            'd' => 86400,
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return Err(invalid()),
+        };
+        secs += number.parse::<u64>().map_err(|_| invalid())? * unit;
+        number.clear();
+    }
+    if !number.is_empty() || value.is_empty() {
+        return Err(invalid());
+    }
+    Ok(Duration::from_secs(secs))
+}
+
+/// Name of the machine, for the `{hostname}` placeholder
+fn hostname() -> String {
+    std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "localhost".to_string())
+}
+
+/// Expand the `{date}`, `{year}`, `{month}`, `{day}`, `{time}` and `{hostname}` placeholders of a path
+pub(crate) fn expand_placeholders(path: &str) -> String {
+    if !path.contains('{') {
+        return path.to_string();
+    }
+    let now = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
+    let format = |description| now.format(description).unwrap_or_default();
+    let placeholders = [
+        (
+            "{date}",
+            format(format_description!("[year]-[month]-[day]")),
+        ),
+        ("{year}", format(format_description!("[year]"))),
+        ("{month}", format(format_description!("[month]"))),
+        ("{day}", format(format_description!("[day]"))),
+        (
+            "{time}",
+            format(format_description!("[hour][minute][second]")),
+        ),
+        ("{hostname}", hostname()),
+    ];
+    placeholders
+        .iter()
+        .fold(path.to_string(), |path, (placeholder, value)| {
+            path.replace(placeholder, value)
+        })
+}
+
+/// Expand the environment variables of a path: `$NAME`, `${NAME}` and `%NAME%`, unknown variables are kept
+fn expand_env(path: &str) -> String {
+    let mut expanded = String::new();
+    let mut rest = path;
+    while let Some(idx) = rest.find(['$', '%']) {
+        expanded.push_str(&rest[..idx]);
+        let after = &rest[idx + 1..];
+        let (name, len) = if rest[idx..].starts_with('%') {
+            match after.split_once('%') {
+                Some((name, _)) => (name, name.len() + 2),
+                None => ("", 1),
+            }
+        } else if let Some(braced) = after.strip_prefix('{') {
+            match braced.split_once('}') {
+                Some((name, _)) => (name, name.len() + 3),
+                None => ("", 1),
+            }
+        } else {
+            let name_len = after
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .unwrap_or(after.len());
+            (&after[..name_len], name_len + 1)
+        };
+        match std::env::var(name).ok().filter(|_| !name.is_empty()) {
+            Some(value) => expanded.push_str(&value),
+            None => expanded.push_str(&rest[idx..idx + len]),
+        }
+        rest = &rest[idx + len..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+/// Expand a leading `~` and the environment variables of a path
+pub(crate) fn expand_path(path: &str) -> String {
+    let path = expand_env(path);
+    let home = || home_dir().map(|home| home.to_string_lossy().into_owned());
+    if path == "~" {
+        return home().unwrap_or(path);
+    }
+    match path.strip_prefix("~/").or_else(|| path.strip_prefix("~\\")) {
+        Some(rest) => match home() {
+            Some(home) => format!("{home}{}{rest}", std::path::MAIN_SEPARATOR),
+            None => path,
+        },
+        None => path,
+    }
+}
+
+/// Normalize a local Windows path: `c:/dir` is `C:\dir` and `//server/share` is `\\server\share`
+/// # Errors
+/// Fails for a drive-relative path like `C:dir` and for an UNC path without a share
+fn normalize_windows_path(path: &str) -> Result<String, GalionError> {
+    let backslashed = || path.replace('/', "\\");
+    if path.starts_with("\\\\") || path.starts_with("//") {
+        // extended paths like `\\?\C:\dir` are kept as is
+        let unc = backslashed();
+        let mut parts = unc[2..].split('\\').filter(|part| !part.is_empty());
+        if unc[2..].starts_with("?\\") || (parts.next().is_some() && parts.next().is_some()) {
+            return Ok(unc);
+        }
+        return Err(format!("invalid UNC path '{path}' - use \\\\server\\share").into());
+    }
+    let mut chars = path.chars();
+    match (chars.next(), chars.next(), chars.next()) {
+        (Some(drive), Some(':'), None) if drive.is_ascii_alphabetic() => {
+            Ok(format!("{}:\\", drive.to_ascii_uppercase()))
+        }
+        (Some(drive), Some(':'), Some('/' | '\\')) if drive.is_ascii_alphabetic() => Ok(format!(
+            "{}{}",
+            drive.to_ascii_uppercase(),
+            &backslashed()[1..]
+        )),
+        (Some(drive), Some(':'), Some(_)) if drive.is_ascii_alphabetic() => Err(format!(
+            "'{path}' is relative to the current directory of the drive - use {drive}:\\{}",
+            &path[2..]
+        )
+        .into()),
+        _ => Ok(path.to_string()),
+    }
+}
+
+/// Check and normalize a src or dest path for the local system
+/// # Errors
+/// Fails if the path is an invalid Windows path, or a Windows path on another system
+pub(crate) fn normalize_path(path: &str) -> Result<String, GalionError> {
+    if cfg!(windows) {
+        return normalize_windows_path(path);
+    }
+    let mut chars = path.chars();
+    if let (Some(drive), Some(':'), Some('\\')) = (chars.next(), chars.next(), chars.next())
+        && drive.is_ascii_alphabetic()
+    {
+        return Err(format!("'{path}' is a Windows path, only valid on Windows").into());
+    }
+    Ok(path.to_string())
+}
+
+/// A rclone path, either local or on a remote
+#[derive(Debug, Clone, PartialEq)]
+pub enum RclonePath<'a> {
+    /// local path
+    Local(&'a str),
+    /// path on a remote: name, path on the remote
+    Remote(&'a str, &'a str),
+    /// path on a backend created on the fly (`:backend:path`)
+    Backend(&'a str),
+}
+
+impl<'a> RclonePath<'a> {
+    /// Parse a rclone path
+    pub fn parse(path: &'a str) -> Self {
+        if path.starts_with(':') {
+            return Self::Backend(path);
+        }
+        let Some((name, remote_path)) = path.split_once(':') else {
+            return Self::Local(path);
+        };
+        // `C:\dir` is a drive on every system, `C:dir` only on Windows
+        let is_drive_letter = name.len() == 1
+            && name.chars().all(|c| c.is_ascii_alphabetic())
+            && (cfg!(windows) || remote_path.starts_with('\\'));
+        if name.is_empty() || name.contains(['/', '\\']) || is_drive_letter {
+            return Self::Local(path);
+        }
+        // connection string parameters: `remote,param=value:path`
+        let name = name.split_once(',').map_or(name, |(n, _)| n);
+        Self::Remote(name, remote_path)
+    }
+
+    /// Split a rclone path into the `fs` and the `remote` arguments of the operations
+    pub fn split_fs(path: &'a str) -> (&'a str, &'a str) {
+        match Self::parse(path) {
+            Self::Remote(_, remote_path) => (&path[..path.len() - remote_path.len()], remote_path),
+            Self::Local(local_path) => match local_path.rfind(['/', '\\']) {
+                Some(idx) => local_path.split_at(idx + 1),
+                None => (".", local_path),
+            },
+            Self::Backend(_) => (path, ""),
         }
     }
 }
 
 /// Remote Configuration
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq)]
 pub struct RemoteConfiguration {
     /// remote name in the config
     pub remote_name: String,
-    /// local path
+    /// local path, `~` and the environment variables are expanded when the jobs are launched
     pub remote_src: Option<String>,
-    /// remote path
+    /// remote path, `~`, the environment variables and placeholders like `{date}` are expanded when the jobs are launched
     pub remote_dest: Option<String>,
+    /// directory where the files deleted or overwritten by a sync are moved, in a dated subdirectory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_dir: Option<String>,
+    /// operation run by the jobs
+    #[serde(default, skip_serializing_if = "Operation::is_default")]
+    pub operation: Operation,
+    /// healthchecks.io style url, pinged at the start (`/start`), success and failure (`/fail`) of the jobs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ping_url: Option<String>,
+    /// priority of the jobs in the queue
+    #[serde(default, skip_serializing_if = "Priority::is_default")]
+    pub priority: Priority,
+    /// schedule of the syncs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<Schedule>,
+    /// group of remotes launched together
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// remote whose successful sync launches the sync of this one, like local to NAS then NAS to cloud
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+    /// the jobs running longer, like `2h`, are stopped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_duration: Option<String>,
+    /// check the free space of the destination before a sync
+    #[serde(default, skip_serializing_if = "SpaceCheck::is_default")]
+    pub space_check: SpaceCheck,
+    /// what to do when a sync is launched while another sync of the remote is running
+    #[serde(default, skip_serializing_if = "OverlapPolicy::is_default")]
+    pub overlap: OverlapPolicy,
+    /// check the files of the destination after a successful sync
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub verify: bool,
+    /// queue a sync when the files of the local source change
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub watch: bool,
+    /// log file of the jobs, `<remote>.log` in the log directory if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_file: Option<String>,
+    /// rclone options of the syncs
+    #[serde(default, skip_serializing_if = "SyncOptions::is_empty")]
+    pub options: SyncOptions,
+    /// rclone filter file of the syncs, like `--filter-from`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter_from: Option<String>,
+    /// rclone filter rules of the syncs, like `- *.tmp`, applied before the filter file
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub filters: Vec<String>,
+    /// free notes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// tags
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// date of the last successful sync
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_sync: Option<String>,
+    /// number of consecutive failed syncs
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub failures: u32,
+    /// skipped by the batches after too many consecutive failures, until a successful sync
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub quarantined: bool,
 
     /// config origin
     #[serde(skip)]
     pub config_origin: ConfigOrigin,
+
+    /// rclone remote details (from `config/get`), loaded lazily
+    #[serde(skip)]
+    pub details: Option<RcRemoteConfig>,
+
+    /// rclone backend type, for the remotes of the rclone config
+    #[serde(skip)]
+    pub backend_type: Option<String>,
+
+    /// rclone remote description
+    #[serde(skip)]
+    pub description: Option<String>,
+
+    /// extra rclone config file of the remote, `None` for the main one
+    #[serde(skip)]
+    pub rclone_config_file: Option<String>,
 }
 
 impl RemoteConfiguration {
     /// Translate to a row
-    pub fn to_table_row(&self) -> [String; 3] {
+    pub fn to_table_row(&self) -> [String; 4] {
+        let mut origin = match &self.backend_type {
+            Some(backend_type) => format!("{} ({backend_type})", self.config_origin),
+            None => self.config_origin.to_string(),
+        };
+        if let Some(file_name) = self
+            .rclone_config_file
+            .as_deref()
+            .and_then(|file| Path::new(file).file_name())
+        {
+            origin = format!("{origin} @{}", file_name.to_string_lossy());
+        }
+        if let Some(group) = &self.group {
+            origin = format!("{origin} [{group}]");
+        }
+        for tag in &self.tags {
+            origin = format!("{origin} #{tag}");
+        }
+        // the expanded form of a path with `~` or variables is shown below it
+        let with_expanded = |path: &Option<String>| {
+            let path = path.clone().unwrap_or_default();
+            let expanded = expand_path(&path);
+            if expanded == path {
+                path
+            } else {
+                format!("{path}\n= {expanded}")
+            }
+        };
         [
-            format!("{}\n{}", self.remote_name, self.config_origin),
-            self.remote_src.clone().unwrap_or_default(),
-            self.remote_dest.clone().unwrap_or_default(),
+            format!("{}\n{origin}", self.remote_name),
+            with_expanded(&self.remote_src),
+            with_expanded(&self.remote_dest),
+            self.schedule
+                .as_ref()
+                .map(Schedule::describe_next_run)
+                .into_iter()
+                .chain(self.last_sync.as_ref().map(|date| format!("last: {date}")))
+                .chain(
+                    self.quarantined
+                        .then(|| format!("quarantined ({} failures)", self.failures)),
+                )
+                .collect::<Vec<String>>()
+                .join("\n"),
         ]
     }
+
+    /// Set the backend type and the description of the rclone remote
+    pub fn set_backend(&mut self, rclone_remote: &RcRemote) {
+        self.backend_type = Some(rclone_remote.backend_type.clone());
+        self.rclone_config_file
+            .clone_from(&rclone_remote.config_file);
+        self.description = Some(rclone_remote.description.clone()).filter(|d| !d.is_empty());
+    }
+
+    /// Is the edit of a rclone remote only changing its [`RemoteMetadata`]
+    pub fn is_metadata_edit(&self, edited: &Self) -> bool {
+        let mut with_metadata = self.clone();
+        RemoteMetadata::from_remote(edited).apply(&mut with_metadata);
+        let mut edited = edited.clone();
+        // the form gives empty paths
+        edited.remote_src = edited.remote_src.filter(|src| !src.is_empty());
+        edited.remote_dest = edited.remote_dest.filter(|dest| !dest.is_empty());
+        with_metadata.remote_dest = with_metadata.remote_dest.filter(|dest| !dest.is_empty());
+        edited.config_origin = self.config_origin.clone();
+        edited.details.clone_from(&self.details);
+        edited.backend_type.clone_from(&self.backend_type);
+        edited
+            .rclone_config_file
+            .clone_from(&self.rclone_config_file);
+        edited.description.clone_from(&self.description);
+        edited == with_metadata
+    }
+
+    /// Count a finished sync, a success resets the failures and too many failures in a row
+    /// quarantine the remote, 0 to never quarantine
+    pub fn record_sync(&mut self, success: bool, quarantine_after: u32) -> SyncRecord {
+        if success {
+            self.last_sync = Some(report_date());
+            self.failures = 0;
+            self.quarantined = false;
+        } else {
+            self.failures += 1;
+            self.quarantined |= quarantine_after > 0 && self.failures >= quarantine_after;
+        }
+        SyncRecord {
+            name: self.remote_name.clone(),
+            last_sync: self.last_sync.clone(),
+            failures: self.failures,
+            quarantined: self.quarantined,
+        }
+    }
+
+    /// Apply the sync state counted by the background thread, returns true if it quarantined the remote
+    pub fn apply_sync_record(&mut self, record: &SyncRecord) -> bool {
+        let newly_quarantined = record.quarantined && !self.quarantined;
+        self.last_sync.clone_from(&record.last_sync);
+        self.failures = record.failures;
+        self.quarantined = record.quarantined;
+        newly_quarantined
+    }
+
+    /// Are the rclone details needed and not loaded yet
+    pub fn needs_details(&self) -> bool {
+        self.config_origin != ConfigOrigin::GalionConfig && self.details.is_none()
+    }
+
+    /// Cache the rclone remote details
+    pub fn set_details(&mut self, details: RcRemoteConfig) {
+        if self.remote_dest.is_none() {
+            self.remote_dest = details
+                .parameters
+                .get("remote")
+                .and_then(Value::as_str)
+                .map(String::from);
+        }
+        self.details = Some(details);
+    }
+}
+
+/// Sync state of a remote, counted by the background thread and saved by the ui
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncRecord {
+    /// remote name
+    pub(crate) name: String,
+    /// date of the last successful sync
+    pub(crate) last_sync: Option<String>,
+    /// number of consecutive failed syncs
+    pub(crate) failures: u32,
+    /// skipped by the batches, the schedules and the retries
+    pub(crate) quarantined: bool,
 }
 
-/// Input string state
+/// Galion side of a rclone remote, stored by remote name
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct RemoteMetadata {
+    /// chosen source
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) remote_src: Option<String>,
+    /// free notes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) notes: Option<String>,
+    /// tags
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) tags: Vec<String>,
+    /// date of the last successful sync
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) last_sync: Option<String>,
+}
+
+impl RemoteMetadata {
+    /// Metadata of a remote
+    pub fn from_remote(remote: &RemoteConfiguration) -> Self {
+        Self {
+            remote_src: remote.remote_src.clone().filter(|src| !src.is_empty()),
+            notes: remote.notes.clone(),
+            tags: remote.tags.clone(),
+            last_sync: remote.last_sync.clone(),
+        }
+    }
+
+    /// Is there no metadata
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Set the metadata of a remote
+    pub fn apply(&self, remote: &mut RemoteConfiguration) {
+        remote.remote_src.clone_from(&self.remote_src);
+        remote.notes.clone_from(&self.notes);
+        remote.tags.clone_from(&self.tags);
+        remote.last_sync.clone_from(&self.last_sync);
+    }
+}
+
+/// Field of the edit form
+#[derive(Debug)]
+pub(crate) struct EditField {
+    /// label of the field
+    pub(crate) label: &'static str,
+    /// input of the field
+    pub(crate) input: TextInput,
+}
+
+/// Edit form of a remote
 #[derive(Debug)]
 pub(crate) struct EditRemote {
     /// idx edit string
     pub(crate) idx_string: usize,
-    /// Position of cursor in the editor area
-    pub(crate) character_index: usize,
-    /// Remote name
-    pub(crate) remote_name: String,
-    /// Remote src
-    pub(crate) remote_src: String,
-    /// Remote destination
-    pub(crate) remote_dest: String,
+    /// fields of the form
+    pub(crate) fields: Vec<EditField>,
+    /// are the rclone options shown
+    pub(crate) extended: bool,
+    /// edited remote, keeps the settings not in the form
+    remote: RemoteConfiguration,
 }
 
 impl EditRemote {
-    /// Byte index of the selected input
-    fn byte_index(&mut self) -> usize {
-        let input = match self.idx_string {
-            0 => &mut self.remote_name,
-            1 => &mut self.remote_src,
-            _ => &mut self.remote_dest,
-        };
-        input
-            .char_indices()
-            .map(|(i, _)| i)
-            .nth(self.character_index)
-            .unwrap_or(input.len())
-    }
-
-    /// Add a char to a selected input
-    pub fn enter_char(&mut self, new_char: char) {
-        let index = self.byte_index();
-        let input = match self.idx_string {
-            0 => &mut self.remote_name,
-            1 => &mut self.remote_src,
-            _ => &mut self.remote_dest,
+    /// Label of the src field, with the Windows path syntax on Windows
+    const SRC_LABEL: &str = if cfg!(windows) {
+        "Remote source, like C:\\dir or \\\\server\\share"
+    } else {
+        "Remote source"
+    };
+    /// Label of the dest field
+    const DEST_LABEL: &str = if cfg!(windows) {
+        "Remote destination, like remote:dir or D:\\dir"
+    } else {
+        "Remote destination"
+    };
+    /// Index of the name field
+    const NAME: usize = 0;
+    /// Index of the source field
+    const SRC: usize = 1;
+    /// Index of the destination field
+    const DEST: usize = 2;
+    /// Index of the backup dir field
+    const BACKUP_DIR: usize = 3;
+    /// Index of the operation field
+    const OPERATION: usize = 4;
+    /// Index of the ping url field
+    const PING_URL: usize = 5;
+    /// Index of the priority field
+    const PRIORITY: usize = 6;
+    /// Index of the schedule field
+    const SCHEDULE: usize = 7;
+    /// Index of the group field
+    const GROUP: usize = 8;
+    /// Index of the maximum duration field
+    const MAX_DURATION: usize = 9;
+    /// Index of the free space check field
+    const SPACE_CHECK: usize = 10;
+    /// Index of the overlap policy field
+    const OVERLAP: usize = 11;
+    /// Index of the verification field
+    const VERIFY: usize = 12;
+    /// Index of the log file field
+    const LOG_FILE: usize = 13;
+    /// Index of the filter file field
+    const FILTER_FROM: usize = 14;
+    /// Index of the notes field
+    const NOTES: usize = 15;
+    /// Index of the tags field
+    const TAGS: usize = 16;
+    /// Index of the chained remote field
+    const AFTER: usize = 17;
+    /// Index of the watch field
+    const WATCH: usize = 18;
+    /// Index of the first rclone option field, the fields of the extended form
+    const OPTIONS: usize = 19;
+
+    /// Edit form of a remote
+    pub fn new(remote: &RemoteConfiguration) -> Self {
+        let field = |label, value: &Option<String>| EditField {
+            label,
+            input: TextInput::new(value.clone().unwrap_or_default()),
         };
-        input.insert(index, new_char);
-        self.move_cursor_right();
+        let mut fields = vec![
+            EditField {
+                label: "Remote name",
+                input: TextInput::new(remote.remote_name.clone()),
+            },
+            field(Self::SRC_LABEL, &remote.remote_src),
+            field(Self::DEST_LABEL, &remote.remote_dest),
+            field("Backup dir (optional)", &remote.backup_dir),
+            EditField {
+                label: "Operation (sync, copy, move, bisync)",
+                input: TextInput::new(remote.operation.to_string()),
+            },
+            field("Ping url (optional)", &remote.ping_url),
+            EditField {
+                label: "Priority (low, normal, high)",
+                input: TextInput::new(remote.priority.to_string()),
+            },
+            field(
+                "Schedule (cron, optional)",
+                &remote
+                    .schedule
+                    .as_ref()
+                    .map(|schedule| schedule.cron.clone()),
+            ),
+            field("Group (optional)", &remote.group),
+            field("Max duration, like 2h (optional)", &remote.max_duration),
+            EditField {
+                label: "Free space check (off, warn, refuse)",
+                input: TextInput::new(remote.space_check.to_string()),
+            },
+            EditField {
+                label: "Overlapping syncs (queue, refuse, allow)",
+                input: TextInput::new(remote.overlap.to_string()),
+            },
+            EditField {
+                label: "Verify after sync (true/false)",
+                input: TextInput::new(remote.verify.to_string()),
+            },
+            field("Log file (optional)", &remote.log_file),
+            field("Filter file (optional)", &remote.filter_from),
+            field("Notes (optional)", &remote.notes),
+            EditField {
+                label: "Tags, separated by commas (optional)",
+                input: TextInput::new(remote.tags.join(", ")),
+            },
+            field("Run after the sync of the remote (optional)", &remote.after),
+            EditField {
+                label: "Sync when the local source changes (true/false)",
+                input: TextInput::new(remote.watch.to_string()),
+            },
+        ];
+        fields.extend(
+            SyncOptions::FIELDS
+                .into_iter()
+                .zip(remote.options.field_values())
+                .map(|(label, value)| EditField {
+                    label,
+                    input: TextInput::new(value),
+                }),
+        );
+        Self {
+            idx_string: Self::NAME,
+            fields,
+            extended: false,
+            remote: remote.clone(),
+        }
     }
 
-    /// Clamp cursor based on the selected input
-    fn clamp_cursor(&self, new_cursor_pos: usize) -> usize {
-        let input_count = match self.idx_string {
-            0 => self.remote_name.chars().count(),
-            1 => self.remote_src.chars().count(),
-            _ => self.remote_dest.chars().count(),
-        };
-        new_cursor_pos.clamp(0, input_count)
+    /// Shown fields
+    pub fn visible_fields(&self) -> &[EditField] {
+        if self.extended {
+            &self.fields
+        } else {
+            &self.fields[..Self::OPTIONS]
+        }
+    }
+
+    /// Show or hide the rclone options
+    pub fn toggle_extended(&mut self) {
+        self.extended = !self.extended;
+        self.idx_string = self.idx_string.min(self.visible_fields().len() - 1);
+        self.reset_char_index();
+    }
+
+    /// Selected input
+    pub fn selected(&mut self) -> &mut TextInput {
+        let idx = self.idx_string.min(self.fields.len() - 1);
+        &mut self.fields[idx].input
     }
 
-    /// Move the cursor to the right for the selected input
-    pub fn move_cursor_right(&mut self) {
-        let cursor_moved_right = self.character_index.saturating_add(1);
-        self.character_index = self.clamp_cursor(cursor_moved_right);
+    /// Select the next field
+    pub fn next_field(&mut self) {
+        self.idx_string = (self.idx_string + 1).min(self.visible_fields().len() - 1);
+        self.reset_char_index();
     }
 
-    /// Move the cursor to the left for the selected input
-    pub fn move_cursor_left(&mut self) {
-        let cursor_moved_left = self.character_index.saturating_sub(1);
-        self.character_index = self.clamp_cursor(cursor_moved_left);
+    /// Select the previous field
+    pub fn previous_field(&mut self) {
+        self.idx_string = self.idx_string.saturating_sub(1);
+        self.reset_char_index();
     }
 
-    /// Delete char for the selected input
-    pub fn delete_char(&mut self) {
-        let is_not_cursor_leftmost = self.character_index != 0;
-        if is_not_cursor_leftmost {
-            let input = match self.idx_string {
-                0 => &mut self.remote_name,
-                1 => &mut self.remote_src,
-                _ => &mut self.remote_dest,
-            };
-            // Method "remove" is not used on the saved text for deleting the selected char.
-            // Reason: Using remove on String works on bytes instead of the chars.
-            // Using remove would require special care because of char boundaries.
+    /// Put the cursor at the end of the selected input
+    fn reset_char_index(&mut self) {
+        let input = self.selected();
+        input.character_index = input.value.chars().count();
+    }
 
-            let current_index = self.character_index;
-            let from_left_to_current_index = current_index - 1;
+    /// Value of a field
+    fn value(&self, idx: usize) -> &str {
+        self.fields
+            .get(idx)
+            .map_or("", |field| field.input.value.as_str())
+    }
 
-            // Getting all characters before the selected character.
-            let before_char_to_delete = input.chars().take(from_left_to_current_index);
-            // Getting all characters after selected character.
-            let after_char_to_delete = input.chars().skip(current_index);
+    /// Value of an optional field, `None` if empty
+    fn optional_value(&self, idx: usize) -> Option<String> {
+        Some(self.value(idx).trim())
+            .filter(|value| !value.is_empty())
+            .map(String::from)
+    }
 
-            // Put all characters together except the selected one.
-            // By leaving the selected one out, it is forgotten and therefore deleted.
-            *input = before_char_to_delete.chain(after_char_to_delete).collect();
-            self.move_cursor_left();
+    /// Get the edited new remote
+    /// # Errors
+    /// Fails if a path, a rclone option, the schedule, the max duration or the chained remote is invalid
+    pub fn finish(&self) -> Result<RemoteConfiguration, GalionError> {
+        let options = self.fields[Self::OPTIONS..]
+            .iter()
+            .map(|field| field.input.value.as_str())
+            .collect::<Vec<&str>>();
+        let mut remote = self.remote.clone();
+        remote.options = SyncOptions::from_fields(&options)?;
+        remote.operation = self.value(Self::OPERATION).parse()?;
+        remote.priority = self.value(Self::PRIORITY).parse()?;
+        remote.space_check = self.value(Self::SPACE_CHECK).parse()?;
+        remote.overlap = self.value(Self::OVERLAP).parse()?;
+        remote.verify = match self.value(Self::VERIFY).trim() {
+            "" | "false" => false,
+            "true" => true,
+            value => return Err(format!("invalid value '{value}' for the verification").into()),
+        };
+        remote.watch = match self.value(Self::WATCH).trim() {
+            "" | "false" => false,
+            "true" => true,
+            value => return Err(format!("invalid value '{value}' for the watch").into()),
+        };
+        remote.remote_name = self.value(Self::NAME).to_string();
+        remote.remote_src = Some(normalize_path(self.value(Self::SRC))?);
+        remote.remote_dest = Some(normalize_path(self.value(Self::DEST))?);
+        remote.backup_dir = self.optional_value(Self::BACKUP_DIR);
+        remote.ping_url = self.optional_value(Self::PING_URL);
+        remote.group = self.optional_value(Self::GROUP);
+        remote.log_file = self.optional_value(Self::LOG_FILE);
+        remote.filter_from = self.optional_value(Self::FILTER_FROM);
+        remote.notes = self.optional_value(Self::NOTES);
+        remote.tags = self
+            .value(Self::TAGS)
+            .split(',')
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(String::from)
+            .collect();
+        remote.after = self.optional_value(Self::AFTER);
+        if remote.after.as_ref() == Some(&remote.remote_name) {
+            return Err("a remote cannot run after itself".into());
         }
+        remote.max_duration = self.optional_value(Self::MAX_DURATION);
+        if let Some(max_duration) = &remote.max_duration {
+            parse_duration(max_duration)?;
+        }
+        remote.schedule = match self.optional_value(Self::SCHEDULE) {
+            None => None,
+            // keep the state of an unchanged schedule
+            Some(cron) if remote.schedule.as_ref().is_some_and(|s| s.cron == cron) => {
+                remote.schedule
+            }
+            Some(cron) => Some(Schedule::new(&cron)?),
+        };
+        remote.config_origin = ConfigOrigin::GalionConfig;
+        remote.details = None;
+        remote.backend_type = None;
+        remote.rclone_config_file = None;
+        remote.description = None;
+        Ok(remote)
     }
+}
 
-    /// Reset char index
-    pub fn reset_char_index(&mut self) {
-        let input_len = match self.idx_string {
-            0 => self.remote_name.chars().count(),
-            1 => self.remote_src.chars().count(),
-            _ => self.remote_dest.chars().count(),
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Home directory of the tests
+    fn home() -> String {
+        let Some(home) = home_dir() else {
+            panic!("no home directory");
         };
-        self.character_index = self.clamp_cursor(input_len);
+        home.to_string_lossy().into_owned()
     }
 
-    /// Get the edited new remote
-    pub fn finish(&self) -> RemoteConfiguration {
-        RemoteConfiguration {
-            remote_name: self.remote_name.clone(),
-            remote_src: Some(self.remote_src.clone()),
-            remote_dest: Some(self.remote_dest.clone()),
-            config_origin: ConfigOrigin::GalionConfig,
+    #[test]
+    fn test_expand_path() {
+        let sep = std::path::MAIN_SEPARATOR;
+        assert_eq!(expand_path("~"), home());
+        assert_eq!(expand_path("~/backup"), format!("{}{sep}backup", home()));
+        assert_eq!(expand_path("~\\backup"), format!("{}{sep}backup", home()));
+        // only a leading `~` is the home
+        assert_eq!(expand_path("/data/~/backup"), "/data/~/backup");
+        assert_eq!(expand_path("~user/backup"), "~user/backup");
+        assert_eq!(expand_path("remote:dir"), "remote:dir");
+    }
+
+    #[test]
+    fn test_expand_env() {
+        let path = std::env::var("PATH").unwrap_or_else(|_| panic!("PATH is not set"));
+        assert_eq!(expand_path("$PATH/x"), format!("{path}/x"));
+        assert_eq!(expand_path("${PATH}x"), format!("{path}x"));
+        assert_eq!(expand_path("%PATH%\\x"), format!("{path}\\x"));
+        // unknown variables are kept
+        for unknown in [
+            "$GALION_UNKNOWN_VARIABLE/x",
+            "${GALION_UNKNOWN_VARIABLE}/x",
+            "%GALION_UNKNOWN_VARIABLE%/x",
+        ] {
+            assert_eq!(expand_path(unknown), unknown);
+        }
+        // lone and unclosed markers
+        assert_eq!(expand_path("cost$/100%"), "cost$/100%");
+        assert_eq!(expand_path("${PATH"), "${PATH");
+    }
+
+    #[test]
+    fn test_expand_placeholders() {
+        assert_eq!(expand_placeholders("remote:backup"), "remote:backup");
+        assert_eq!(expand_placeholders("remote:{unknown}"), "remote:{unknown}");
+        let expanded = expand_placeholders("remote:{date}/{year}{month}{day}-{time}");
+        let Some((date, rest)) = expanded["remote:".len()..].split_once('/') else {
+            panic!("'{expanded}' has no '/'");
+        };
+        // 2024-09-06/20240906-123456
+        assert_eq!(date.len(), 10, "{expanded}");
+        assert_eq!(&rest[..8], date.replace('-', ""), "{expanded}");
+        assert_eq!(rest.len(), 15, "{expanded}");
+        assert!(!expand_placeholders("{hostname}").is_empty());
+    }
+
+    #[test]
+    fn test_parse_rclone_path() {
+        assert_eq!(RclonePath::parse("C:\\x"), RclonePath::Local("C:\\x"));
+        assert_eq!(
+            RclonePath::parse("remote:"),
+            RclonePath::Remote("remote", "")
+        );
+        assert_eq!(
+            RclonePath::parse("remote:dir/sub"),
+            RclonePath::Remote("remote", "dir/sub")
+        );
+        assert_eq!(
+            RclonePath::parse("remote,param=value:dir"),
+            RclonePath::Remote("remote", "dir")
+        );
+        assert_eq!(RclonePath::parse("file.txt"), RclonePath::Local("file.txt"));
+        assert_eq!(RclonePath::parse("./a:b"), RclonePath::Local("./a:b"));
+        assert_eq!(
+            RclonePath::parse("/data/a:b"),
+            RclonePath::Local("/data/a:b")
+        );
+        assert_eq!(
+            RclonePath::parse(":s3:bucket"),
+            RclonePath::Backend(":s3:bucket")
+        );
+        if cfg!(windows) {
+            assert_eq!(RclonePath::parse("C:x"), RclonePath::Local("C:x"));
+        } else {
+            assert_eq!(RclonePath::parse("C:x"), RclonePath::Remote("C", "x"));
+        }
+    }
+
+    #[test]
+    fn test_split_fs() {
+        assert_eq!(
+            RclonePath::split_fs("remote:dir/sub"),
+            ("remote:", "dir/sub")
+        );
+        assert_eq!(RclonePath::split_fs("remote:"), ("remote:", ""));
+        assert_eq!(
+            RclonePath::split_fs("/data/file.txt"),
+            ("/data/", "file.txt")
+        );
+        assert_eq!(RclonePath::split_fs("C:\\dir\\file"), ("C:\\dir\\", "file"));
+        assert_eq!(RclonePath::split_fs("file.txt"), (".", "file.txt"));
+    }
+
+    #[test]
+    fn test_normalize_windows_path() {
+        let normalized = |path| normalize_windows_path(path).ok();
+        assert_eq!(normalized("c:"), Some("C:\\".to_string()));
+        assert_eq!(normalized("c:/"), Some("C:\\".to_string()));
+        assert_eq!(normalized("c:/dir/sub"), Some("C:\\dir\\sub".to_string()));
+        // a trailing slash is kept as a backslash
+        assert_eq!(normalized("D:\\dir/"), Some("D:\\dir\\".to_string()));
+        assert_eq!(
+            normalized("//server/share/"),
+            Some("\\\\server\\share\\".to_string())
+        );
+        assert_eq!(
+            normalized("\\\\?\\C:\\dir"),
+            Some("\\\\?\\C:\\dir".to_string())
+        );
+        assert_eq!(normalized("dir/sub/"), Some("dir/sub/".to_string()));
+        assert_eq!(normalized("remote:dir"), Some("remote:dir".to_string()));
+        // drive-relative and UNC paths without a share
+        assert!(normalize_windows_path("C:dir").is_err());
+        assert!(normalize_windows_path("//server").is_err());
+        assert!(normalize_windows_path("\\\\server\\").is_err());
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        for path in ["/data/dir/", "dir", "remote:dir/"] {
+            assert_eq!(normalize_path(path).ok().as_deref(), Some(path));
+        }
+        if !cfg!(windows) {
+            assert_eq!(normalize_path("C:/dir").ok().as_deref(), Some("C:/dir"));
+            assert!(normalize_path("C:\\dir").is_err());
+        }
+    }
+
+    #[test]
+    fn test_priority() {
+        assert!(Priority::High > Priority::Normal && Priority::Normal > Priority::Low);
+        for priority in [Priority::Low, Priority::Normal, Priority::High] {
+            assert_eq!(
+                priority.to_string().parse::<Priority>().ok(),
+                Some(priority)
+            );
         }
+        assert_eq!(" high ".parse::<Priority>().ok(), Some(Priority::High));
+        assert_eq!("".parse::<Priority>().ok(), Some(Priority::Normal));
+        assert!("urgent".parse::<Priority>().is_err());
+        assert!(Priority::Normal.is_default());
     }
 }
@@ -0,0 +1,314 @@
+//! Reports written to files
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use time::{OffsetDateTime, macros::format_description};
+
+use crate::browser::format_size;
+use crate::errors::GalionError;
+use crate::librclone::Rclone;
+use crate::librclone::rclone::RcListItem;
+
+/// Format of a report file, from its extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ReportFormat {
+    /// one line per entry, with a header
+    Csv,
+    /// pretty JSON document
+    Json,
+}
+
+impl ReportFormat {
+    /// Format of a report path
+    /// # Errors
+    /// Fails if the extension is not supported
+    pub fn from_path(path: &Path) -> Result<Self, GalionError> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("csv") => Ok(Self::Csv),
+            Some("json") => Ok(Self::Json),
+            _ => Err(format!(
+                "unknown report format for '{}' - use .csv or .json",
+                path.display()
+            )
+            .into()),
+        }
+    }
+}
+
+/// Escape a CSV field
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Build a CSV line from its fields
+pub(crate) fn csv_line<S: AsRef<str>>(fields: &[S]) -> String {
+    let fields = fields
+        .iter()
+        .map(|field| csv_field(field.as_ref()))
+        .collect::<Vec<String>>();
+    format!("{}\n", fields.join(","))
+}
+
+/// Current local date of the reports
+pub(crate) fn report_date() -> String {
+    let now = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
+    let format = format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
+    now.format(&format).unwrap_or_default()
+}
+
+/// File that differs between the source and the destination
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct CompareEntry {
+    /// `missing` (not on dest), `extra` (not on src), `mismatched` or `error`
+    pub(crate) status: &'static str,
+    /// path relative to the source and the destination
+    pub(crate) path: String,
+    /// size on the source
+    pub(crate) src_size: Option<i64>,
+    /// size on the destination
+    pub(crate) dest_size: Option<i64>,
+    /// hash on the source
+    pub(crate) src_hash: Option<String>,
+    /// hash on the destination
+    pub(crate) dest_hash: Option<String>,
+}
+
+/// Comparison of a source and a destination
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct CompareReport {
+    /// compared source
+    pub(crate) src: String,
+    /// compared destination
+    pub(crate) dest: String,
+    /// date of the comparison
+    pub(crate) date: String,
+    /// hash type used by the comparison, empty if the sizes and dates were used
+    pub(crate) hash_type: String,
+    /// files that differ
+    pub(crate) entries: Vec<CompareEntry>,
+}
+
+impl CompareReport {
+    /// Header of the CSV report
+    const CSV_HEADER: [&str; 6] = [
+        "status",
+        "path",
+        "src_size",
+        "dest_size",
+        "src_hash",
+        "dest_hash",
+    ];
+
+    /// Compare a source and a destination
+    /// # Errors
+    /// Fails if the check or the listings fail
+    pub fn new(rclone: &Rclone, src: &str, dest: &str) -> Result<Self, GalionError> {
+        let check = rclone.check(src, dest, None)?;
+        let opt = json!({ "recurse": true, "filesOnly": true, "showHash": true });
+        let by_path = |items: Vec<RcListItem>| {
+            items
+                .into_iter()
+                .map(|item| (item.path.clone(), item))
+                .collect::<BTreeMap<String, RcListItem>>()
+        };
+        let src_items = by_path(rclone.list(src, "", &opt)?);
+        let dest_items = by_path(rclone.list(dest, "", &opt)?);
+        let hash = |item: &RcListItem| {
+            item.hashes
+                .get(&check.hash_type)
+                .or_else(|| item.hashes.values().next())
+                .cloned()
+        };
+        let entries = check
+            .combined
+            .iter()
+            .filter_map(|line| {
+                let (marker, path) = line.split_once(' ')?;
+                let status = match marker {
+                    "-" => "missing",
+                    "+" => "extra",
+                    "*" => "mismatched",
+                    "!" => "error",
+                    _ => return None,
+                };
+                let src_item = src_items.get(path);
+                let dest_item = dest_items.get(path);
+                Some(CompareEntry {
+                    status,
+                    path: path.to_string(),
+                    src_size: src_item.map(|item| item.size),
+                    dest_size: dest_item.map(|item| item.size),
+                    src_hash: src_item.and_then(hash),
+                    dest_hash: dest_item.and_then(hash),
+                })
+            })
+            .collect();
+        Ok(Self {
+            src: src.to_string(),
+            dest: dest.to_string(),
+            date: report_date(),
+            hash_type: check.hash_type,
+            entries,
+        })
+    }
+
+    /// Write the report, as CSV or JSON depending on the extension
+    /// # Errors
+    /// Fails if the format is unknown or if the file cannot be written
+    pub fn write(&self, path: &Path) -> Result<(), GalionError> {
+        let content = match ReportFormat::from_path(path)? {
+            ReportFormat::Json => serde_json::to_string_pretty(self)?,
+            ReportFormat::Csv => {
+                let mut content = csv_line(&Self::CSV_HEADER);
+                for entry in &self.entries {
+                    let optional = |value: Option<String>| value.unwrap_or_default();
+                    content.push_str(&csv_line(&[
+                        entry.status.to_string(),
+                        entry.path.clone(),
+                        optional(entry.src_size.map(|size| size.to_string())),
+                        optional(entry.dest_size.map(|size| size.to_string())),
+                        optional(entry.src_hash.clone()),
+                        optional(entry.dest_hash.clone()),
+                    ]));
+                }
+                content
+            }
+        };
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+/// Format of the batch summaries
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SummaryFormat {
+    /// markdown table
+    #[default]
+    Markdown,
+    /// html page
+    Html,
+}
+
+impl SummaryFormat {
+    /// Extension of the summary files
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+        }
+    }
+}
+
+/// Result of a job of a batch
+#[derive(Debug, Clone)]
+pub(crate) struct SummaryRow {
+    /// remote name
+    pub(crate) name: String,
+    /// success status
+    pub(crate) success: bool,
+    /// transferred bytes
+    pub(crate) bytes: u64,
+    /// duration in seconds
+    pub(crate) duration: f64,
+    /// error, empty if none
+    pub(crate) error: String,
+}
+
+/// Summary of a batch of jobs
+#[derive(Debug, Clone)]
+pub(crate) struct BatchSummary {
+    /// start date of the batch
+    pub(crate) started: String,
+    /// result of each job
+    pub(crate) rows: Vec<SummaryRow>,
+}
+
+/// Escape a text for html
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl BatchSummary {
+    /// Title of the summary
+    fn title(&self) -> String {
+        let failed = self.rows.iter().filter(|row| !row.success).count();
+        format!(
+            "Galion batch of {} - {} jobs, {failed} failed",
+            self.started,
+            self.rows.len()
+        )
+    }
+
+    /// Cells of a row
+    fn cells(row: &SummaryRow) -> [String; 5] {
+        [
+            row.name.clone(),
+            if row.success { "success" } else { "failed" }.to_string(),
+            format_size(row.bytes),
+            format!("{:.1}s", row.duration),
+            row.error.clone(),
+        ]
+    }
+
+    /// Markdown summary
+    fn to_markdown(&self) -> String {
+        let mut content = format!("# {}\n\n", self.title());
+        content.push_str("| remote | status | transferred | duration | error |\n");
+        content.push_str("|---|---|---|---|---|\n");
+        for row in &self.rows {
+            let cells = Self::cells(row).map(|cell| cell.replace('|', "\\|").replace('\n', " "));
+            content.push_str("| ");
+            content.push_str(&cells.join(" | "));
+            content.push_str(" |\n");
+        }
+        content
+    }
+
+    /// Html summary
+    fn to_html(&self) -> String {
+        let title = html_escape(&self.title());
+        let mut content = format!(
+            "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>{title}</title></head>\n<body>\n<h1>{title}</h1>\n<table>\n"
+        );
+        content.push_str("<tr><th>remote</th><th>status</th><th>transferred</th><th>duration</th><th>error</th></tr>\n");
+        for row in &self.rows {
+            let cells = Self::cells(row)
+                .map(|cell| format!("<td>{}</td>", html_escape(&cell)))
+                .join("");
+            content.push_str("<tr>");
+            content.push_str(&cells);
+            content.push_str("</tr>\n");
+        }
+        content.push_str("</table>\n</body>\n</html>\n");
+        content
+    }
+
+    /// Write the summary in a new file of the report directory, returns its path
+    /// # Errors
+    /// Fails if the file cannot be written
+    pub fn write(&self, dir: &Path, format: SummaryFormat) -> Result<PathBuf, GalionError> {
+        std::fs::create_dir_all(dir)?;
+        let file_name = format!(
+            "batch-{}.{}",
+            self.started.replace([' ', ':'], "_"),
+            format.extension()
+        );
+        let path = dir.join(file_name);
+        let content = match format {
+            SummaryFormat::Markdown => self.to_markdown(),
+            SummaryFormat::Html => self.to_html(),
+        };
+        std::fs::write(&path, content)?;
+        Ok(path)
+    }
+}
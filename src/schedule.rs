@@ -0,0 +1,290 @@
+//! Schedules of the remotes, as cron expressions
+
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use time::{Date, Duration, OffsetDateTime, Time, macros::format_description};
+
+use crate::errors::GalionError;
+
+/// Number of days searched for the next run, a leap day can be 4 years away
+const MAX_DAYS: i64 = 366 * 4 + 1;
+
+/// Parsed cron expression: `minute hour day-of-month month day-of-week`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Cron {
+    /// allowed minutes, one bit per value
+    minutes: u64,
+    /// allowed hours
+    hours: u64,
+    /// allowed days of the month
+    days: u64,
+    /// allowed months
+    months: u64,
+    /// allowed days of the week, sunday is 0
+    weekdays: u64,
+    /// the day of the month is restricted
+    days_restricted: bool,
+    /// the day of the week is restricted
+    weekdays_restricted: bool,
+}
+
+/// Parse a field of a cron expression into a bit set
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, GalionError> {
+    let invalid = || GalionError::new(format!("invalid cron field '{field}'"));
+    let mut bits = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().map_err(|_| invalid())?),
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (
+                start.parse::<u32>().map_err(|_| invalid())?,
+                end.parse::<u32>().map_err(|_| invalid())?,
+            )
+        } else {
+            let start = range.parse::<u32>().map_err(|_| invalid())?;
+            // `5/10` goes from 5 to the end
+            (start, if step > 1 { max } else { start })
+        };
+        if step == 0 || start < min || end > max || start > end {
+            return Err(invalid());
+        }
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+impl FromStr for Cron {
+    type Err = GalionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expression = match s.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            expression => expression,
+        };
+        let fields = expression.split_whitespace().collect::<Vec<&str>>();
+        let [minutes, hours, days, months, weekdays] = fields[..] else {
+            return Err(format!("invalid cron expression '{s}' - use 5 fields").into());
+        };
+        let mut weekdays_bits = parse_field(weekdays, 0, 7)?;
+        // 7 is also sunday
+        if weekdays_bits & (1 << 7) != 0 {
+            weekdays_bits = (weekdays_bits | 1) & !(1 << 7);
+        }
+        Ok(Self {
+            minutes: parse_field(minutes, 0, 59)?,
+            hours: parse_field(hours, 0, 23)?,
+            days: parse_field(days, 1, 31)?,
+            months: parse_field(months, 1, 12)?,
+            weekdays: weekdays_bits,
+            days_restricted: days != "*",
+            weekdays_restricted: weekdays != "*",
+        })
+    }
+}
+
+impl Cron {
+    /// Does the cron run on a date
+    fn matches_date(&self, date: Date) -> bool {
+        if self.months & (1 << u8::from(date.month())) == 0 {
+            return false;
+        }
+        let day = self.days & (1 << date.day()) != 0;
+        let weekday = self.weekdays & (1 << date.weekday().number_days_from_sunday()) != 0;
+        // like cron, a run is due on either day when both are restricted
+        match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+
+    /// First run strictly after a date
+    pub fn next_after(&self, after: OffsetDateTime) -> Option<OffsetDateTime> {
+        let start = after.replace_second(0).ok()?.replace_nanosecond(0).ok()? + Duration::MINUTE;
+        let mut date = start.date();
+        for day in 0..MAX_DAYS {
+            if self.matches_date(date) {
+                let (first_hour, first_minute) = if day == 0 {
+                    (start.hour(), start.minute())
+                } else {
+                    (0, 0)
+                };
+                for hour in first_hour..24 {
+                    if self.hours & (1 << hour) == 0 {
+                        continue;
+                    }
+                    let from = if hour == first_hour { first_minute } else { 0 };
+                    if let Some(minute) =
+                        (from..60).find(|minute| self.minutes & (1 << minute) != 0)
+                    {
+                        let time = Time::from_hms(hour, minute, 0).ok()?;
+                        return Some(date.with_time(time).assume_offset(start.offset()));
+                    }
+                }
+            }
+            date = date.next_day()?;
+        }
+        None
+    }
+}
+
+/// Schedule of the syncs of a remote
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct Schedule {
+    /// cron expression, like `0 3 * * *`
+    pub(crate) cron: String,
+    /// is the schedule active
+    #[serde(default = "Schedule::default_enabled")]
+    pub(crate) enabled: bool,
+    /// date of the last scheduled run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) last_run: Option<String>,
+    /// date of the next scheduled run, as planned by the scheduler
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) next_run: Option<String>,
+}
+
+impl Schedule {
+    /// Default for [`Schedule::enabled`]
+    fn default_enabled() -> bool {
+        true
+    }
+
+    /// New enabled schedule
+    /// # Errors
+    /// Fails if the cron expression is invalid
+    pub fn new(cron: &str) -> Result<Self, GalionError> {
+        cron.parse::<Cron>()?;
+        Ok(Self {
+            cron: cron.trim().to_string(),
+            enabled: true,
+            last_run: None,
+            next_run: None,
+        })
+    }
+
+    /// Next run computed from the cron expression, `None` if disabled or invalid
+    pub fn next_run_after(&self, after: OffsetDateTime) -> Option<OffsetDateTime> {
+        if !self.enabled {
+            return None;
+        }
+        self.cron.parse::<Cron>().ok()?.next_after(after)
+    }
+
+    /// Description of the next run, shown in the remotes table
+    pub fn describe_next_run(&self) -> String {
+        if !self.enabled {
+            return format!("{} (disabled)", self.cron);
+        }
+        let now = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
+        let format = format_description!("[year]-[month]-[day] [hour]:[minute]");
+        match self
+            .next_run_after(now)
+            .and_then(|next| next.format(&format).ok())
+        {
+            Some(next) => format!("{}\nnext: {next}", self.cron),
+            None => format!("{} (invalid)", self.cron),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    /// Bit set of values
+    fn bits(values: &[u32]) -> u64 {
+        values.iter().fold(0, |bits, value| bits | 1 << value)
+    }
+
+    /// Parsed cron expression
+    fn cron(expression: &str) -> Cron {
+        expression
+            .parse()
+            .unwrap_or_else(|e| panic!("'{expression}' is valid: {e}"))
+    }
+
+    #[test]
+    fn test_fields() {
+        assert_eq!(parse_field("*", 0, 59).ok(), Some((1 << 60) - 1));
+        assert_eq!(parse_field("1-3", 0, 59).ok(), Some(bits(&[1, 2, 3])));
+        assert_eq!(
+            parse_field("*/15", 0, 59).ok(),
+            Some(bits(&[0, 15, 30, 45]))
+        );
+        assert_eq!(
+            parse_field("1-10/3", 0, 59).ok(),
+            Some(bits(&[1, 4, 7, 10]))
+        );
+        assert_eq!(parse_field("5/20", 0, 59).ok(), Some(bits(&[5, 25, 45])));
+        assert_eq!(parse_field("1,3,5", 1, 31).ok(), Some(bits(&[1, 3, 5])));
+        assert_eq!(parse_field("1-2,20", 0, 23).ok(), Some(bits(&[1, 2, 20])));
+    }
+
+    #[test]
+    fn test_invalid_fields() {
+        for field in ["", "60", "5-1", "*/0", "a", "1-", "-1", "1,,2", "*/x"] {
+            assert!(parse_field(field, 0, 59).is_err(), "'{field}' is invalid");
+        }
+        assert!(parse_field("0", 1, 31).is_err(), "days start at 1");
+        assert!("0 0 * *".parse::<Cron>().is_err(), "4 fields");
+        assert!("0 0 * * * *".parse::<Cron>().is_err(), "6 fields");
+        assert!("0 0 * 13 *".parse::<Cron>().is_err(), "month 13");
+    }
+
+    #[test]
+    fn test_weekdays() {
+        // 7 is sunday like 0
+        assert_eq!(cron("0 0 * * 7"), cron("0 0 * * 0"));
+        assert_eq!(cron("0 0 * * 5-7").weekdays, bits(&[0, 5, 6]));
+        assert_eq!(cron("0 0 * * *").weekdays, bits(&[0, 1, 2, 3, 4, 5, 6]));
+        assert_eq!(cron("@weekly"), cron("0 0 * * 0"));
+    }
+
+    #[test]
+    fn test_next_after() {
+        let daily = cron("30 2 * * *");
+        // strictly after
+        assert_eq!(
+            daily.next_after(datetime!(2024-01-01 02:30:00 UTC)),
+            Some(datetime!(2024-01-02 02:30:00 UTC))
+        );
+        assert_eq!(
+            daily.next_after(datetime!(2024-01-01 02:29:59 UTC)),
+            Some(datetime!(2024-01-01 02:30:00 UTC))
+        );
+        assert_eq!(
+            cron("*/15 * * * *").next_after(datetime!(2024-01-01 23:50:00 UTC)),
+            Some(datetime!(2024-01-02 00:00:00 UTC))
+        );
+        // the next leap day
+        assert_eq!(
+            cron("0 0 29 2 *").next_after(datetime!(2024-03-01 00:00:00 UTC)),
+            Some(datetime!(2028-02-29 00:00:00 UTC))
+        );
+        // the 13th or a friday, 2024-09-06 is a friday
+        assert_eq!(
+            cron("0 0 13 * 5").next_after(datetime!(2024-09-01 00:00:00 UTC)),
+            Some(datetime!(2024-09-06 00:00:00 UTC))
+        );
+        // a saturday in the range wrapping to sunday
+        assert_eq!(
+            cron("0 12 * * 6-7").next_after(datetime!(2024-09-02 00:00:00 UTC)),
+            Some(datetime!(2024-09-07 12:00:00 UTC))
+        );
+        assert_eq!(
+            cron("0 0 31 2 *").next_after(datetime!(2024-01-01 00:00:00 UTC)),
+            None
+        );
+    }
+}
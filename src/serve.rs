@@ -0,0 +1,128 @@
+//! Servers of remotes, started with rclone `serve/start`
+
+use rand::{Rng, distr::Alphanumeric};
+use serde_json::json;
+use std::fmt::Display;
+
+use crate::errors::GalionError;
+use crate::librclone::rclone::Rclone;
+
+/// Protocol of a server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ServeProtocol {
+    /// SFTP server, with generated credentials
+    Sftp,
+    /// NFS server, without authentication
+    Nfs,
+}
+
+impl ServeProtocol {
+    /// Can rclone serve the protocol on this platform
+    pub fn is_supported(self) -> bool {
+        match self {
+            Self::Sftp => true,
+            // rclone only builds its NFS server for unix
+            Self::Nfs => cfg!(unix),
+        }
+    }
+}
+
+impl Display for ServeProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sftp => write!(f, "sftp"),
+            Self::Nfs => write!(f, "nfs"),
+        }
+    }
+}
+
+/// Running server of a rclone path
+#[derive(Debug, Clone)]
+pub(crate) struct Server {
+    /// rclone server id
+    pub(crate) id: String,
+    /// protocol
+    pub(crate) protocol: ServeProtocol,
+    /// served rclone path
+    pub(crate) fs: String,
+    /// listening address
+    pub(crate) addr: String,
+    /// user and password, if the server needs some
+    pub(crate) credentials: Option<(String, String)>,
+}
+
+impl Server {
+    /// Address listened by the servers, the port is chosen by rclone
+    const ADDR: &str = "127.0.0.1:0";
+
+    /// User of the servers with credentials
+    const USER: &str = "galion";
+
+    /// Length of the generated passwords
+    const PASSWORD_LEN: usize = 16;
+
+    /// Start a server of a rclone path
+    /// # Errors
+    /// Fails if the server cannot start
+    pub fn start(
+        rclone: &Rclone,
+        protocol: ServeProtocol,
+        fs: String,
+    ) -> Result<Self, GalionError> {
+        if !protocol.is_supported() {
+            return Err(format!("rclone cannot serve {protocol} on this platform").into());
+        }
+        let mut options = json!({ "addr": Self::ADDR });
+        let credentials = match protocol {
+            ServeProtocol::Sftp => {
+                let password = rand::rng()
+                    .sample_iter(&Alphanumeric)
+                    .take(Self::PASSWORD_LEN)
+                    .map(char::from)
+                    .collect::<String>();
+                options["user"] = json!(Self::USER);
+                options["pass"] = json!(password);
+                Some((Self::USER.to_string(), password))
+            }
+            ServeProtocol::Nfs => {
+                // the NFS server needs the cache to write files
+                options["vfs_cache_mode"] = json!("full");
+                None
+            }
+        };
+        let serve = rclone.serve_start(&protocol.to_string(), &fs, &options)?;
+        Ok(Self {
+            id: serve.id,
+            protocol,
+            fs,
+            addr: serve.addr,
+            credentials,
+        })
+    }
+
+    /// Stop the server
+    /// # Errors
+    /// Fails if the server cannot be stopped
+    pub fn stop(&self, rclone: &Rclone) -> Result<(), GalionError> {
+        rclone.serve_stop(&self.id)
+    }
+
+    /// Mount command of the NFS export
+    fn nfs_mount_command(&self) -> String {
+        let (host, port) = self.addr.rsplit_once(':').unwrap_or((&self.addr, "2049"));
+        format!("mount -t nfs -o port={port},mountport={port},tcp {host}:/ <mountpoint>")
+    }
+}
+
+impl Display for Server {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} on {}", self.protocol, self.fs, self.addr)?;
+        if let Some((user, password)) = &self.credentials {
+            write!(f, " (user: {user}, password: {password})")?;
+        }
+        if self.protocol == ServeProtocol::Nfs {
+            write!(f, " - {}", self.nfs_mount_command())?;
+        }
+        Ok(())
+    }
+}
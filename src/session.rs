@@ -0,0 +1,39 @@
+//! State of the ui, saved when galion exits and restored at the next launch
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::app::write_file_atomic;
+use crate::errors::GalionError;
+use crate::stats::StatsWindow;
+
+/// State of the ui, kept apart from the config
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub(crate) struct Session {
+    /// name of the selected remote
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) selected: Option<String>,
+    /// window of the statistics
+    #[serde(default)]
+    pub(crate) stats_window: StatsWindow,
+}
+
+impl Session {
+    /// Session file name
+    pub const FILE_NAME: &str = "session.json";
+
+    /// Load the session, the default one if the file is missing or invalid
+    pub fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save the session
+    /// # Errors
+    /// Fails if the file cannot be written
+    pub fn save(&self, path: &Path) -> Result<(), GalionError> {
+        write_file_atomic(path, serde_json::to_string(self)?.as_bytes())
+    }
+}
@@ -0,0 +1,207 @@
+//! Settings of galion, edited in the ui
+
+use ratatui::style::{Color, Style};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::app::GalionConfig;
+use crate::errors::GalionError;
+use crate::input::TextInput;
+use crate::options::SyncOptions;
+use crate::remote::EditField;
+
+/// Colors of the ui
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    /// white rows and dark popups
+    #[default]
+    Default,
+    /// dark rows and popups
+    Dark,
+    /// white rows and popups
+    Light,
+}
+
+impl Theme {
+    /// Is it the default theme
+    #[allow(clippy::trivially_copy_pass_by_ref)] // signature of skip_serializing_if
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Style of the rows of the remotes table
+    pub fn row_style(self) -> Style {
+        match self {
+            Self::Default | Self::Light => Style::new().fg(Color::Black).bg(Color::White),
+            Self::Dark => Style::new().fg(Color::White).bg(Color::Black),
+        }
+    }
+
+    /// Color of the selected row
+    pub fn selected_color(self) -> Color {
+        match self {
+            Self::Default | Self::Light => Color::Blue,
+            Self::Dark => Color::Cyan,
+        }
+    }
+
+    /// Style of the popups
+    pub fn popup_style(self) -> Style {
+        match self {
+            Self::Default | Self::Dark => Style::new().bg(Color::Black).fg(Color::White),
+            Self::Light => Style::new().bg(Color::White).fg(Color::Black),
+        }
+    }
+
+    /// Color of the focused field
+    pub fn focus_color(self) -> Color {
+        match self {
+            Self::Default | Self::Dark => Color::Yellow,
+            Self::Light => Color::Blue,
+        }
+    }
+}
+
+impl Display for Theme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Default => write!(f, "default"),
+            Self::Dark => write!(f, "dark"),
+            Self::Light => write!(f, "light"),
+        }
+    }
+}
+
+impl FromStr for Theme {
+    type Err = GalionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "default" => Ok(Self::Default),
+            "dark" => Ok(Self::Dark),
+            "light" => Ok(Self::Light),
+            _ => Err(format!("unknown theme '{s}' - use default, dark or light").into()),
+        }
+    }
+}
+
+/// Settings form
+#[derive(Debug)]
+pub(crate) struct SettingsForm {
+    /// selected field
+    pub(crate) idx_string: usize,
+    /// fields of the form
+    pub(crate) fields: Vec<EditField>,
+}
+
+impl SettingsForm {
+    /// Index of the theme field
+    const THEME: usize = 0;
+    /// Index of the refresh rate field
+    const REFRESH_RATE: usize = 1;
+    /// Index of the deletion confirmation field
+    const CONFIRM_DELETIONS: usize = 2;
+    /// Index of the maximum running jobs field
+    const MAX_RUNNING_JOBS: usize = 3;
+    /// Index of the batch policy field
+    const BATCH_POLICY: usize = 4;
+    /// Index of the first default rclone option field
+    const DEFAULTS: usize = 5;
+
+    /// Form with the current settings
+    pub fn new(config: &GalionConfig) -> Self {
+        let field = |label, value: String| EditField {
+            label,
+            input: TextInput::new(value),
+        };
+        let mut fields = vec![
+            field("Theme (default, dark, light)", config.theme.to_string()),
+            field("Refresh rate in ms", config.refresh_rate_ms.to_string()),
+            field(
+                "Confirm the deletions (true/false)",
+                config.confirm_deletions.to_string(),
+            ),
+            field("Maximum running jobs", config.max_running_jobs.to_string()),
+            field(
+                "Batch policy (parallel, sequential)",
+                config.batch_policy.to_string(),
+            ),
+        ];
+        fields.extend(
+            SyncOptions::FIELDS
+                .into_iter()
+                .zip(config.defaults.field_values())
+                .map(|(label, value)| field(label, value)),
+        );
+        Self {
+            idx_string: Self::THEME,
+            fields,
+        }
+    }
+
+    /// Input of the selected field
+    pub fn selected(&mut self) -> &mut TextInput {
+        let idx = self.idx_string.min(self.fields.len() - 1);
+        &mut self.fields[idx].input
+    }
+
+    /// Select the next field
+    pub fn next_field(&mut self) {
+        self.idx_string = (self.idx_string + 1).min(self.fields.len() - 1);
+        let input = self.selected();
+        input.character_index = input.value.chars().count();
+    }
+
+    /// Select the previous field
+    pub fn previous_field(&mut self) {
+        self.idx_string = self.idx_string.saturating_sub(1);
+        let input = self.selected();
+        input.character_index = input.value.chars().count();
+    }
+
+    /// Value of a field
+    fn value(&self, idx: usize) -> &str {
+        self.fields
+            .get(idx)
+            .map_or("", |field| field.input.value.trim())
+    }
+
+    /// Apply the settings to the config
+    /// # Errors
+    /// Fails if a value is invalid, the config is unchanged
+    pub fn apply(&self, config: &mut GalionConfig) -> Result<(), GalionError> {
+        let invalid = |label: &str, value: &str| format!("invalid value '{value}' for {label}");
+        let number = |idx: usize| {
+            let value = self.value(idx);
+            value
+                .parse::<u64>()
+                .ok()
+                .filter(|number| *number > 0)
+                .ok_or_else(|| invalid(self.fields[idx].label, value))
+        };
+        let theme = self.value(Self::THEME).parse()?;
+        let refresh_rate_ms = number(Self::REFRESH_RATE)?;
+        let confirm_deletions = self.value(Self::CONFIRM_DELETIONS).parse().map_err(|_| {
+            invalid(
+                self.fields[Self::CONFIRM_DELETIONS].label,
+                self.value(Self::CONFIRM_DELETIONS),
+            )
+        })?;
+        let max_running_jobs = usize::try_from(number(Self::MAX_RUNNING_JOBS)?)
+            .map_err(|_| "too many running jobs")?;
+        let batch_policy = self.value(Self::BATCH_POLICY).parse()?;
+        let defaults = self.fields[Self::DEFAULTS..]
+            .iter()
+            .map(|field| field.input.value.as_str())
+            .collect::<Vec<&str>>();
+        config.defaults = SyncOptions::from_fields(&defaults)?;
+        config.theme = theme;
+        config.refresh_rate_ms = refresh_rate_ms;
+        config.confirm_deletions = confirm_deletions;
+        config.max_running_jobs = max_running_jobs;
+        config.batch_policy = batch_policy;
+        Ok(())
+    }
+}
@@ -0,0 +1,240 @@
+//! Statistics of the remotes, computed from the job history
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use time::format_description::well_known::Rfc3339;
+use time::{Duration, OffsetDateTime};
+
+use crate::browser::format_size;
+use crate::errors::GalionError;
+use crate::history::HistoryEntry;
+use crate::report::csv_line;
+
+/// Time window of the statistics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum StatsWindow {
+    /// last 7 days
+    Week,
+    /// last 30 days
+    #[default]
+    Month,
+    /// last 365 days
+    Year,
+    /// whole history
+    All,
+}
+
+impl StatsWindow {
+    /// Next window, cycling
+    pub fn next(self) -> Self {
+        match self {
+            Self::Week => Self::Month,
+            Self::Month => Self::Year,
+            Self::Year => Self::All,
+            Self::All => Self::Week,
+        }
+    }
+
+    /// Length of the window, `None` for the whole history
+    fn duration(self) -> Option<Duration> {
+        match self {
+            Self::Week => Some(Duration::days(7)),
+            Self::Month => Some(Duration::days(30)),
+            Self::Year => Some(Duration::days(365)),
+            Self::All => None,
+        }
+    }
+}
+
+impl Display for StatsWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Week => write!(f, "7 days"),
+            Self::Month => write!(f, "30 days"),
+            Self::Year => write!(f, "365 days"),
+            Self::All => write!(f, "all"),
+        }
+    }
+}
+
+/// Statistics of a remote
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct RemoteStats {
+    /// remote name
+    pub(crate) name: String,
+    /// number of jobs
+    pub(crate) jobs: u64,
+    /// number of failed jobs
+    pub(crate) failures: u64,
+    /// transferred bytes
+    pub(crate) bytes: u64,
+    /// transferred files
+    pub(crate) files: u64,
+    /// total duration in seconds
+    pub(crate) duration: f64,
+}
+
+impl RemoteStats {
+    /// Header of the CSV export
+    const CSV_HEADER: [&str; 7] = [
+        "remote",
+        "jobs",
+        "failures",
+        "failure_rate",
+        "bytes",
+        "files",
+        "average_duration",
+    ];
+
+    /// Average duration of the jobs in seconds
+    #[allow(clippy::cast_precision_loss)] // job counts are small
+    pub fn average_duration(&self) -> f64 {
+        if self.jobs == 0 {
+            0.0
+        } else {
+            self.duration / self.jobs as f64
+        }
+    }
+
+    /// Failure rate in percent
+    #[allow(clippy::cast_precision_loss)] // job counts are small
+    pub fn failure_rate(&self) -> f64 {
+        if self.jobs == 0 {
+            0.0
+        } else {
+            self.failures as f64 * 100.0 / self.jobs as f64
+        }
+    }
+
+    /// Cells of the stats table
+    pub fn to_row(&self) -> [String; 6] {
+        [
+            self.name.clone(),
+            self.jobs.to_string(),
+            format!("{:.0}%", self.failure_rate()),
+            format_size(self.bytes),
+            self.files.to_string(),
+            format!("{:.1}s", self.average_duration()),
+        ]
+    }
+
+    /// Statistics of each remote over a window, the remotes transferring the most first
+    pub fn compute(
+        entries: &[HistoryEntry],
+        window: StatsWindow,
+        now: OffsetDateTime,
+    ) -> Vec<Self> {
+        let since = window.duration().map(|duration| now - duration);
+        let mut by_remote = BTreeMap::<&str, Self>::new();
+        for entry in entries {
+            if let Some(since) = since {
+                // the entries without a valid start time are only in the whole history
+                match OffsetDateTime::parse(&entry.start_time, &Rfc3339) {
+                    Ok(start) if start >= since => {}
+                    _ => continue,
+                }
+            }
+            let stats = by_remote.entry(&entry.name).or_insert_with(|| Self {
+                name: entry.name.clone(),
+                ..Self::default()
+            });
+            stats.jobs += 1;
+            stats.failures += u64::from(!entry.success);
+            stats.bytes += entry.bytes;
+            stats.files += entry.files;
+            stats.duration += entry.duration;
+        }
+        let mut stats = by_remote.into_values().collect::<Vec<Self>>();
+        stats.sort_by_key(|remote| Reverse(remote.bytes));
+        stats
+    }
+
+    /// Write the statistics as CSV in the report directory, returns its path
+    /// # Errors
+    /// Fails if the file cannot be written
+    pub fn export(stats: &[Self], window: StatsWindow, dir: &Path) -> Result<PathBuf, GalionError> {
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(format!(
+            "stats-{}.csv",
+            window.to_string().replace(' ', "-")
+        ));
+        let mut content = csv_line(&Self::CSV_HEADER);
+        for remote in stats {
+            content.push_str(&csv_line(&[
+                remote.name.clone(),
+                remote.jobs.to_string(),
+                remote.failures.to_string(),
+                format!("{:.1}", remote.failure_rate()),
+                remote.bytes.to_string(),
+                remote.files.to_string(),
+                format!("{:.1}", remote.average_duration()),
+            ]));
+        }
+        std::fs::write(&path, content)?;
+        Ok(path)
+    }
+}
+
+/// Median duration in seconds of the successful jobs of a remote, `None` with too few jobs
+pub(crate) fn median_duration<'a, I: IntoIterator<Item = &'a HistoryEntry>>(
+    entries: I,
+    name: &str,
+) -> Option<f64> {
+    /// fewer jobs do not give a typical duration
+    const MIN_JOBS: usize = 3;
+    let mut durations = entries
+        .into_iter()
+        .filter(|entry| entry.name == name && entry.success && entry.duration > 0.0)
+        .map(|entry| entry.duration)
+        .collect::<Vec<f64>>();
+    if durations.len() < MIN_JOBS {
+        return None;
+    }
+    durations.sort_by(f64::total_cmp);
+    let middle = durations.len() / 2;
+    Some(if durations.len() % 2 == 0 {
+        f64::midpoint(durations[middle - 1], durations[middle])
+    } else {
+        durations[middle]
+    })
+}
+
+/// Statistics popup
+#[derive(Debug)]
+pub(crate) struct StatsView {
+    /// history, with the finished jobs still in memory
+    entries: Vec<HistoryEntry>,
+    /// shown window
+    pub(crate) window: StatsWindow,
+    /// statistics of the window
+    pub(crate) stats: Vec<RemoteStats>,
+}
+
+impl StatsView {
+    /// Statistics of a window
+    pub fn new(entries: Vec<HistoryEntry>, window: StatsWindow) -> Self {
+        let mut view = Self {
+            entries,
+            window,
+            stats: Vec::new(),
+        };
+        view.refresh();
+        view
+    }
+
+    /// Show the next window
+    pub fn next_window(&mut self) {
+        self.window = self.window.next();
+        self.refresh();
+    }
+
+    /// Compute the statistics of the window
+    fn refresh(&mut self) {
+        let now = OffsetDateTime::now_utc();
+        self.stats = RemoteStats::compute(&self.entries, self.window, now);
+    }
+}
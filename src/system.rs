@@ -0,0 +1,150 @@
+//! State of the machine, used to defer or slow down the syncs
+
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+/// What to do with the syncs on a metered connection
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MeteredPolicy {
+    /// sync as usual
+    #[default]
+    Ignore,
+    /// wait for an unmetered connection to launch the syncs
+    Pause,
+    /// limit the bandwidth of rclone to `metered_bwlimit`
+    Limit,
+}
+
+/// Is the connection metered, `None` if the platform doesn't tell
+///
+/// Only `NetworkManager` exposes it, through D-Bus
+pub(crate) fn is_metered() -> Option<bool> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    let output = Command::new("busctl")
+        .args([
+            "get-property",
+            "org.freedesktop.NetworkManager",
+            "/org/freedesktop/NetworkManager",
+            "org.freedesktop.NetworkManager",
+            "Metered",
+        ])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    // `u 1`: 1 is yes and 3 is guessed yes, 2 and 4 are no, 0 is unknown
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match stdout.split_whitespace().nth(1)? {
+        "1" | "3" => Some(true),
+        "2" | "4" => Some(false),
+        _ => None,
+    }
+}
+
+/// Power source of the machine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PowerState {
+    /// is the machine running on its battery
+    pub(crate) on_battery: bool,
+    /// charge of the battery in percent
+    pub(crate) charge: Option<u8>,
+}
+
+/// Power source of the machine, `None` without battery or if the platform doesn't tell
+pub(crate) fn power_state() -> Option<PowerState> {
+    if cfg!(target_os = "linux") {
+        linux_power_state()
+    } else if cfg!(target_os = "macos") {
+        macos_power_state()
+    } else {
+        None
+    }
+}
+
+/// Power source from `/sys/class/power_supply`
+fn linux_power_state() -> Option<PowerState> {
+    let read = |path: &std::path::Path, file: &str| {
+        std::fs::read_to_string(path.join(file))
+            .map(|content| content.trim().to_string())
+            .unwrap_or_default()
+    };
+    std::fs::read_dir("/sys/class/power_supply")
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .find(|path| read(path, "type") == "Battery")
+        .map(|battery| PowerState {
+            on_battery: read(&battery, "status") == "Discharging",
+            charge: read(&battery, "capacity").parse().ok(),
+        })
+}
+
+/// Power source from `pmset -g batt`
+fn macos_power_state() -> Option<PowerState> {
+    let output = Command::new("pmset")
+        .args(["-g", "batt"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // `Now drawing from 'Battery Power'` then ` -InternalBattery-0 (id=1)	85%; discharging;`
+    // no charge without battery
+    let charge = stdout
+        .split_whitespace()
+        .find_map(|word| word.strip_suffix("%;"))?
+        .parse()
+        .ok()?;
+    Some(PowerState {
+        on_battery: stdout.contains("'Battery Power'"),
+        charge: Some(charge),
+    })
+}
+
+/// Load throttling settings
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LoadThrottle {
+    /// load average per CPU above which the machine is busy
+    #[serde(default = "LoadThrottle::default_max_load")]
+    pub(crate) max_load: f64,
+    /// bandwidth limit while the machine is busy
+    #[serde(default = "LoadThrottle::default_bwlimit")]
+    pub(crate) bwlimit: String,
+}
+
+impl LoadThrottle {
+    /// Default for [`LoadThrottle::max_load`]
+    fn default_max_load() -> f64 {
+        1.0
+    }
+
+    /// Default for [`LoadThrottle::bwlimit`]
+    fn default_bwlimit() -> String {
+        "2M".to_string()
+    }
+
+    /// Is the machine busy, `false` if the platform doesn't tell
+    pub fn is_busy(&self) -> bool {
+        let cpus = std::thread::available_parallelism().map_or(1, std::num::NonZero::get);
+        load_average()
+            .is_some_and(|load| load / f64::from(u32::try_from(cpus).unwrap_or(1)) > self.max_load)
+    }
+}
+
+/// Load average of the last minute, it includes the processes waiting for the disks
+fn load_average() -> Option<f64> {
+    let content = if cfg!(target_os = "linux") {
+        std::fs::read_to_string("/proc/loadavg").ok()?
+    } else if cfg!(target_os = "macos") {
+        // `{ 1.23 1.10 0.98 }`
+        let output = Command::new("sysctl")
+            .args(["-n", "vm.loadavg"])
+            .output()
+            .ok()?;
+        String::from_utf8_lossy(&output.stdout).replace(['{', '}'], "")
+    } else {
+        return None;
+    };
+    content.split_whitespace().next()?.parse().ok()
+}
@@ -0,0 +1,68 @@
+//! Remote templates, instantiated with the values of their variables
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::errors::GalionError;
+use crate::remote::RemoteConfiguration;
+
+/// Remote whose name and paths contain variables like `{project}`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RemoteTemplate {
+    /// template name
+    pub(crate) name: String,
+    /// variables of the template, used as `{variable}`
+    pub(crate) variables: Vec<String>,
+    /// remote with the variables
+    pub(crate) remote: RemoteConfiguration,
+}
+
+impl RemoteTemplate {
+    /// Parse the values of the variables: `key=value`
+    /// # Errors
+    /// Fails if a value has no `=`
+    pub fn parse_values<'a, I: IntoIterator<Item = &'a str>>(
+        values: I,
+    ) -> Result<BTreeMap<String, String>, GalionError> {
+        values
+            .into_iter()
+            .map(|value| {
+                value
+                    .split_once('=')
+                    .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+                    .ok_or_else(|| format!("invalid value '{value}' - use key=value").into())
+            })
+            .collect()
+    }
+
+    /// Remote with the variables replaced by their values
+    /// # Errors
+    /// Fails if a variable has no value
+    pub fn instantiate(
+        &self,
+        values: &BTreeMap<String, String>,
+    ) -> Result<RemoteConfiguration, GalionError> {
+        if let Some(missing) = self.variables.iter().find(|v| !values.contains_key(*v)) {
+            return Err(format!(
+                "no value for '{missing}' of the template {} - use {missing}=value",
+                self.name
+            )
+            .into());
+        }
+        let replace = |text: &str| {
+            self.variables
+                .iter()
+                .fold(text.to_string(), |text, variable| {
+                    text.replace(&format!("{{{variable}}}"), &values[variable])
+                })
+        };
+        let replace_opt = |text: &Option<String>| text.as_deref().map(replace);
+        let mut remote = self.remote.clone();
+        remote.remote_name = replace(&remote.remote_name);
+        remote.remote_src = replace_opt(&remote.remote_src);
+        remote.remote_dest = replace_opt(&remote.remote_dest);
+        remote.backup_dir = replace_opt(&remote.backup_dir);
+        remote.ping_url = replace_opt(&remote.ping_url);
+        Ok(remote)
+    }
+}
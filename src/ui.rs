@@ -1,12 +1,16 @@
 //! Galion ui using ratatui
 
-use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, poll};
+use ratatui::crossterm::event::{
+    self, DisableBracketedPaste, EnableBracketedPaste, Event, KeyCode, KeyEvent, KeyEventKind,
+    KeyModifiers, poll,
+};
+use ratatui::crossterm::execute;
 use ratatui::layout::{Alignment, Flex, Margin, Position, Rect};
 use ratatui::style::{Modifier, Style, Stylize};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{
-    Borders, Cell, Clear, HighlightSpacing, Row, Scrollbar, ScrollbarOrientation, ScrollbarState,
-    Table, TableState, Wrap,
+    Borders, Cell, Clear, HighlightSpacing, LineGauge, List, ListItem, ListState, Row, Scrollbar,
+    ScrollbarOrientation, ScrollbarState, Table, TableState, Wrap,
 };
 use ratatui::{
     DefaultTerminal, Frame,
@@ -15,241 +19,132 @@ use ratatui::{
     text::Text,
     widgets::{Block, Paragraph},
 };
-use serde_json::Value;
+use serde_json::{Value, json};
+use std::cmp::Reverse;
 use std::collections::BTreeMap;
-use std::fmt::Display;
-use std::sync::mpsc::{self, Receiver, Sender};
-use std::thread::sleep;
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::time::{Duration, Instant};
 use std::{io, thread};
 use time::{OffsetDateTime, macros::format_description};
 
-use crate::app::GalionConfig;
+use crate::app::{FileWatcher, GalionConfig};
+use crate::bisync::{
+    BisyncConflicts, BisyncResolution, BisyncRun, CONFLICT_RULE, FileConflict, needs_resync,
+};
+use crate::browser::{Browser, format_size, join_path};
+use crate::builder::{RemoteWizard, UnionBuilder, UnionFocus, WizardField, WizardStep};
+use crate::filters::FilterEditor;
+use crate::health::Health;
+use crate::history::runs_report;
+use crate::i18n::{Locale, tr, tr_with};
+use crate::input::TextInput;
+use crate::jobs::{
+    ExecutionPolicy, JobKind, JobState, JobsList, ResultJob, ShutdownPolicy, SyncJob, SyncJobData,
+    background_thread,
+};
+use crate::keys::{
+    BISYNC_KEYS, BROWSE_KEYS, EDIT_KEYS, FILTER_KEYS, NORMAL_KEYS, STATS_KEYS, cheat_sheet,
+};
 use crate::librclone::Rclone;
-use crate::remote::{ConfigOrigin, EditRemote, RemoteConfiguration};
-use crate::{GalionApp, GalionError};
-
-/// [`SyncJob`] data
-#[derive(Debug, Clone, PartialEq, PartialOrd, Ord, Eq)]
-pub struct SyncJobData {
-    /// sync job id
-    job_id: u64,
-    /// sync job name
-    name: String,
-    /// sync job src
-    src: String,
-    /// sync job dest
-    dest: String,
-}
-
-/// rclone job type
-pub type JobsList = BTreeMap<SyncJobData, JobState>;
-
-/// Job statut
-#[derive(Debug)]
-pub enum ResultJob {
-    /// Exit
-    Exit,
-    /// Sync
-    Sync(JobsList),
-}
+use crate::librclone::rclone::{RcRemoteConfig, RcStats, RcTransfer};
+use crate::options::SyncOptions;
+use crate::remote::{
+    ConfigOrigin, EditField, EditRemote, OverlapPolicy, Priority, RclonePath, RemoteConfiguration,
+    SpaceCheck, SyncRecord, parse_duration,
+};
+use crate::report::CompareReport;
+use crate::serve::{ServeProtocol, Server};
+use crate::session::Session;
+use crate::settings::{SettingsForm, Theme};
+use crate::stats::{RemoteStats, StatsView};
+use crate::{GalionApp, GalionArgs, GalionError};
 
-/// Job statut
+/// Event received by the ui, from the input thread or from the background thread
 #[derive(Debug)]
-pub enum SyncJob {
-    /// Exit
-    Exit,
-    /// Sync
-    Sync(SyncJobData),
-}
-
-/// Job status from rclone
-#[derive(Debug, PartialEq, Clone, serde::Deserialize, serde::Serialize)]
-pub struct JobStatus {
-    /// success status
-    success: bool,
-    /// duration
-    duration: f64,
-    /// error
-    error: String,
-    /// start time
-    #[serde(rename = "startTime")]
-    start_time: String,
-
-    /// Debug string
-    debug_str: Option<String>,
-}
-
-impl Display for JobStatus {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.error.is_empty() {
-            write!(f, "success: {}, duration: {}", self.success, self.duration)
-        } else {
-            write!(
-                f,
-                "success: {} ({}), duration: {}",
-                self.success, self.error, self.duration
-            )
-        }
-    }
-}
-
-/// Job state
-#[derive(Debug, PartialEq, Clone)]
-pub enum JobState {
-    /// Sent
-    Sent,
-    /// Waiting to finish
-    Pending(JobStatus),
-    /// Done
-    Done(JobStatus),
-}
-
-impl JobState {
-    /// Is this job waiting
-    fn is_waiting(&self) -> bool {
-        match self {
-            Self::Sent | Self::Pending(_) => true,
-            Self::Done(_) => false,
-        }
-    }
-
-    /// Is this job an error
-    fn success_color(&self) -> Color {
-        match self {
-            Self::Sent | Self::Pending(_) => Color::Blue,
-            Self::Done(s) => {
-                if s.success {
-                    Color::Green
-                } else {
-                    Color::Red
-                }
-            }
-        }
-    }
-}
-
-impl Display for JobState {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            JobState::Sent => write!(f, "sent"),
-            JobState::Pending(job_status) => {
-                write!(
-                    f,
-                    "waiting: start_time: {}",
-                    job_status.start_time, // job_status.debug_str
-                )
-            }
-            JobState::Done(job_status) => write!(f, "done: {job_status}"),
-        }
-    }
+pub enum UiEvent {
+    /// Terminal event
+    Terminal(Event),
+    /// Background thread result
+    Job(ResultJob),
+    /// Rclone details of a remote, fetched after the start of the ui
+    RemoteDetails(String, Box<RcRemoteConfig>),
 }
 
 impl GalionApp {
-    /// Background thread to use rclone
-    fn background_thread(
-        rclone: &Rclone,
-        tx_to_ui: &Sender<ResultJob>,
-        rx_to_ui: &Receiver<SyncJob>,
-    ) -> Result<(), GalionError> {
-        let thread_loop = || -> Result<(), GalionError> {
-            let mut tracking_jobs = JobsList::new();
-            loop {
-                let is_jobs_waiting = tracking_jobs.values().any(JobState::is_waiting);
-                let res_job = if is_jobs_waiting {
-                    for (job_sync_data, job_state) in tracking_jobs.clone() {
-                        if let JobState::Done(_) = job_state {
-                            // skip done job
-                        } else if let Ok(value_job_status) = rclone.job_status(job_sync_data.job_id)
-                        {
-                            // println!("{:?}", value_job_status);
-                            let is_finished = value_job_status.get("finished").cloned();
-                            let debug_str = value_job_status.to_string();
-                            let mut job_status: JobStatus =
-                                serde_json::from_value(value_job_status)?;
-                            job_status.debug_str = Some(debug_str);
-                            if let Some(Value::Bool(finished)) = is_finished
-                                && finished
-                            {
-                                tracking_jobs.insert(job_sync_data, JobState::Done(job_status));
-                            } else {
-                                tracking_jobs.insert(job_sync_data, JobState::Pending(job_status));
-                            }
-                        }
-                    }
-                    match tx_to_ui.send(ResultJob::Sync(tracking_jobs.clone())) {
-                        Ok(a) => a,
-                        Err(_) => return Ok(()),
-                    }
-                    match rx_to_ui.try_recv() {
-                        Ok(job) => job,
-                        Err(mpsc::TryRecvError::Empty) => {
-                            sleep(Duration::from_millis(500));
-                            continue;
-                        }
-                        Err(mpsc::TryRecvError::Disconnected) => return Ok(()),
-                    }
-                } else {
-                    match rx_to_ui.recv() {
-                        Ok(job) => job,
-                        Err(_) => {
-                            return Ok(());
-                        }
-                    }
-                };
-                match res_job {
-                    SyncJob::Exit => {
+    /// Input thread, forwards the terminal events to the ui until `stop` is set
+    fn input_thread(tx_to_ui: &Sender<UiEvent>, stop: &AtomicBool) -> Result<(), GalionError> {
+        let input_loop = || -> Result<(), GalionError> {
+            while !stop.load(Ordering::Relaxed) {
+                if poll(INPUT_POLL)? {
+                    let event = event::read()?;
+                    if tx_to_ui.send(UiEvent::Terminal(event)).is_err() {
+                        // ui exited
                         return Ok(());
                     }
-                    SyncJob::Sync(sync_data_received) => {
-                        let job =
-                            rclone.sync(&sync_data_received.src, &sync_data_received.dest, true)?;
-                        if let Some(Value::Number(jobid)) = job.get("jobid")
-                            && let Some(job_id) = jobid.as_u64()
-                        {
-                            let mut sync_data = sync_data_received.clone();
-                            sync_data.job_id = job_id;
-                            tracking_jobs.insert(sync_data, JobState::Sent);
-                        }
-                    }
                 }
             }
+            Ok(())
         };
-        match thread_loop() {
-            Ok(()) => Ok(()),
-            Err(err) => {
-                eprintln!("Background thread crashed: {err}");
-                if let Err(e) = tx_to_ui.send(ResultJob::Exit) {
-                    eprintln!("Failed to stop UI {e}");
-                }
-                Err(GalionError::new(format!(
-                    "Background thread crashed: {err}"
-                )))
-            }
+        let res = input_loop();
+        if res.is_err() && tx_to_ui.send(UiEvent::Job(ResultJob::Exit)).is_err() {
+            // ui already exited
         }
+        res
     }
 
     /// Run the galion ui - final methods so it's `mut self` and not `&mut self`
     /// # Errors
     /// Errors when ui errors
     pub fn run_tui(mut self) -> Result<(), GalionError> {
+        crate::i18n::set_locale(self.config.language.unwrap_or_else(Locale::detect));
+        let stop_input = &AtomicBool::new(false);
         // thread scope assert that the thread will not outlive the function
         thread::scope(|s| {
             let rclone = &self.rclone;
+            let config = self.config.clone();
             let (tx_to_thread, rx_to_ui) = mpsc::channel();
-            let (tx_to_ui, rx_from_thread) = mpsc::channel();
+            // a single channel for the terminal events and the job updates
+            let (tx_to_ui, rx_events) = mpsc::channel();
+            let tx_input = tx_to_ui.clone();
+            let tx_details = tx_to_ui.clone();
             let sync_handler: thread::ScopedJoinHandle<'_, Result<(), GalionError>> =
-                s.spawn(move || Self::background_thread(rclone, &tx_to_ui, &rx_to_ui));
+                s.spawn(move || background_thread(rclone, &tx_to_ui, &rx_to_ui, &config));
 
             let mut terminal = ratatui::init();
-            let app_result = TuiApp::new(&mut self.config, rx_from_thread, tx_to_thread)
-                .run(&mut terminal)
-                .map_err(|e| GalionError::new(e.to_string()));
+            // a paste arrives as a single event, not as key presses, if the terminal supports it
+            let _ = execute!(io::stdout(), EnableBracketedPaste);
+            let input_handler: thread::ScopedJoinHandle<'_, Result<(), GalionError>> =
+                s.spawn(move || Self::input_thread(&tx_input, stop_input));
+            // the details are only needed for display, the ui doesn't wait for them
+            let pending_details = self.config.pending_details();
+            s.spawn(move || {
+                GalionConfig::fetch_remote_details(rclone, &pending_details, &|name, details| {
+                    let event = UiEvent::RemoteDetails(name.to_string(), Box::new(details));
+                    tx_details.send(event).is_ok()
+                });
+            });
+            let app_result = TuiApp::new(
+                &mut self.config,
+                &self.galion_args,
+                rclone,
+                rx_events,
+                tx_to_thread,
+            )
+            .run(&mut terminal)
+            .map_err(|e| GalionError::new(e.to_string()));
+            stop_input.store(true, Ordering::Relaxed);
+            let input_result = input_handler
+                .join()
+                .map_err(|_e| "Error joining the input thread")?; // join error
+            let _ = execute!(io::stdout(), DisableBracketedPaste);
             ratatui::restore(); // Clean exit terminal
             let thread_result = sync_handler
                 .join()
                 .map_err(|_e| "Error joining the thread")?; // join error
             thread_result?; // thread error
+            input_result?; // input thread error
             if !self.galion_args.hide_banner {
                 println!("  ~Galion~");
             }
@@ -267,8 +162,75 @@ enum TuiMode {
     Error(String),
     /// Delete mode - confirmation
     Delete,
+    /// Delete a remote of the rclone config - confirmation, then a second one when true
+    DeleteRclone(String, bool),
+    /// Delete a remote file - confirmation with the full path
+    DeleteFile(String),
+    /// Restore from a backup dir - confirmation of the job
+    Restore(SyncJobData),
+    /// Union remote builder
+    Union(UnionBuilder),
+    /// New rclone remote wizard
+    Wizard(RemoteWizard),
+    /// Bisync conflicts or resync - choice of the resolution
+    Bisync(BisyncConflicts),
+    /// Quit mode - jobs are still running
+    Quit,
     /// Edit string mode
-    EditString(EditRemote),
+    EditString(Box<EditRemote>),
+    /// Read-only details popup: title, content, scroll
+    Details(String, String, u16),
+    /// Tree explorer of a remote
+    Browse,
+    /// Text prompt
+    Prompt(Prompt),
+    /// Statistics of the remotes
+    Stats(StatsView),
+    /// Settings form
+    Settings(SettingsForm),
+    /// Filter rules of a remote
+    Filters(FilterEditor),
+}
+
+/// Action run when a prompt is validated
+#[derive(Debug)]
+enum PromptAction {
+    /// Search files in a rclone path
+    Search(String),
+    /// Rename the selected item of the explorer
+    Rename,
+    /// Download a remote file to a local directory
+    Download(String),
+    /// Upload a local file to a remote directory
+    Upload(String),
+    /// Create an alias remote of a remote directory
+    Alias(String),
+    /// Obscure a password
+    Obscure,
+    /// Write the comparison report of a source and a destination
+    Compare(String, String),
+    /// Launch the job of a remote template
+    Template,
+    /// Run all the remotes, or a group, as a batch
+    RunBatch(Option<String>),
+}
+
+impl PromptAction {
+    /// Is the input hidden
+    fn is_secret(&self) -> bool {
+        matches!(self, Self::Obscure)
+    }
+}
+
+/// Text prompt
+#[derive(Debug)]
+struct Prompt {
+    /// title of the prompt
+    title: String,
+    /// text input
+    input: TextInput,
+    /// action run with the input
+    action: PromptAction,
 }
 
 /// Galion Tui app
@@ -276,8 +238,12 @@ enum TuiMode {
 pub struct TuiApp<'a> {
     /// app
     app_config: &'a mut GalionConfig,
-    /// receiver of job
-    pub rx_from_thread: Receiver<ResultJob>,
+    /// galion arguments
+    galion_args: &'a GalionArgs,
+    /// rclone instance, used to lazily load remote details
+    rclone: &'a Rclone,
+    /// receiver of terminal events and job updates
+    pub rx_events: Receiver<UiEvent>,
     /// sender of sync job
     pub tx_to_thread: Sender<SyncJob>,
     /// Map of jobs
@@ -285,23 +251,50 @@ pub struct TuiApp<'a> {
     /// should exit
     exit: bool,
     /// longest item length
-    longest_item_lens: (u16, u16, u16),
+    longest_item_lens: (u16, u16, u16, u16),
+    /// cached cells of the remotes table
+    table_rows: Vec<[String; 4]>,
     /// state of the table
     state: TableState,
     /// state of the scrollbar
     scroll_state: ScrollbarState,
     /// Error display
     mode: TuiMode,
+    /// watcher of the galion and rclone config files
+    config_watcher: FileWatcher,
+    /// last check of the config files
+    last_config_check: Instant,
+    /// last listing of the rclone remotes
+    last_remotes_refresh: Instant,
+    /// notification message and its creation time
+    notification: Option<(String, Instant)>,
+    /// waiting for the jobs to finish before exiting
+    draining: bool,
+    /// non-fatal error banner and its creation time
+    banner: Option<(String, Instant)>,
+    /// state of the ui restored from the last launch
+    session: Session,
+    /// open tree explorer, kept under the popups
+    browser: Option<Browser>,
+    /// running servers of remotes
+    servers: Vec<Server>,
+    /// health of the destinations, by remote name
+    health: BTreeMap<String, Health>,
 }
 
 /// Item size
 const ITEM_HEIGHT: usize = 1;
 
+/// Maximum size of a previewed file
+const PREVIEW_MAX_SIZE: u64 = 256 * 1024;
+
+/// Input thread poll time, to check if the ui exited
+const INPUT_POLL: Duration = Duration::from_millis(100);
+
 /// Tiny helper
-fn constraint_len_calculator(items: &[RemoteConfiguration]) -> (u16, u16, u16) {
-    let mut longest_item_lens = (0, 0, 0);
-    for item in items {
-        let item_lens = item.to_table_row();
+fn constraint_len_calculator(rows: &[[String; 4]]) -> (u16, u16, u16, u16) {
+    let mut longest_item_lens = (0, 0, 0, 0);
+    for item_lens in rows {
         longest_item_lens.0 = longest_item_lens
             .0
             .max(u16::try_from(item_lens[0].len()).unwrap_or(0));
@@ -311,13 +304,93 @@ fn constraint_len_calculator(items: &[RemoteConfiguration]) -> (u16, u16, u16) {
         longest_item_lens.2 = longest_item_lens
             .2
             .max(u16::try_from(item_lens[2].len()).unwrap_or(0));
+        longest_item_lens.3 = longest_item_lens
+            .3
+            .max(u16::try_from(item_lens[3].len()).unwrap_or(0));
     }
     longest_item_lens
 }
 
+/// Key helpers from the keys and the translation keys of their actions
+fn hints(helpers: &[(&'static str, &'static str)]) -> Vec<Span<'static>> {
+    let mut spans = Vec::with_capacity(helpers.len() * 2);
+    for (idx, (key, action)) in helpers.iter().enumerate() {
+        let separator = if idx + 1 < helpers.len() { " | " } else { "" };
+        spans.push(key.bold());
+        spans.push(format!(" {}{separator}", tr(action)).into());
+    }
+    spans
+}
+
+/// Format an `operations/stat` item for the details popup
+fn format_stat(item: &Value) -> String {
+    let field = |key: &str| {
+        item.get(key)
+            .map(|value| {
+                value
+                    .as_str()
+                    .map_or_else(|| value.to_string(), String::from)
+            })
+            .unwrap_or_default()
+    };
+    let is_dir = item.get("IsDir").and_then(Value::as_bool).unwrap_or(false);
+    let mut lines = vec![
+        format!("path: {}", field("Path")),
+        format!("type: {}", if is_dir { "directory" } else { "file" }),
+        format!("size: {}", field("Size")),
+        format!("modtime: {}", field("ModTime")),
+        format!("mime type: {}", field("MimeType")),
+    ];
+    if let Some(hashes) = item.get("Hashes").and_then(Value::as_object) {
+        lines.push("hashes:".to_string());
+        for (hash_name, hash) in hashes {
+            lines.push(format!(
+                "  {hash_name}: {}",
+                hash.as_str().unwrap_or_default()
+            ));
+        }
+    }
+    lines.join("\n")
+}
+
+/// Progress of the biggest files being transferred, with a bar each
+fn format_transfers(stats: &RcStats) -> String {
+    /// number of files shown
+    const SHOWN: usize = 10;
+    /// width of the bars
+    const BAR_WIDTH: usize = 20;
+    let mut transfers = stats.transferring.iter().collect::<Vec<&RcTransfer>>();
+    transfers.sort_by_key(|transfer| Reverse(transfer.size));
+    let mut lines = vec![format!("transferring {} files:", transfers.len())];
+    for transfer in transfers.into_iter().take(SHOWN) {
+        let filled = usize::from(transfer.percentage.min(100)) * BAR_WIDTH / 100;
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // a positive speed
+        let speed = format_size(transfer.speed as u64);
+        lines.push(format!(
+            "[{}{}] {:>3}% {} / {} at {speed}/s  {}",
+            "#".repeat(filled),
+            "-".repeat(BAR_WIDTH - filled),
+            transfer.percentage,
+            format_size(transfer.bytes),
+            format_size(transfer.size),
+            transfer.name,
+        ));
+    }
+    lines.join("\n")
+}
+
 impl<'a> TuiApp<'a> {
-    /// UI poll time
-    const REFRESH: Duration = Duration::from_millis(500);
+    /// Time between two checks of the config files
+    const CONFIG_CHECK: Duration = Duration::from_secs(1);
+
+    /// Display time of a notification
+    const NOTIFICATION_TIME: Duration = Duration::from_secs(3);
+
+    /// Display time of the error banner
+    const BANNER_TIME: Duration = Duration::from_secs(30);
+
+    /// Maximum number of progress bars in the jobs panel
+    const MAX_GAUGES: usize = 8;
 
     /// App name and version
     const APP: &'static str = concat!(env!("CARGO_PKG_NAME"), "@", env!("CARGO_PKG_VERSION"));
@@ -325,42 +398,315 @@ impl<'a> TuiApp<'a> {
     /// Tui App
     pub fn new(
         app_config: &'a mut GalionConfig,
-        rx_from_thread: Receiver<ResultJob>,
+        galion_args: &'a GalionArgs,
+        rclone: &'a Rclone,
+        rx_events: Receiver<UiEvent>,
         tx_to_thread: Sender<SyncJob>,
     ) -> Self {
-        let remotes = app_config.remotes();
-        let longest_item_lens = constraint_len_calculator(remotes);
-        let remotes_len = remotes.len();
-        TuiApp {
+        let mut watched_files = vec![app_config.config_path.clone()];
+        if let Ok(rclone_config_path) = rclone.config_path() {
+            watched_files.push(PathBuf::from(rclone_config_path));
+        }
+        watched_files.extend(rclone.extra_config_files().into_iter().map(PathBuf::from));
+        let session = Session::load(&app_config.session_path());
+        let mut tui_app = TuiApp {
             app_config,
-            rx_from_thread,
+            galion_args,
+            rclone,
+            rx_events,
             tx_to_thread,
             jobs: JobsList::default(),
             exit: false,
-            longest_item_lens,
-            state: TableState::default().with_selected(0),
-            scroll_state: ScrollbarState::new(remotes_len * ITEM_HEIGHT),
+            longest_item_lens: (0, 0, 0, 0),
+            table_rows: Vec::new(),
+            state: TableState::default(),
+            scroll_state: ScrollbarState::default(),
             mode: TuiMode::Normal,
+            config_watcher: FileWatcher::new(watched_files),
+            last_config_check: Instant::now(),
+            last_remotes_refresh: Instant::now(),
+            notification: None,
+            draining: false,
+            banner: None,
+            session,
+            browser: None,
+            servers: Vec::new(),
+            health: BTreeMap::new(),
+        };
+        if galion_args.demo {
+            tui_app.banner = Some((
+                "Demo mode: the remotes and the jobs are fake, nothing is synced".to_string(),
+                Instant::now(),
+            ));
+        }
+        tui_app.refresh_table();
+        let selected = tui_app.session.selected.as_ref().and_then(|name| {
+            tui_app
+                .app_config
+                .remotes()
+                .iter()
+                .position(|remote| remote.remote_name == *name)
+        });
+        tui_app.select_remote(selected.unwrap_or(0));
+        tui_app
+    }
+
+    /// Recompute the cached table rows and column widths, to call when the remotes change
+    fn refresh_table(&mut self) {
+        let rows = self
+            .app_config
+            .remotes()
+            .iter()
+            .map(RemoteConfiguration::to_table_row)
+            .collect::<Vec<[String; 4]>>();
+        self.longest_item_lens = constraint_len_calculator(&rows);
+        self.scroll_state = self.scroll_state.content_length(rows.len() * ITEM_HEIGHT);
+        if let Some(idx) = self.state.selected() {
+            self.scroll_state = self.scroll_state.position(idx * ITEM_HEIGHT);
+        }
+        self.table_rows = rows
+            .into_iter()
+            .map(|row| row.map(|content| format!("\n{content}\n")))
+            .collect();
+    }
+
+    /// Select a remote row and load its details if needed
+    fn select_remote(&mut self, idx: usize) {
+        self.state.select(Some(idx));
+        self.scroll_state = self.scroll_state.position(idx * ITEM_HEIGHT);
+        match self.app_config.load_remote_details(self.rclone, idx) {
+            Ok(true) => self.refresh_table(),
+            Ok(false) => {}
+            Err(e) => self.new_error(format!("Failed to load the remote details: {e}")),
         }
     }
 
     /// runs the application's main loop until the user quits
     pub fn run(mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        terminal.draw(|frame| self.draw(frame))?;
         while !self.exit {
-            if let Ok(rx_from_thread) = self.rx_from_thread.try_recv() {
-                match rx_from_thread {
-                    ResultJob::Exit => self.exit = true,
-                    ResultJob::Sync(jobs_list) => {
-                        self.jobs = jobs_list;
-                    }
-                }
+            let refresh = Duration::from_millis(self.app_config.refresh_rate_ms.max(1));
+            match self.rx_events.recv_timeout(refresh) {
+                Ok(ui_event) => self.handle_ui_event(ui_event),
+                Err(RecvTimeoutError::Timeout) => {} // redraw the clock
+                Err(RecvTimeoutError::Disconnected) => self.exit = true,
+            }
+            // handle everything already queued before drawing
+            while let Ok(ui_event) = self.rx_events.try_recv() {
+                self.handle_ui_event(ui_event);
             }
+            self.check_config_changes();
+            self.check_rclone_remotes();
             terminal.draw(|frame| self.draw(frame))?;
-            self.handle_events()?;
+        }
+        self.save_session();
+        Ok(())
+    }
+
+    /// Save the state of the ui for the next launch
+    fn save_session(&mut self) {
+        self.session.selected = self
+            .state
+            .selected()
+            .and_then(|idx| self.app_config.remotes().get(idx))
+            .map(|remote| remote.remote_name.clone());
+        // the tui is closed, a session that cannot be saved is only lost
+        let _ = self.session.save(&self.app_config.session_path());
+    }
+
+    /// Reload the remotes when the galion or the rclone config file changed
+    fn check_config_changes(&mut self) {
+        // do not reload under an open popup, the change is picked up later
+        if !matches!(self.mode, TuiMode::Normal)
+            || self.last_config_check.elapsed() < Self::CONFIG_CHECK
+        {
+            return;
+        }
+        self.last_config_check = Instant::now();
+        if !self.config_watcher.changed() {
+            return;
+        }
+        if let Err(e) = self
+            .app_config
+            .reload(self.rclone, self.galion_args.ignore_duplicate_remote)
+        {
+            self.new_error(format!("Failed to reload the configuration: {e}"));
+            return;
+        }
+        self.refresh_table();
+        let last_idx = self.app_config.remotes().len().saturating_sub(1);
+        self.select_remote(self.state.selected().unwrap_or(0).min(last_idx));
+        self.notify("Configuration reloaded");
+    }
+
+    /// List the rclone remotes again when the refresh interval elapsed
+    fn check_rclone_remotes(&mut self) {
+        let Some(interval) = self
+            .app_config
+            .remotes_refresh_interval
+            .as_deref()
+            .and_then(|interval| parse_duration(interval).ok())
+        else {
+            return;
+        };
+        if matches!(self.mode, TuiMode::Normal) && self.last_remotes_refresh.elapsed() >= interval {
+            self.refresh_rclone_remotes(false);
+        }
+    }
+
+    /// Merge the new rclone remotes into the table, and drop the deleted ones
+    fn refresh_rclone_remotes(&mut self, notify_unchanged: bool) {
+        self.last_remotes_refresh = Instant::now();
+        let selected = self.selected_remote_name();
+        let (added, removed) = match self
+            .app_config
+            .refresh_rclone_remotes(self.rclone, self.galion_args.ignore_duplicate_remote)
+        {
+            Ok(changes) => changes,
+            Err(e) => {
+                self.new_error(format!("Failed to list the rclone remotes: {e}"));
+                return;
+            }
+        };
+        if added == 0 && removed == 0 {
+            if notify_unchanged {
+                self.notify("No new rclone remote");
+            }
+            return;
+        }
+        self.refresh_table();
+        let remotes = self.app_config.remotes();
+        let idx = remotes
+            .iter()
+            .position(|remote| remote.remote_name == selected)
+            .unwrap_or(0)
+            .min(remotes.len().saturating_sub(1));
+        self.select_remote(idx);
+        let config = Box::new(self.app_config.clone());
+        if self.tx_to_thread.send(SyncJob::Config(config)).is_err() {
+            // background thread already exited
+        }
+        self.notify(format!(
+            "Rclone remotes refreshed: {added} added, {removed} removed"
+        ));
+    }
+
+    /// Save the config, without reloading it as an external change
+    /// # Errors
+    /// Fails if the config cannot be saved
+    fn save_config(&mut self) -> Result<(), GalionError> {
+        self.app_config.save_config()?;
+        self.config_watcher.written(&self.app_config.config_path);
+        let config = Box::new(self.app_config.clone());
+        if self.tx_to_thread.send(SyncJob::Config(config)).is_err() {
+            // background thread already exited
         }
         Ok(())
     }
 
+    /// Show a notification in the bottom bar
+    fn notify<S: Into<String>>(&mut self, msg: S) {
+        self.notification = Some((msg.into(), Instant::now()));
+    }
+
+    /// Handle an event from the input thread or from the background thread
+    fn handle_ui_event(&mut self, ui_event: UiEvent) {
+        match ui_event {
+            UiEvent::Terminal(event) => self.handle_event(&event),
+            UiEvent::RemoteDetails(name, details) => {
+                if self.app_config.set_remote_details(&name, *details) {
+                    // the background thread can now sync the remote
+                    let config = Box::new(self.app_config.clone());
+                    if self.tx_to_thread.send(SyncJob::Config(config)).is_err() {
+                        // background thread already exited
+                    }
+                }
+                self.refresh_table();
+            }
+            UiEvent::Job(ResultJob::Exit) => self.exit = true,
+            UiEvent::Job(ResultJob::Error(msg)) => self.new_error(msg),
+            UiEvent::Job(ResultJob::Report(path)) => {
+                self.notify(format!("Batch summary written to {}", path.display()));
+            }
+            UiEvent::Job(ResultJob::Warning(msg)) => {
+                self.banner = Some((msg, Instant::now()));
+            }
+            UiEvent::Job(ResultJob::Health(name, health)) => {
+                if let Health::Failing(error) = &health
+                    && !matches!(self.health.get(&name), Some(Health::Failing(_)))
+                {
+                    self.banner = Some((
+                        format!("Health check of {name} failed: {error}"),
+                        Instant::now(),
+                    ));
+                }
+                self.health.insert(name, health);
+            }
+            UiEvent::Job(ResultJob::Benchmark(name, benchmark)) => match benchmark {
+                Ok(benchmark) if matches!(self.mode, TuiMode::Normal) => {
+                    let title = format!("Benchmark of {name}");
+                    self.mode = TuiMode::Details(title, benchmark.report(), 0);
+                }
+                Ok(benchmark) => {
+                    self.notify(format!("Benchmark of {name}: {}", benchmark.summary()));
+                }
+                Err(e) => self.new_error(format!("Benchmark of {name} failed: {e}")),
+            },
+            UiEvent::Job(ResultJob::DryRun(name, dry_run)) => match dry_run {
+                Ok(dry_run) => {
+                    let title = format!("Dry run of {name} - (arrow_right) to sync");
+                    self.mode = TuiMode::Details(title, dry_run.report(), 0);
+                }
+                Err(e) => self.new_error(format!("Dry run of {name} failed: {e}")),
+            },
+            UiEvent::Job(ResultJob::Crashed(msg)) => {
+                self.banner = Some((
+                    format!("Background thread restarted after a crash: {msg}"),
+                    Instant::now(),
+                ));
+            }
+            UiEvent::Job(ResultJob::Recorded(records)) => self.record_syncs(&records),
+            UiEvent::Job(ResultJob::Sync(jobs_list)) => {
+                let finished_bisyncs = jobs_list
+                    .iter()
+                    .filter(|(job_data, job_state)| {
+                        job_data.kind == JobKind::Bisync
+                            && !job_state.is_waiting()
+                            && self.jobs.get(job_data).is_some_and(JobState::is_waiting)
+                    })
+                    .map(|(job_data, job_state)| (job_data.clone(), job_state.clone()))
+                    .collect::<Vec<(SyncJobData, JobState)>>();
+                self.jobs = jobs_list;
+                for (job_data, job_state) in finished_bisyncs {
+                    self.check_bisync(job_data, &job_state);
+                }
+            }
+        }
+    }
+
+    /// Save the sync states counted by the background thread: the date of the last successful sync
+    /// and the consecutive failures of the remotes
+    fn record_syncs(&mut self, records: &[SyncRecord]) {
+        let quarantined = self.app_config.apply_sync_records(records);
+        if !quarantined.is_empty() {
+            self.banner = Some((
+                format!(
+                    "Quarantined after {} failures, skipped by the batches: {}",
+                    self.app_config.quarantine_after,
+                    quarantined.join(", ")
+                ),
+                Instant::now(),
+            ));
+        }
+        self.refresh_table();
+        if let Err(e) = self.save_config() {
+            self.banner = Some((
+                format!("Cannot save the state of the syncs: {e}"),
+                Instant::now(),
+            ));
+        }
+    }
+
     /// Ratatui draw
     fn draw(&mut self, frame: &mut Frame<'_>) {
         let chunks = Layout::default()
@@ -374,20 +720,39 @@ impl<'a> TuiApp<'a> {
         self.render_table(frame, sub_chunks[0]);
         self.render_scrollbar(frame, sub_chunks[0]);
         self.render_right_panel(frame, sub_chunks[1]);
+        if let Some(browser) = &mut self.browser {
+            Self::render_browser(browser, frame, chunks[0]);
+        }
         self.render_bottom_bar(frame, chunks[1]);
         self.render_popup(frame);
     }
 
     /// Render the popup error
     fn render_error_popup(&self, frame: &mut Frame<'_>) {
-        let (title, content) = if let TuiMode::Error(error_msg) = &self.mode {
-            ("Error", error_msg.as_ref())
-        } else {
-            ("Delete remote configuration", "Delete the config (y/n)")
+        let (title, content) = match &self.mode {
+            TuiMode::Error(error_msg) => (tr("title.error"), error_msg.clone()),
+            TuiMode::Quit => (
+                tr("title.jobs_running"),
+                tr("message.jobs_running").to_string(),
+            ),
+            TuiMode::DeleteFile(path) => (tr("title.delete_file"), path.clone()),
+            TuiMode::Restore(job) => (tr("title.restore"), job.dest.clone()),
+            TuiMode::DeleteRclone(name, false) => (
+                tr("title.delete_rclone_remote"),
+                tr_with("message.delete_rclone_remote", &[("name", name)]),
+            ),
+            TuiMode::DeleteRclone(name, true) => (
+                tr("title.delete_rclone_remote"),
+                tr_with("message.delete_rclone_remote_sure", &[("name", name)]),
+            ),
+            _ => (
+                tr("title.delete_remote"),
+                tr("message.delete_remote").to_string(),
+            ),
         };
         let block = Block::bordered().title(title);
         let error_msg_widget = Paragraph::new(Line::from(content))
-            .style(Style::default().bg(Color::Black).fg(Color::White))
+            .style(self.app_config.theme.popup_style())
             .block(block);
         let vertical = Layout::vertical([Constraint::Length(3)]).flex(Flex::Center);
         let horizontal = Layout::horizontal([Constraint::Percentage(40)]).flex(Flex::Center);
@@ -397,197 +762,1265 @@ impl<'a> TuiApp<'a> {
         frame.render_widget(error_msg_widget, area);
     }
 
+    /// Render a form, like the popup to edit a remote
+    fn render_form_popup(
+        title: &str,
+        fields: &[EditField],
+        selected: usize,
+        theme: Theme,
+        frame: &mut Frame<'_>,
+    ) {
+        let height = u16::try_from(fields.len() * 2 + 2).unwrap_or(u16::MAX);
+        let area = frame
+            .area()
+            .centered(Constraint::Percentage(30), Constraint::Length(height));
+        frame.render_widget(Clear, area); //this clears out the background
+        let block = Block::bordered().title(title);
+        let inner_block_area = block.inner(area);
+        frame.render_widget(block, area);
+        let areas = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Length(1); fields.len() * 2])
+            .split(inner_block_area);
+        for (idx, (field, field_areas)) in fields.iter().zip(areas.chunks_exact(2)).enumerate() {
+            let style = if idx == selected {
+                Style::default().fg(theme.focus_color())
+            } else {
+                Style::default()
+            };
+            let title = Paragraph::new(field.label).style(style);
+            let input = Paragraph::new(field.input.value.as_str()).style(style);
+            frame.render_widget(title, field_areas[0]);
+            frame.render_widget(input, field_areas[1]);
+            if idx == selected {
+                frame.set_cursor_position(Position::new(
+                    // Draw the cursor at the current position in the input field.
+                    // This position is can be controlled via the left and right arrow key
+                    field_areas[1].x + u16::try_from(field.input.character_index).unwrap_or(0),
+                    field_areas[1].y,
+                ));
+            }
+        }
+    }
+
     /// Render the popup error
     fn render_popup(&self, frame: &mut Frame<'_>) {
         match &self.mode {
-            TuiMode::Error(_) | TuiMode::Delete => {
+            TuiMode::Error(_)
+            | TuiMode::Delete
+            | TuiMode::DeleteRclone(_, _)
+            | TuiMode::DeleteFile(_)
+            | TuiMode::Restore(_)
+            | TuiMode::Quit => {
                 self.render_error_popup(frame);
             }
-            TuiMode::EditString(edit_string) => {
+            TuiMode::EditString(edit_string) => Self::render_form_popup(
+                tr("title.edit"),
+                edit_string.visible_fields(),
+                edit_string.idx_string,
+                self.app_config.theme,
+                frame,
+            ),
+            TuiMode::Settings(form) => Self::render_form_popup(
+                tr("title.settings"),
+                &form.fields,
+                form.idx_string,
+                self.app_config.theme,
+                frame,
+            ),
+            TuiMode::Details(title, content, scroll) => {
                 let area = frame
                     .area()
-                    .centered(Constraint::Percentage(30), Constraint::Length(8));
+                    .centered(Constraint::Percentage(60), Constraint::Percentage(60));
                 frame.render_widget(Clear, area); //this clears out the background
-                let block = Block::bordered().title("Edit");
-                let inner_block_area = block.inner(area);
-                frame.render_widget(block, area);
-                let [
-                    area_title_name,
-                    area_name,
-                    area_title_src,
-                    area_src,
-                    area_title_dest,
-                    area_dest,
-                ] = Layout::default()
-                    .direction(Direction::Vertical)
-                    .constraints([
-                        Constraint::Length(1),
-                        Constraint::Length(1),
-                        Constraint::Length(1),
-                        Constraint::Length(1),
-                        Constraint::Length(1),
-                        Constraint::Length(1),
-                    ])
-                    .areas(inner_block_area);
-                let title_name =
-                    Paragraph::new("Remote name").style(match edit_string.idx_string {
-                        0 => Style::default().fg(Color::Yellow),
-                        _ => Style::default(),
-                    });
-                let input_name = Paragraph::new(edit_string.remote_name.as_str()).style(
-                    match edit_string.idx_string {
-                        0 => Style::default().fg(Color::Yellow),
-                        _ => Style::default(),
-                    },
-                );
-                frame.render_widget(title_name, area_title_name);
-                frame.render_widget(input_name, area_name);
-                if edit_string.idx_string == 0 {
-                    frame.set_cursor_position(Position::new(
-                        // Draw the cursor at the current position in the input field.
-                        // This position is can be controlled via the left and right arrow key
-                        area_name.x + u16::try_from(edit_string.character_index).unwrap_or(0),
-                        area_name.y,
-                    ));
-                }
-                let title_src =
-                    Paragraph::new("Remote source").style(match edit_string.idx_string {
-                        1 => Style::default().fg(Color::Yellow),
-                        _ => Style::default(),
-                    });
-                let input_src = Paragraph::new(edit_string.remote_src.as_str()).style(
-                    match edit_string.idx_string {
-                        1 => Style::default().fg(Color::Yellow),
-                        _ => Style::default(),
-                    },
-                );
-                frame.render_widget(title_src, area_title_src);
-                frame.render_widget(input_src, area_src);
-                if edit_string.idx_string == 1 {
-                    frame.set_cursor_position(Position::new(
-                        // Draw the cursor at the current position in the input field.
-                        // This position is can be controlled via the left and right arrow key
-                        area_src.x + u16::try_from(edit_string.character_index).unwrap_or(0),
-                        area_src.y,
-                    ));
-                }
-                let title_dest =
-                    Paragraph::new("Remote destination").style(match edit_string.idx_string {
-                        2 => Style::default().fg(Color::Yellow),
-                        _ => Style::default(),
-                    });
-                let input_dest = Paragraph::new(edit_string.remote_dest.as_str()).style(
-                    match edit_string.idx_string {
-                        2 => Style::default().fg(Color::Yellow),
-                        _ => Style::default(),
-                    },
-                );
-                frame.render_widget(title_dest, area_title_dest);
-                frame.render_widget(input_dest, area_dest);
-                if edit_string.idx_string == 2 {
-                    frame.set_cursor_position(Position::new(
-                        // Draw the cursor at the current position in the input field.
-                        // This position is can be controlled via the left and right arrow key
-                        area_dest.x + u16::try_from(edit_string.character_index).unwrap_or(0),
-                        area_dest.y,
-                    ));
-                }
+                let details_widget = Paragraph::new(content.as_str())
+                    .wrap(Wrap { trim: false })
+                    .scroll((*scroll, 0))
+                    .style(self.app_config.theme.popup_style())
+                    .block(Block::bordered().title(title.as_str()));
+                frame.render_widget(details_widget, area);
             }
-            TuiMode::Normal => {}
-        }
-    }
-
-    /// updates the application's state based on user input
-    fn handle_events(&mut self) -> io::Result<()> {
-        if poll(Self::REFRESH)? {
-            match event::read()? {
-                // it's important to check that the event is a key press event as
-                // crossterm also emits key release and repeat events on Windows.
-                Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
-                    self.handle_key_event(key_event);
-                }
-                _ => {}
+            TuiMode::Prompt(prompt) => {
+                let area = frame
+                    .area()
+                    .centered(Constraint::Percentage(40), Constraint::Length(3));
+                frame.render_widget(Clear, area); //this clears out the background
+                let block = Block::bordered().title(prompt.title.as_str());
+                let input_area = block.inner(area);
+                let value = if prompt.action.is_secret() {
+                    "*".repeat(prompt.input.value.chars().count())
+                } else {
+                    prompt.input.value.clone()
+                };
+                frame.render_widget(Paragraph::new(value).block(block), area);
+                frame.set_cursor_position(Position::new(
+                    input_area.x + u16::try_from(prompt.input.character_index).unwrap_or(0),
+                    input_area.y,
+                ));
             }
+            TuiMode::Union(builder) => Self::render_union_popup(builder, frame),
+            TuiMode::Wizard(wizard) => Self::render_wizard_popup(wizard, frame),
+            TuiMode::Filters(editor) => Self::render_filters_popup(editor, frame),
+            TuiMode::Bisync(conflicts) => Self::render_bisync_popup(conflicts, frame),
+            TuiMode::Stats(view) => Self::render_stats_popup(view, frame),
+            TuiMode::Normal | TuiMode::Browse => {}
         }
-        Ok(())
     }
 
-    /// Add a new error
-    fn new_error<S: Into<String>>(&mut self, msg: S) {
-        self.mode = TuiMode::Error(msg.into());
+    /// Render the statistics of the remotes
+    fn render_stats_popup(view: &StatsView, frame: &mut Frame<'_>) {
+        let area = frame
+            .area()
+            .centered(Constraint::Percentage(70), Constraint::Percentage(60));
+        frame.render_widget(Clear, area); //this clears out the background
+        let header = [
+            "remote",
+            "jobs",
+            "failures",
+            "transferred",
+            "files",
+            "avg duration",
+        ]
+        .into_iter()
+        .map(Cell::from)
+        .collect::<Row<'_>>()
+        .style(Style::default().add_modifier(Modifier::BOLD));
+        let rows = view.stats.iter().map(|remote| {
+            remote
+                .to_row()
+                .into_iter()
+                .map(Cell::from)
+                .collect::<Row<'_>>()
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Min(10),
+                Constraint::Length(6),
+                Constraint::Length(9),
+                Constraint::Length(12),
+                Constraint::Length(8),
+                Constraint::Length(13),
+            ],
+        )
+        .header(header)
+        .block(Block::bordered().title(tr_with(
+            "title.statistics",
+            &[("window", &view.window.to_string())],
+        )));
+        frame.render_widget(table, area);
     }
 
-    /// send a job
-    fn send_job(&mut self) {
-        let current_selected_job = if let Some(idx) = self.state.selected() {
-            if let Some(remote) = self.app_config.remotes().get(idx) {
-                remote
+    /// Render the conflicted paths and the resolution strategies of a bisync
+    fn render_bisync_popup(conflicts: &BisyncConflicts, frame: &mut Frame<'_>) {
+        let area = frame
+            .area()
+            .centered(Constraint::Percentage(60), Constraint::Percentage(60));
+        frame.render_widget(Clear, area); //this clears out the background
+        let title = if conflicts.needs_resync {
+            tr_with("title.bisync_resync", &[("remote", &conflicts.job.name)])
+        } else {
+            tr_with("title.bisync_conflicts", &[("remote", &conflicts.job.name)])
+        };
+        let block = Block::bordered().title(title);
+        let inner_area = block.inner(area);
+        frame.render_widget(block, area);
+        let strategies_len = u16::try_from(BisyncResolution::ALL.len()).unwrap_or(u16::MAX);
+        let [paths_area, strategies_area] = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(strategies_len + 2)])
+            .areas(inner_area);
+        let focused = |is_focused: bool| {
+            if is_focused {
+                Style::default().fg(Color::Yellow)
             } else {
-                self.new_error(format!("No remote configuration at index {idx} in remotes"));
-                return;
+                Style::default()
             }
+        };
+        if conflicts.needs_resync {
+            let message = ListItem::new("The listings of the previous runs are missing or broken");
+            frame.render_widget(List::new([message]), paths_area);
         } else {
-            self.new_error("No remote configuration selected");
-            return;
+            let files = conflicts
+                .files
+                .iter()
+                .map(|file| ListItem::new(file.describe()))
+                .collect::<Vec<ListItem<'_>>>();
+            let list = List::new(files)
+                .block(Block::bordered().title(tr("title.conflicts")))
+                .highlight_style(focused(conflicts.files_focused))
+                .highlight_symbol("> ");
+            let mut state = ListState::default().with_selected(Some(conflicts.selected_file));
+            frame.render_stateful_widget(list, paths_area, &mut state);
+        }
+        let strategies = BisyncResolution::ALL
+            .iter()
+            .map(|resolution| ListItem::new(resolution.to_string()))
+            .collect::<Vec<ListItem<'_>>>();
+        let list = List::new(strategies)
+            .block(Block::bordered().title(tr("title.resolution")))
+            .highlight_style(focused(!conflicts.files_focused))
+            .highlight_symbol("> ");
+        let mut state = ListState::default().with_selected(Some(conflicts.selected));
+        frame.render_stateful_widget(list, strategies_area, &mut state);
+    }
+
+    /// Render the union remote builder
+    fn render_union_popup(builder: &UnionBuilder, frame: &mut Frame<'_>) {
+        let focused = |is_focused: bool| {
+            if is_focused {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default()
+            }
         };
-        if current_selected_job.config_origin == ConfigOrigin::RcloneConfig {
-            self.new_error("Cannot sync a rclone config - press e for edit");
-            return;
+        let mut lines = vec![
+            Line::styled("Remote name", focused(builder.focus == UnionFocus::Name)),
+            Line::from(builder.name.value.as_str()),
+            Line::styled(
+                "Members (space to toggle)",
+                focused(builder.focus == UnionFocus::Members),
+            ),
+        ];
+        for (idx, (member, selected)) in builder.members.iter().enumerate() {
+            let check = if *selected { "[x]" } else { "[ ]" };
+            let style = if builder.focus == UnionFocus::Members && idx == builder.member_cursor {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default()
+            };
+            lines.push(Line::styled(format!("{check} {member}"), style));
         }
-        let Some(remote_src) = &current_selected_job.remote_src else {
-            self.new_error("Remote doesn't have a source - press e for edit");
-            return;
+        for (idx, policy_name) in UnionBuilder::POLICY_NAMES.iter().enumerate() {
+            lines.push(Line::styled(
+                format!("{policy_name}: < {} >", builder.policy(idx)),
+                focused(builder.focus == UnionFocus::Policy(idx)),
+            ));
+        }
+        let height = u16::try_from(lines.len() + 2).unwrap_or(u16::MAX);
+        let area = frame
+            .area()
+            .centered(Constraint::Percentage(40), Constraint::Length(height));
+        frame.render_widget(Clear, area); //this clears out the background
+        let block = Block::bordered().title(tr("title.new_union"));
+        if builder.focus == UnionFocus::Name {
+            let inner_area = block.inner(area);
+            frame.set_cursor_position(Position::new(
+                inner_area.x + u16::try_from(builder.name.character_index).unwrap_or(0),
+                inner_area.y + 1,
+            ));
+        }
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    /// Render the remote wizard: the backend types matching the search, then the fields of the remote
+    fn render_wizard_popup(wizard: &RemoteWizard, frame: &mut Frame<'_>) {
+        let area = frame
+            .area()
+            .centered(Constraint::Percentage(60), Constraint::Percentage(70));
+        frame.render_widget(Clear, area); //this clears out the background
+        let [input_area, list_area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(area);
+        let (label, input, items, selected) = match wizard.step {
+            WizardStep::Backend => {
+                let items = wizard
+                    .matching()
+                    .iter()
+                    .map(|provider| {
+                        ListItem::new(format!("{:<14} {}", provider.name, provider.description))
+                    })
+                    .collect::<Vec<ListItem<'_>>>();
+                (
+                    "Search a backend type".to_string(),
+                    wizard.search.value.clone(),
+                    items,
+                    wizard.cursor,
+                )
+            }
+            WizardStep::Options => {
+                let items = wizard
+                    .fields
+                    .iter()
+                    .map(|field| {
+                        ListItem::new(format!("{}\n  {}", field.label(), field.display_value()))
+                    })
+                    .collect::<Vec<ListItem<'_>>>();
+                let field = wizard.fields.get(wizard.field_idx);
+                (
+                    field.map(WizardField::label).unwrap_or_default(),
+                    field.map(WizardField::display_value).unwrap_or_default(),
+                    items,
+                    wizard.field_idx,
+                )
+            }
         };
-        let Some(remote_dest) = &current_selected_job.remote_dest else {
-            self.new_error("Remote doesn't have a destination - press e for edit");
-            return;
+        let title = match (wizard.step, wizard.backend()) {
+            (WizardStep::Options, Some(backend)) => {
+                format!("{} - {backend}", tr("title.new_remote"))
+            }
+            _ => tr("title.new_remote").to_string(),
         };
-        let sync_job = SyncJobData {
-            name: current_selected_job.remote_name.clone(),
-            src: remote_src.clone(),
-            dest: remote_dest.clone(),
-            job_id: 0, // fake job id
+        let input_block = Block::bordered().title(label);
+        let inner_area = input_block.inner(input_area);
+        frame.render_widget(Paragraph::new(input).block(input_block), input_area);
+        let character_index = match wizard.step {
+            WizardStep::Backend => wizard.search.character_index,
+            WizardStep::Options => wizard
+                .fields
+                .get(wizard.field_idx)
+                .map_or(0, |field| field.input.character_index),
         };
-        if let Err(_e) = self.tx_to_thread.send(SyncJob::Sync(sync_job)) {
-            // ignore
+        frame.set_cursor_position(Position::new(
+            inner_area.x + u16::try_from(character_index).unwrap_or(0),
+            inner_area.y,
+        ));
+        let list = List::new(items)
+            .block(Block::bordered().title(title))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        let mut state = ListState::default().with_selected(Some(selected));
+        frame.render_stateful_widget(list, list_area, &mut state);
+    }
+
+    /// Render the filter rules editor, the new rule is typed on the last line
+    fn render_filters_popup(editor: &FilterEditor, frame: &mut Frame<'_>) {
+        let mut lines = editor
+            .rules
+            .iter()
+            .enumerate()
+            .map(|(idx, rule)| {
+                let style = if idx == editor.cursor {
+                    Style::default().add_modifier(Modifier::REVERSED)
+                } else {
+                    Style::default()
+                };
+                Line::styled(rule.as_str(), style)
+            })
+            .collect::<Vec<Line<'_>>>();
+        if lines.is_empty() {
+            lines.push(Line::styled(
+                "No rule, every file is synced",
+                Style::default().fg(Color::DarkGray),
+            ));
         }
+        lines.push(Line::styled(
+            "New rule, like '- *.tmp' or '+ docs/**'",
+            Style::default().fg(Color::Yellow),
+        ));
+        lines.push(Line::from(editor.input.value.as_str()));
+        let height = u16::try_from(lines.len() + 2).unwrap_or(u16::MAX);
+        let area = frame
+            .area()
+            .centered(Constraint::Percentage(50), Constraint::Length(height));
+        frame.render_widget(Clear, area); //this clears out the background
+        let block = Block::bordered().title(format!("Filter rules of {}", editor.remote_name));
+        let inner_area = block.inner(area);
+        frame.set_cursor_position(Position::new(
+            inner_area.x + u16::try_from(editor.input.character_index).unwrap_or(0),
+            inner_area.y + inner_area.height.saturating_sub(1),
+        ));
+        frame.render_widget(Paragraph::new(lines).block(block), area);
     }
 
-    /// Ratatui handle key for normal mode
-    fn handle_key_event_normal_mode(&mut self, key_event: KeyEvent) {
-        match key_event.code {
+    /// Render the tree explorer
+    fn render_browser(browser: &mut Browser, frame: &mut Frame<'_>, area: Rect) {
+        frame.render_widget(Clear, area); //this clears out the background
+        let items = browser
+            .rows
+            .iter()
+            .map(|row| ListItem::new(row.label()))
+            .collect::<Vec<_>>();
+        let list = List::new(items)
+            .block(Block::bordered().title(browser.title.as_str()))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, area, &mut browser.state);
+    }
+
+    /// updates the application's state based on user input
+    fn handle_event(&mut self, event: &Event) {
+        match event {
+            // it's important to check that the event is a key press event as
+            // crossterm also emits key release and repeat events on Windows.
+            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                self.handle_key_event(*key_event);
+            }
+            Event::Paste(text) => self.handle_paste(text),
+            // recompute the layout dependent state, the frame is redrawn right after
+            Event::Resize(_, _) => self.refresh_table(),
+            _ => {}
+        }
+    }
+
+    /// Insert a pasted text in the focused input
+    fn handle_paste(&mut self, text: &str) {
+        match &mut self.mode {
+            TuiMode::EditString(edit_string) => edit_string.selected().insert_str(text),
+            TuiMode::Prompt(prompt) => prompt.input.insert_str(text),
+            TuiMode::Settings(form) => form.selected().insert_str(text),
+            TuiMode::Union(builder) if builder.focus == UnionFocus::Name => {
+                builder.name.insert_str(text);
+            }
+            TuiMode::Filters(editor) => editor.input.insert_str(text),
+            TuiMode::Wizard(wizard) => {
+                wizard.selected().insert_str(text);
+                if wizard.step == WizardStep::Backend {
+                    wizard.reset_cursor();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Add a new error
+    fn new_error<S: Into<String>>(&mut self, msg: S) {
+        self.mode = TuiMode::Error(msg.into());
+    }
+
+    /// Show the last rclone status of the latest job of the selected remote
+    fn show_job_details(&mut self) {
+        let Some(remote) = self
+            .state
+            .selected()
+            .and_then(|idx| self.app_config.remotes().get(idx))
+        else {
+            self.new_error("No remote configuration selected");
+            return;
+        };
+        let Some((job_data, job_state)) = self
+            .jobs
+            .iter()
+            .rev()
+            .find(|(job_data, _)| job_data.name == remote.remote_name)
+        else {
+            self.new_error(format!("No job for {}", remote.remote_name));
+            return;
+        };
+        let title = format!("Job {} ({})", job_data.name, job_data.job_id);
+        let content = match job_state {
+            JobState::Pending(job_status, stats) if !stats.transferring.is_empty() => format!(
+                "{}\n\n{}",
+                format_transfers(stats),
+                serde_json::to_string_pretty(&job_status.raw)
+                    .unwrap_or_else(|_| job_status.raw.to_string())
+            ),
+            JobState::Pending(job_status, _) | JobState::Done(job_status, _) => {
+                serde_json::to_string_pretty(&job_status.raw)
+                    .unwrap_or_else(|_| job_status.raw.to_string())
+            }
+            other => other.to_string(),
+        };
+        self.mode = TuiMode::Details(title, content, 0);
+    }
+
+    /// Cancel the latest waiting job of the selected remote
+    fn cancel_job(&mut self) {
+        let Some(remote) = self
+            .state
+            .selected()
+            .and_then(|idx| self.app_config.remotes().get(idx))
+        else {
+            self.new_error("No remote configuration selected");
+            return;
+        };
+        let Some(job_data) = self
+            .jobs
+            .iter()
+            .rev()
+            .find(|(job_data, job_state)| {
+                job_data.name == remote.remote_name && job_state.is_waiting()
+            })
+            .map(|(job_data, _)| job_data)
+        else {
+            self.new_error(format!("No running job for {}", remote.remote_name));
+            return;
+        };
+        let name = job_data.name.clone();
+        if self
+            .tx_to_thread
+            .send(SyncJob::Cancel(job_data.submission))
+            .is_err()
+        {
+            self.new_error("Cannot cancel the job: the background thread exited");
+        } else {
+            self.notify(format!("Cancelling the job of {name}"));
+        }
+    }
+
+    /// Show the metadata of the destination of the selected remote
+    fn show_dest_stat(&mut self) {
+        let Some(remote_dest) = self
+            .state
+            .selected()
+            .and_then(|idx| self.app_config.remotes().get(idx))
+            .and_then(|remote| remote.remote_dest.clone())
+        else {
+            self.new_error("Remote doesn't have a destination - press e for edit");
+            return;
+        };
+        self.show_stat(remote_dest);
+    }
+
+    /// Show the metadata of a rclone path
+    fn show_stat(&mut self, path: String) {
+        let (fs, remote_path) = RclonePath::split_fs(&path);
+        match self.rclone.stat(fs, remote_path, true) {
+            Ok(Some(item)) => self.mode = TuiMode::Details(path, format_stat(&item), 0),
+            Ok(None) => self.new_error(format!("'{path}' doesn't exist")),
+            Err(e) => self.new_error(format!("Failed to stat '{path}': {e}")),
+        }
+    }
+
+    /// Open the tree explorer on the destination of the selected remote
+    fn open_browser(&mut self) {
+        let Some(remote_dest) = self
+            .state
+            .selected()
+            .and_then(|idx| self.app_config.remotes().get(idx))
+            .and_then(|remote| remote.remote_dest.clone())
+        else {
+            self.new_error("Remote doesn't have a destination - press e for edit");
+            return;
+        };
+        match Browser::open(self.rclone, remote_dest) {
+            Ok(browser) => {
+                self.browser = Some(browser);
+                self.mode = TuiMode::Browse;
+            }
+            Err(e) => self.new_error(format!("Failed to list the destination: {e}")),
+        }
+    }
+
+    /// Open the search prompt on the destination of the selected remote
+    fn open_search(&mut self) {
+        let Some(remote_dest) = self
+            .state
+            .selected()
+            .and_then(|idx| self.app_config.remotes().get(idx))
+            .and_then(|remote| remote.remote_dest.clone())
+        else {
+            self.new_error("Remote doesn't have a destination - press e for edit");
+            return;
+        };
+        self.mode = TuiMode::Prompt(Prompt {
+            title: format!("Search in {remote_dest} (include glob)"),
+            input: TextInput::default(),
+            action: PromptAction::Search(remote_dest),
+        });
+    }
+
+    /// Run the action of a validated prompt
+    fn run_prompt(&mut self, action: PromptAction, input: &str) {
+        match action {
+            PromptAction::Search(fs) => match Browser::search(self.rclone, fs, input) {
+                Ok(browser) => {
+                    self.browser = Some(browser);
+                    self.mode = TuiMode::Browse;
+                }
+                Err(e) => self.new_error(format!("Failed to search: {e}")),
+            },
+            PromptAction::Download(path) => self.download(path, input),
+            PromptAction::Upload(remote_dir) => self.upload(input, &remote_dir),
+            PromptAction::Alias(target) => self.create_alias(input.trim(), &target),
+            PromptAction::Obscure => match self.rclone.obscure(input) {
+                Ok(obscured) => {
+                    self.mode = TuiMode::Details("Obscured password".to_string(), obscured, 0);
+                }
+                Err(e) => self.new_error(format!("Failed to obscure the password: {e}")),
+            },
+            PromptAction::Compare(src, dest) => {
+                let path = Path::new(input.trim());
+                match CompareReport::new(self.rclone, &src, &dest)
+                    .and_then(|report| report.write(path).map(|()| report.entries.len()))
+                {
+                    Ok(count) => self.notify(format!("{count} differences in {}", path.display())),
+                    Err(e) => self.new_error(format!("Failed to write the report: {e}")),
+                }
+            }
+            PromptAction::Template => self.launch_template(input),
+            PromptAction::RunBatch(group) => self.run_batch(group.as_deref(), input),
+            PromptAction::Rename => {
+                let Some(browser) = &mut self.browser else {
+                    return;
+                };
+                match browser.rename_selected(self.rclone, input) {
+                    Ok(()) => self.notify(format!("Renamed to {input}")),
+                    Err(e) => self.new_error(format!("Failed to rename: {e}")),
+                }
+            }
+        }
+    }
+
+    /// Ratatui handle key for a prompt
+    fn handle_key_event_prompt_mode(&mut self, key_event: KeyEvent) {
+        let TuiMode::Prompt(prompt) = &mut self.mode else {
+            return;
+        };
+        match key_event.code {
+            KeyCode::Esc => self.close_popup(),
+            KeyCode::Enter => {
+                let mode = std::mem::replace(&mut self.mode, TuiMode::Normal);
+                self.close_popup();
+                if let TuiMode::Prompt(prompt) = mode {
+                    self.run_prompt(prompt.action, &prompt.input.value);
+                }
+            }
+            KeyCode::Left => prompt.input.move_cursor_left(),
+            KeyCode::Right => prompt.input.move_cursor_right(),
+            KeyCode::Char(to_insert) => prompt.input.enter_char(to_insert),
+            KeyCode::Backspace => prompt.input.delete_char(),
+            _ => {}
+        }
+    }
+
+    /// Open the prompt of the comparison report of the selected remote
+    fn open_compare(&mut self) {
+        let Some(remote) = self
+            .state
+            .selected()
+            .and_then(|idx| self.app_config.remotes().get(idx))
+        else {
+            self.new_error("No remote configuration selected");
+            return;
+        };
+        let (Some(src), Some(dest)) = (remote.remote_src.clone(), remote.remote_dest.clone())
+        else {
+            self.new_error("Remote needs a source and a destination - press e for edit");
+            return;
+        };
+        self.mode = TuiMode::Prompt(Prompt {
+            title: "Comparison report path (.csv or .json)".to_string(),
+            input: TextInput::new(format!("{}-compare.csv", remote.remote_name)),
+            action: PromptAction::Compare(src, dest),
+        });
+    }
+
+    /// Open the versions of the backup dir of the selected remote
+    fn open_versions(&mut self) {
+        let Some(remote) = self
+            .state
+            .selected()
+            .and_then(|idx| self.app_config.remotes().get(idx))
+        else {
+            self.new_error("No remote configuration selected");
+            return;
+        };
+        let (Some(backup_dir), Some(remote_src)) = (&remote.backup_dir, &remote.remote_src) else {
+            self.new_error("Remote doesn't have a backup dir - press e for edit");
+            return;
+        };
+        match Browser::versions(self.rclone, backup_dir.clone(), remote_src.clone()) {
+            Ok(browser) => {
+                self.browser = Some(browser);
+                self.mode = TuiMode::Browse;
+            }
+            Err(e) => self.new_error(format!("Failed to list the backup dir: {e}")),
+        }
+    }
+
+    /// Open the union remote builder
+    fn open_union_builder(&mut self) {
+        match self.rclone.list_remotes() {
+            Ok(remotes) => self.mode = TuiMode::Union(UnionBuilder::new(remotes)),
+            Err(e) => self.new_error(format!("Failed to list the rclone remotes: {e}")),
+        }
+    }
+
+    /// Open the wizard of a new rclone remote
+    fn open_wizard(&mut self) {
+        let remotes = match self.rclone.list_remotes() {
+            Ok(remotes) => remotes,
+            Err(e) => {
+                self.new_error(format!("Failed to list the rclone remotes: {e}"));
+                return;
+            }
+        };
+        match self.rclone.providers() {
+            Ok(providers) => self.mode = TuiMode::Wizard(RemoteWizard::new(providers, remotes)),
+            Err(e) => self.new_error(format!("Failed to list the backend types: {e}")),
+        }
+    }
+
+    /// Ratatui handle key for the remote wizard
+    fn handle_key_event_wizard_mode(&mut self, key_event: KeyEvent) {
+        let TuiMode::Wizard(wizard) = &mut self.mode else {
+            return;
+        };
+        match (key_event.code, wizard.step) {
+            (KeyCode::Esc, WizardStep::Backend) => self.mode = TuiMode::Normal,
+            (KeyCode::Esc, WizardStep::Options) => wizard.back(),
+            (KeyCode::Enter, WizardStep::Backend) => wizard.choose_backend(),
+            (KeyCode::Enter, WizardStep::Options) => match wizard.finish() {
+                Ok((name, backend, parameters)) => self.create_remote(&name, &backend, &parameters),
+                Err(e) => self.new_error(format!("Cannot create the remote: {e}")),
+            },
+            (KeyCode::Down, WizardStep::Backend) => wizard.move_cursor(true),
+            (KeyCode::Up, WizardStep::Backend) => wizard.move_cursor(false),
+            (KeyCode::Down | KeyCode::Tab, WizardStep::Options) => wizard.move_field(true),
+            (KeyCode::Up | KeyCode::BackTab, WizardStep::Options) => wizard.move_field(false),
+            (KeyCode::Left, _) => wizard.selected().move_cursor_left(),
+            (KeyCode::Right, _) => wizard.selected().move_cursor_right(),
+            (KeyCode::Char(to_insert), step) => {
+                wizard.selected().enter_char(to_insert);
+                if step == WizardStep::Backend {
+                    wizard.reset_cursor();
+                }
+            }
+            (KeyCode::Backspace, step) => {
+                wizard.selected().delete_char();
+                if step == WizardStep::Backend {
+                    wizard.reset_cursor();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Create a rclone remote, and reload the remotes
+    fn create_remote(&mut self, name: &str, backend_type: &str, parameters: &Value) {
+        let config_file = self.rclone.remote_config_file(None);
+        if let Err(e) = self.rclone.create_remote(name, backend_type, parameters) {
+            self.new_error(format!("Failed to create the remote: {e}"));
+            return;
+        }
+        if let Ok(config_file) = config_file {
+            self.config_watcher.written(Path::new(&config_file));
+        }
+        self.mode = TuiMode::Normal;
+        if let Err(e) = self
+            .app_config
+            .reload(self.rclone, self.galion_args.ignore_duplicate_remote)
+        {
+            self.new_error(format!("Failed to reload the configuration: {e}"));
+            return;
+        }
+        self.refresh_table();
+        self.notify(format!("Remote {name} created"));
+    }
+
+    /// Create an alias remote of a remote directory
+    fn create_alias(&mut self, name: &str, target: &str) {
+        if name.is_empty() {
+            self.new_error("The remote name is empty");
+            return;
+        }
+        match self.rclone.list_remotes() {
+            Ok(remotes) if remotes.iter().any(|remote| remote == name) => {
+                self.new_error(format!("A rclone remote named '{name}' already exists"));
+            }
+            Ok(_) => {
+                self.browser = None;
+                self.create_remote(name, "alias", &json!({ "remote": target }));
+            }
+            Err(e) => self.new_error(format!("Failed to list the rclone remotes: {e}")),
+        }
+    }
+
+    /// Ratatui handle key for the union remote builder
+    fn handle_key_event_union_mode(&mut self, key_event: KeyEvent) {
+        let TuiMode::Union(builder) = &mut self.mode else {
+            return;
+        };
+        match (key_event.code, builder.focus) {
+            (KeyCode::Esc, _) => self.mode = TuiMode::Normal,
+            (KeyCode::Enter, _) => match builder.finish() {
+                Ok((name, parameters)) => self.create_remote(&name, "union", &parameters),
+                Err(e) => self.new_error(format!("Cannot create the union: {e}")),
+            },
+            (KeyCode::Tab, _) => builder.next_focus(),
+            (KeyCode::BackTab, _) => builder.previous_focus(),
+            (KeyCode::Down | KeyCode::Right, UnionFocus::Members | UnionFocus::Policy(_)) => {
+                builder.move_cursor(true);
+            }
+            (KeyCode::Up | KeyCode::Left, UnionFocus::Members | UnionFocus::Policy(_)) => {
+                builder.move_cursor(false);
+            }
+            (KeyCode::Char(' '), UnionFocus::Members) => builder.toggle_member(),
+            (KeyCode::Left, UnionFocus::Name) => builder.name.move_cursor_left(),
+            (KeyCode::Right, UnionFocus::Name) => builder.name.move_cursor_right(),
+            (KeyCode::Char(to_insert), UnionFocus::Name) => builder.name.enter_char(to_insert),
+            (KeyCode::Backspace, UnionFocus::Name) => builder.name.delete_char(),
+            _ => {}
+        }
+    }
+
+    /// Handle the keys of the filter rules editor, the rules are saved when it is closed
+    fn handle_key_event_filters_mode(&mut self, key_event: KeyEvent) {
+        let TuiMode::Filters(editor) = &mut self.mode else {
+            return;
+        };
+        match key_event.code {
+            KeyCode::Esc => {
+                let name = editor.remote_name.clone();
+                let rules = std::mem::take(&mut editor.rules);
+                self.mode = TuiMode::Normal;
+                self.save_filters(&name, rules);
+            }
+            KeyCode::Enter => {
+                if let Err(e) = editor.add_rule() {
+                    self.new_error(e.to_string());
+                }
+            }
+            KeyCode::Down => editor.move_cursor(true),
+            KeyCode::Up => editor.move_cursor(false),
+            KeyCode::Delete => editor.remove_rule(),
+            KeyCode::Left => editor.input.move_cursor_left(),
+            KeyCode::Right => editor.input.move_cursor_right(),
+            KeyCode::Char(to_insert) => editor.input.enter_char(to_insert),
+            KeyCode::Backspace => editor.input.delete_char(),
+            _ => {}
+        }
+    }
+
+    /// Save the filter rules of a remote
+    fn save_filters(&mut self, name: &str, rules: Vec<String>) {
+        let Some(remote) = self
+            .app_config
+            .remote_configurations
+            .iter_mut()
+            .find(|remote| remote.remote_name == name)
+        else {
+            self.new_error(format!("No remote {name}"));
+            return;
+        };
+        if remote.filters == rules {
+            return;
+        }
+        remote.filters = rules;
+        if let Err(e) = self.save_config() {
+            self.new_error(format!("{} {e}", tr("error.save_config")));
+        } else {
+            self.notify(format!("Filter rules of {name} saved"));
+        }
+    }
+
+    /// Open the filter rules editor of the selected remote
+    fn open_filters(&mut self) {
+        match self
+            .state
+            .selected()
+            .and_then(|idx| self.app_config.remotes().get(idx))
+        {
+            Some(remote) if remote.config_origin == ConfigOrigin::RcloneConfig => {
+                self.new_error("Cannot filter a rclone config - press e for edit");
+            }
+            Some(remote) => self.mode = TuiMode::Filters(FilterEditor::new(remote)),
+            None => self.new_error("No remote configuration selected"),
+        }
+    }
+
+    /// Mode to go back to when a popup is closed
+    fn close_popup(&mut self) {
+        self.mode = if self.browser.is_some() {
+            TuiMode::Browse
+        } else {
+            TuiMode::Normal
+        };
+    }
+
+    /// Ask to confirm the restore of a backup
+    fn open_restore(&mut self, src: String, dest: String, is_dir: bool) {
+        self.mode = TuiMode::Restore(SyncJobData {
+            job_id: 0, // fake job id
+            name: self.selected_remote_name(),
+            src,
+            dest,
+            kind: if is_dir {
+                JobKind::Copy
+            } else {
+                JobKind::CopyFile
+            },
+            backup_dir: None,
+            options: SyncOptions::default(),
+            bisync: BisyncRun::default(),
+            // a restore jumps ahead of the syncs
+            priority: Priority::High,
+            submission: 0,
+            max_duration: None,
+            space_check: SpaceCheck::default(),
+            filter_from: None,
+            filters: Vec::new(),
+            overlap: OverlapPolicy::default(),
+            verify: false,
+        });
+    }
+
+    /// Ratatui handle key for the tree explorer
+    fn handle_key_event_browse_mode(&mut self, key_event: KeyEvent) {
+        let Some(browser) = &mut self.browser else {
+            self.mode = TuiMode::Normal;
+            return;
+        };
+        match key_event.code {
+            KeyCode::Char('q') | KeyCode::Esc => {
+                self.browser = None;
+                self.mode = TuiMode::Normal;
+            }
+            KeyCode::Char('j') | KeyCode::Down => browser.next(),
+            KeyCode::Char('k') | KeyCode::Up => browser.previous(),
+            KeyCode::Char('l') | KeyCode::Right | KeyCode::Enter => {
+                if let Err(e) = browser.expand(self.rclone) {
+                    self.new_error(format!("Failed to list the directory: {e}"));
+                }
+            }
+            KeyCode::Char('h') | KeyCode::Left => browser.collapse(),
+            KeyCode::Backspace => match browser.parent(self.rclone) {
+                Ok(Some(parent)) => *browser = parent,
+                Ok(None) => {}
+                Err(e) => self.new_error(format!("Failed to list the parent directory: {e}")),
+            },
+            KeyCode::Char('s') => {
+                if let Some(path) = browser.selected_path() {
+                    self.show_stat(path);
+                }
+            }
+            KeyCode::Char('d') => match browser.selected_item() {
+                Some(item) if item.is_dir => self.new_error("Only files can be deleted"),
+                Some(_) => {
+                    if let Some(path) = browser.selected_path() {
+                        self.mode = TuiMode::DeleteFile(path);
+                        if !self.app_config.confirm_deletions {
+                            self.delete_selected_file();
+                        }
+                    }
+                }
+                None => {}
+            },
+            KeyCode::Char('g') => match browser.selected_item() {
+                Some(item) if item.is_dir => self.new_error("Only files can be downloaded"),
+                Some(_) => {
+                    if let Some(path) = browser.selected_path() {
+                        let local_dir = std::env::current_dir()
+                            .map(|dir| dir.to_string_lossy().into_owned())
+                            .unwrap_or_default();
+                        self.mode = TuiMode::Prompt(Prompt {
+                            title: format!("Download {path} to the local directory"),
+                            input: TextInput::new(local_dir),
+                            action: PromptAction::Download(path),
+                        });
+                    }
+                }
+                None => {}
+            },
+            KeyCode::Char('R') => {
+                if let Some((src, dest, is_dir)) = browser.restore_target() {
+                    self.open_restore(src, dest, is_dir);
+                }
+            }
+            KeyCode::Char('p') => match browser.read_selected(self.rclone, PREVIEW_MAX_SIZE) {
+                Ok((path, content)) => self.mode = TuiMode::Details(path, content, 0),
+                Err(e) => self.new_error(format!("Cannot preview the file: {e}")),
+            },
+            KeyCode::Char('a') => {
+                let target = browser.selected_dir();
+                self.mode = TuiMode::Prompt(Prompt {
+                    title: format!("Name of the alias of {target}"),
+                    input: TextInput::default(),
+                    action: PromptAction::Alias(target),
+                });
+            }
+            KeyCode::Char('u') => {
+                let remote_dir = browser.selected_dir();
+                let local_dir = std::env::current_dir()
+                    .map(|dir| format!("{}{}", dir.display(), std::path::MAIN_SEPARATOR))
+                    .unwrap_or_default();
+                self.mode = TuiMode::Prompt(Prompt {
+                    title: format!("Upload a local file to {remote_dir}"),
+                    input: TextInput::new(local_dir),
+                    action: PromptAction::Upload(remote_dir),
+                });
+            }
+            KeyCode::Char('r') => {
+                if let Some(item) = browser.selected_item() {
+                    let name = item.path.rsplit('/').next().unwrap_or_default();
+                    self.mode = TuiMode::Prompt(Prompt {
+                        title: format!("Rename {}", item.path),
+                        input: TextInput::new(name),
+                        action: PromptAction::Rename,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// send a job
+    fn send_job(&mut self) {
+        if self.draining {
+            self.new_error("Cannot launch a job while exiting");
+            return;
+        }
+        let current_selected_job = if let Some(idx) = self.state.selected() {
+            if let Some(remote) = self.app_config.remotes().get(idx) {
+                remote
+            } else {
+                self.new_error(format!("No remote configuration at index {idx} in remotes"));
+                return;
+            }
+        } else {
+            self.new_error("No remote configuration selected");
+            return;
+        };
+        if current_selected_job.config_origin == ConfigOrigin::RcloneConfig {
+            self.new_error("Cannot sync a rclone config - press e for edit");
+            return;
+        }
+        let Some(remote_src) = &current_selected_job.remote_src else {
+            self.new_error("Remote doesn't have a source - press e for edit");
+            return;
+        };
+        let Some(remote_dest) = &current_selected_job.remote_dest else {
+            self.new_error("Remote doesn't have a destination - press e for edit");
+            return;
+        };
+        let sync_job = SyncJobData {
+            name: current_selected_job.remote_name.clone(),
+            src: remote_src.clone(),
+            dest: remote_dest.clone(),
+            job_id: 0, // fake job id
+            kind: JobKind::from(&current_selected_job.operation),
+            backup_dir: current_selected_job.backup_dir.clone(),
+            options: current_selected_job.options.clone(),
+            bisync: BisyncRun::default(),
+            priority: current_selected_job.priority,
+            submission: 0,
+            max_duration: None,
+            space_check: SpaceCheck::default(),
+            filter_from: current_selected_job.filter_from.clone(),
+            filters: current_selected_job.filters.clone(),
+            overlap: current_selected_job.overlap,
+            verify: current_selected_job.verify,
+        };
+        if let Err(_e) = self.tx_to_thread.send(SyncJob::Sync(Box::new(sync_job))) {
+            // ignore
+        }
+    }
+
+    /// Launch the job of a template instantiated with the prompt values: `template key=value...`
+    fn launch_template(&mut self, input: &str) {
+        let mut words = input.split_whitespace();
+        let Some(template_name) = words.next() else {
+            self.new_error("No template name");
+            return;
+        };
+        let job = match self.app_config.instantiate_template(template_name, words) {
+            Ok(remote) => SyncJobData::from_remote(&remote),
+            Err(e) => {
+                self.new_error(e.to_string());
+                return;
+            }
+        };
+        let Some(job) = job else {
+            self.new_error("The template needs a source and a destination");
+            return;
+        };
+        let name = job.name.clone();
+        if self
+            .tx_to_thread
+            .send(SyncJob::Sync(Box::new(job)))
+            .is_err()
+        {
+            self.new_error("Cannot launch the job: the background thread exited");
+        } else {
+            self.notify(format!("Launched {name}"));
+        }
+    }
+
+    /// Open the past runs of the selected remote, from the history and the finished jobs in memory
+    fn open_history(&mut self) {
+        let Some(name) = self
+            .state
+            .selected()
+            .and_then(|idx| self.app_config.remotes().get(idx))
+            .map(|remote| remote.remote_name.clone())
+        else {
+            self.new_error("No remote configuration selected");
+            return;
+        };
+        let mut entries = match self.app_config.history().load() {
+            Ok(entries) => entries,
+            Err(e) => {
+                self.new_error(format!("Cannot read the history: {e}"));
+                return;
+            }
+        };
+        entries.extend(
+            self.jobs
+                .iter()
+                .filter_map(|(job_data, job_state)| job_state.history_entry(job_data)),
+        );
+        self.mode = TuiMode::Details(
+            format!("History of {name}"),
+            runs_report(&entries, &name),
+            0,
+        );
+    }
+
+    /// Open the statistics of the remotes, from the history and the finished jobs in memory
+    fn open_stats(&mut self) {
+        let mut entries = match self.app_config.history().load() {
+            Ok(entries) => entries,
+            Err(e) => {
+                self.new_error(format!("Cannot read the history: {e}"));
+                return;
+            }
+        };
+        entries.extend(
+            self.jobs
+                .iter()
+                .filter_map(|(job_data, job_state)| job_state.history_entry(job_data)),
+        );
+        self.mode = TuiMode::Stats(StatsView::new(entries, self.session.stats_window));
+    }
+
+    /// Open the prompt of the remote templates
+    fn open_template(&mut self) {
+        let Some(template) = self.app_config.templates.first() else {
+            self.new_error("No template in the config");
+            return;
+        };
+        let names = self
+            .app_config
+            .templates
+            .iter()
+            .map(|template| template.name.as_str())
+            .collect::<Vec<&str>>()
+            .join(", ");
+        let example = std::iter::once(template.name.clone())
+            .chain(
+                template
+                    .variables
+                    .iter()
+                    .map(|variable| format!("{variable}=")),
+            )
+            .collect::<Vec<String>>()
+            .join(" ");
+        self.mode = TuiMode::Prompt(Prompt {
+            title: format!("Launch a template ({names}): name key=value..."),
+            input: TextInput::new(example),
+            action: PromptAction::Template,
+        });
+    }
+
+    /// Open the prompt choosing how to run all the remotes, or the group of the selected remote
+    fn open_run_batch(&mut self, of_group: bool) {
+        let group = if of_group {
+            let Some(group) = self
+                .state
+                .selected()
+                .and_then(|idx| self.app_config.remotes().get(idx))
+                .and_then(|remote| remote.group.clone())
+            else {
+                self.new_error("Remote doesn't have a group - press e for edit");
+                return;
+            };
+            Some(group)
+        } else {
+            None
+        };
+        let policy = self.app_config.execution_policy(group.as_deref());
+        let title = match &group {
+            Some(group) => tr_with("title.run_group", &[("group", group)]),
+            None => tr("title.run_all").to_string(),
+        };
+        self.mode = TuiMode::Prompt(Prompt {
+            title,
+            input: TextInput::new(policy.to_string()),
+            action: PromptAction::RunBatch(group),
+        });
+    }
+
+    /// Launch the jobs of all the remotes with a source and a destination, or of a group, as a batch
+    fn run_batch(&mut self, group: Option<&str>, input: &str) {
+        if self.draining {
+            self.new_error("Cannot launch a job while exiting");
+            return;
+        }
+        let policy = match input.parse::<ExecutionPolicy>() {
+            Ok(policy) => policy,
+            Err(e) => {
+                self.new_error(e.to_string());
+                return;
+            }
+        };
+        let jobs = self
+            .app_config
+            .remotes()
+            .iter()
+            .filter(|remote| remote.config_origin != ConfigOrigin::RcloneConfig)
+            .filter(|remote| !remote.quarantined)
+            .filter(|remote| group.is_none() || remote.group.as_deref() == group)
+            .filter_map(SyncJobData::from_remote)
+            .collect::<Vec<SyncJobData>>();
+        if jobs.is_empty() {
+            self.new_error("No remote with a source and a destination");
+            return;
+        }
+        let count = jobs.len();
+        if self
+            .tx_to_thread
+            .send(SyncJob::Batch(jobs, policy))
+            .is_err()
+        {
+            self.new_error("Cannot run the batch: the background thread exited");
+        } else {
+            self.notify(format!("Running {count} jobs ({policy})"));
+        }
+    }
+
+    /// Ratatui handle key for normal mode
+    fn handle_key_event_normal_mode(&mut self, key_event: KeyEvent) {
+        match key_event.code {
             KeyCode::Char('q') | KeyCode::Esc => {
-                self.exit();
+                self.quit();
             }
+            KeyCode::Right if key_event.modifiers.contains(KeyModifiers::SHIFT) => self.dry_run(),
             KeyCode::Right => self.send_job(),
+            KeyCode::Char('A') => self.open_run_batch(false),
+            KeyCode::Char('G') => self.open_run_batch(true),
+            KeyCode::Char('i') => self.show_job_details(),
+            KeyCode::Char('x') => self.cancel_job(),
+            KeyCode::Char('s') => self.show_dest_stat(),
+            KeyCode::Char('b') => self.open_browser(),
+            KeyCode::Char('/') => self.open_search(),
+            KeyCode::Char('v') => self.open_versions(),
+            KeyCode::Char('U') => self.open_union_builder(),
+            KeyCode::Char('W') => self.open_wizard(),
+            KeyCode::Char('S') => self.toggle_server(ServeProtocol::Sftp),
+            KeyCode::Char('N') => self.toggle_server(ServeProtocol::Nfs),
+            KeyCode::Char('C') => self.open_compare(),
+            KeyCode::Char('P') => self.toggle_schedule(),
+            KeyCode::Char('T') => self.open_template(),
+            KeyCode::Char('M') => self.open_stats(),
+            KeyCode::Char('h') => self.open_history(),
+            KeyCode::Char('H') => self.check_health(),
+            KeyCode::Char('B') => self.run_benchmark(),
+            KeyCode::Char('R') => self.refresh_rclone_remotes(true),
+            KeyCode::Char('?') => self.show_cheat_sheet(),
+            KeyCode::Char(',') => self.mode = TuiMode::Settings(SettingsForm::new(self.app_config)),
+            KeyCode::Char('O') => {
+                self.mode = TuiMode::Prompt(Prompt {
+                    title: "Password to obscure".to_string(),
+                    input: TextInput::default(),
+                    action: PromptAction::Obscure,
+                });
+            }
             KeyCode::Char('r') | KeyCode::Delete | KeyCode::Backspace => {
                 if let Some(idx) = self.state.selected()
                     && let Some(config) = self.app_config.remotes().get(idx)
                 {
                     if config.config_origin == ConfigOrigin::RcloneConfig {
-                        self.new_error("Cannot delete a remote from the rclone config");
-                    } else {
+                        // always confirmed twice, the credentials are lost
+                        self.mode = TuiMode::DeleteRclone(config.remote_name.clone(), false);
+                    } else if self.app_config.confirm_deletions {
                         self.mode = TuiMode::Delete;
-                    }
-                } else {
-                    self.new_error("Cannot delete the config");
-                }
-            }
-            KeyCode::Char('d') => {
-                if let Some(idx) = self.state.selected()
-                    && let Some(config) = self.app_config.remotes().get(idx)
-                {
-                    if config.config_origin == ConfigOrigin::RcloneConfig {
-                        self.new_error("Cannot duplicate a rclone config - try to edit it");
                     } else {
-                        self.app_config
-                            .remote_configurations
-                            .insert(0, config.clone());
+                        self.delete_selected_remote();
                     }
                 } else {
-                    self.new_error("Cannot duplicate the config");
+                    self.new_error("Cannot delete the config");
                 }
             }
+            KeyCode::Char('d') => self.duplicate_selected_remote(),
+            KeyCode::Char('F') => self.open_filters(),
             KeyCode::Char('j') | KeyCode::Down => {
                 // Select new row
                 let i = match self.state.selected() {
@@ -600,8 +2033,7 @@ impl<'a> TuiApp<'a> {
                     }
                     None => 0,
                 };
-                self.state.select(Some(i));
-                self.scroll_state = self.scroll_state.position(i * ITEM_HEIGHT);
+                self.select_remote(i);
             }
             KeyCode::Char('k') | KeyCode::Up => {
                 // Select previous row
@@ -615,20 +2047,13 @@ impl<'a> TuiApp<'a> {
                     }
                     None => 0,
                 };
-                self.state.select(Some(i));
-                self.scroll_state = self.scroll_state.position(i * ITEM_HEIGHT);
+                self.select_remote(i);
             }
             KeyCode::Char('e') => {
                 if let Some(idx) = self.state.selected()
                     && let Some(config) = self.app_config.remotes().get(idx)
                 {
-                    self.mode = TuiMode::EditString(EditRemote {
-                        idx_string: 0,
-                        character_index: 0,
-                        remote_name: config.remote_name.clone(),
-                        remote_src: config.remote_src.clone().unwrap_or_default(),
-                        remote_dest: config.remote_dest.clone().unwrap_or_default(),
-                    });
+                    self.mode = TuiMode::EditString(Box::new(EditRemote::new(config)));
                 } else {
                     self.new_error("Cannot edit");
                 }
@@ -637,6 +2062,466 @@ impl<'a> TuiApp<'a> {
         }
     }
 
+    /// Add a copy of the selected remote at the top of the list
+    fn duplicate_selected_remote(&mut self) {
+        if let Some(idx) = self.state.selected()
+            && let Some(config) = self.app_config.remotes().get(idx)
+        {
+            if config.config_origin == ConfigOrigin::RcloneConfig {
+                self.new_error("Cannot duplicate a rclone config - try to edit it");
+            } else {
+                let mut copy = config.clone();
+                copy.remote_name = self.app_config.copy_name(&config.remote_name);
+                copy.config_origin = ConfigOrigin::GalionConfig;
+                self.app_config.remote_configurations.insert(0, copy);
+                self.refresh_table();
+            }
+        } else {
+            self.new_error("Cannot duplicate the config");
+        }
+    }
+
+    /// Show the keys of the ui
+    fn show_cheat_sheet(&mut self) {
+        self.mode = TuiMode::Details(tr("title.keys").to_string(), cheat_sheet(false), 0);
+    }
+
+    /// Probe the destinations of all the remotes in the background thread
+    fn check_health(&mut self) {
+        if self.tx_to_thread.send(SyncJob::Health(None)).is_ok() {
+            self.notify("Checking the health of the destinations");
+        }
+    }
+
+    /// Benchmark the destination of the selected remote in the background thread
+    fn run_benchmark(&mut self) {
+        let Some(remote) = self
+            .state
+            .selected()
+            .and_then(|idx| self.app_config.remotes().get(idx))
+        else {
+            self.new_error("No remote configuration selected");
+            return;
+        };
+        let Some(dest) = remote.remote_dest.clone() else {
+            self.new_error("Remote doesn't have a destination - press e for edit");
+            return;
+        };
+        let name = remote.remote_name.clone();
+        if self
+            .tx_to_thread
+            .send(SyncJob::Benchmark(name.clone(), dest))
+            .is_ok()
+        {
+            self.notify(format!("Benchmarking the destination of {name}"));
+        }
+    }
+
+    /// Preview the files the sync of the selected remote would copy and delete
+    fn dry_run(&mut self) {
+        let Some(remote) = self
+            .state
+            .selected()
+            .and_then(|idx| self.app_config.remotes().get(idx))
+        else {
+            self.new_error("No remote configuration selected");
+            return;
+        };
+        let Some(sync_data) = SyncJobData::from_remote(remote) else {
+            self.new_error("Remote needs a source and a destination - press e for edit");
+            return;
+        };
+        if sync_data.kind != JobKind::Sync {
+            self.new_error("Only the syncs have a dry run");
+            return;
+        }
+        let name = sync_data.name.clone();
+        if self
+            .tx_to_thread
+            .send(SyncJob::DryRun(Box::new(sync_data)))
+            .is_ok()
+        {
+            self.notify(format!("Dry run of {name}"));
+        }
+    }
+
+    /// Enable or disable the schedule of the selected remote
+    fn toggle_schedule(&mut self) {
+        let Some(schedule) = self
+            .state
+            .selected()
+            .and_then(|idx| self.app_config.remote_configurations.get_mut(idx))
+            .and_then(|remote| remote.schedule.as_mut())
+        else {
+            self.new_error("Remote doesn't have a schedule - press e for edit");
+            return;
+        };
+        schedule.enabled = !schedule.enabled;
+        let msg = if schedule.enabled {
+            "Schedule enabled"
+        } else {
+            "Schedule disabled"
+        };
+        self.refresh_table();
+        if let Err(e) = self.save_config() {
+            self.new_error(format!("{} {e}", tr("error.save_config")));
+        } else {
+            self.notify(msg);
+        }
+    }
+
+    /// Start a server of the destination of the selected remote, or stop it if it runs
+    fn toggle_server(&mut self, protocol: ServeProtocol) {
+        let Some(remote_dest) = self
+            .state
+            .selected()
+            .and_then(|idx| self.app_config.remotes().get(idx))
+            .and_then(|remote| remote.remote_dest.clone())
+        else {
+            self.new_error("Remote doesn't have a destination - press e for edit");
+            return;
+        };
+        if let Some(idx) = self
+            .servers
+            .iter()
+            .position(|server| server.protocol == protocol && server.fs == remote_dest)
+        {
+            let server = self.servers.remove(idx);
+            match server.stop(self.rclone) {
+                Ok(()) => self.notify(format!("Stopped the {protocol} server of {remote_dest}")),
+                Err(e) => self.new_error(format!("Failed to stop the {protocol} server: {e}")),
+            }
+            return;
+        }
+        match Server::start(self.rclone, protocol, remote_dest) {
+            Ok(server) => self.servers.push(server),
+            Err(e) => self.new_error(format!("Failed to start the {protocol} server: {e}")),
+        }
+    }
+
+    /// Delete the selected remote, after confirmation
+    fn delete_selected_remote(&mut self) {
+        if let Some(idx) = self.state.selected()
+            && let Some(config) = self.app_config.remotes().get(idx)
+        {
+            if config.config_origin == ConfigOrigin::RcloneConfig {
+                self.new_error("Cannot delete a remote from the rclone config");
+                return;
+            }
+            self.app_config.remove_remote(idx);
+            self.refresh_table();
+            if let Err(e) = self.save_config() {
+                self.new_error(format!(
+                    "Failed to save the config after remote deletion {e}"
+                ));
+            } else {
+                self.mode = TuiMode::Normal;
+                let last_idx = self.app_config.remotes().len().saturating_sub(1);
+                self.select_remote(idx.min(last_idx));
+            }
+        }
+    }
+
+    /// Delete a remote from the rclone config, after two confirmations
+    fn delete_rclone_remote(&mut self, name: &str) {
+        let config_file = self.rclone.remote_config_file(Some(name));
+        if let Err(e) = self.rclone.delete_remote(name) {
+            self.new_error(format!("Failed to delete the remote: {e}"));
+            return;
+        }
+        if let Ok(config_file) = config_file {
+            self.config_watcher.written(Path::new(&config_file));
+        }
+        self.mode = TuiMode::Normal;
+        // the galion metadata of the remote is dropped with it
+        if self.app_config.rclone_remotes.remove(name).is_some()
+            && let Err(e) = self.save_config()
+        {
+            self.new_error(format!("Failed to save the config: {e}"));
+            return;
+        }
+        if let Err(e) = self
+            .app_config
+            .reload(self.rclone, self.galion_args.ignore_duplicate_remote)
+        {
+            self.new_error(format!("Failed to reload the configuration: {e}"));
+            return;
+        }
+        self.refresh_table();
+        let last_idx = self.app_config.remotes().len().saturating_sub(1);
+        self.select_remote(self.state.selected().unwrap_or_default().min(last_idx));
+        self.notify(format!("Remote {name} deleted from the rclone config"));
+    }
+
+    /// Name of the selected remote
+    fn selected_remote_name(&self) -> String {
+        self.state
+            .selected()
+            .and_then(|idx| self.app_config.remotes().get(idx))
+            .map(|remote| remote.remote_name.clone())
+            .unwrap_or_default()
+    }
+
+    /// Download a remote file to a local directory as a tracked job
+    fn download(&mut self, path: String, local_dir: &str) {
+        let local_dir = Path::new(local_dir);
+        if !local_dir.is_dir() {
+            self.new_error(format!("'{}' is not a directory", local_dir.display()));
+            return;
+        }
+        let file_name = path.rsplit(['/', ':']).next().unwrap_or_default();
+        let dest = local_dir.join(file_name).to_string_lossy().into_owned();
+        let job = SyncJobData {
+            job_id: 0, // fake job id
+            name: self.selected_remote_name(),
+            src: path,
+            dest: dest.clone(),
+            kind: JobKind::CopyFile,
+            backup_dir: None,
+            options: SyncOptions::default(),
+            bisync: BisyncRun::default(),
+            priority: Priority::High,
+            submission: 0,
+            max_duration: None,
+            space_check: SpaceCheck::default(),
+            filter_from: None,
+            filters: Vec::new(),
+            overlap: OverlapPolicy::default(),
+            verify: false,
+        };
+        if self
+            .tx_to_thread
+            .send(SyncJob::Sync(Box::new(job)))
+            .is_err()
+        {
+            self.new_error("Cannot download the file: the background thread exited");
+            return;
+        }
+        self.notify(format!("Downloading to {dest}"));
+    }
+
+    /// Upload a local file to a remote directory as a tracked job
+    fn upload(&mut self, local_file: &str, remote_dir: &str) {
+        let local_path = Path::new(local_file);
+        if !local_path.is_file() {
+            self.new_error(format!("'{local_file}' is not a file"));
+            return;
+        }
+        let file_name = local_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let dest = join_path(remote_dir, &file_name);
+        let job = SyncJobData {
+            job_id: 0, // fake job id
+            name: self.selected_remote_name(),
+            src: local_file.to_string(),
+            dest: dest.clone(),
+            kind: JobKind::CopyFile,
+            backup_dir: None,
+            options: SyncOptions::default(),
+            bisync: BisyncRun::default(),
+            priority: Priority::High,
+            submission: 0,
+            max_duration: None,
+            space_check: SpaceCheck::default(),
+            filter_from: None,
+            filters: Vec::new(),
+            overlap: OverlapPolicy::default(),
+            verify: false,
+        };
+        if self
+            .tx_to_thread
+            .send(SyncJob::Sync(Box::new(job)))
+            .is_err()
+        {
+            self.new_error("Cannot upload the file: the background thread exited");
+            return;
+        }
+        self.notify(format!("Uploading to {dest}"));
+    }
+
+    /// Delete the selected file of the explorer as a tracked job, after confirmation
+    fn delete_selected_file(&mut self) {
+        let TuiMode::DeleteFile(path) = std::mem::replace(&mut self.mode, TuiMode::Browse) else {
+            return;
+        };
+        let job = SyncJobData {
+            job_id: 0, // fake job id
+            name: self.selected_remote_name(),
+            src: path.clone(),
+            dest: String::new(),
+            kind: JobKind::DeleteFile,
+            backup_dir: None,
+            options: SyncOptions::default(),
+            bisync: BisyncRun::default(),
+            priority: Priority::High,
+            submission: 0,
+            max_duration: None,
+            space_check: SpaceCheck::default(),
+            filter_from: None,
+            filters: Vec::new(),
+            overlap: OverlapPolicy::default(),
+            verify: false,
+        };
+        if self
+            .tx_to_thread
+            .send(SyncJob::Sync(Box::new(job)))
+            .is_err()
+        {
+            self.new_error("Cannot delete the file: the background thread exited");
+            return;
+        }
+        if let Some(browser) = &mut self.browser {
+            browser.remove_selected();
+        }
+        self.notify(format!("Deleting {path}"));
+    }
+
+    /// Ratatui handle key for the edit form
+    fn handle_key_event_edit_mode(&mut self, key_event: KeyEvent) {
+        let TuiMode::EditString(edit_string) = &mut self.mode else {
+            return;
+        };
+        match key_event.code {
+            KeyCode::Esc => {
+                self.mode = TuiMode::Normal;
+            }
+            KeyCode::Down | KeyCode::Tab => edit_string.next_field(),
+            KeyCode::Up | KeyCode::BackTab => edit_string.previous_field(),
+            KeyCode::F(2) => edit_string.toggle_extended(),
+            KeyCode::Enter => {
+                let new_remote = match edit_string.finish() {
+                    Ok(new_remote) => new_remote,
+                    Err(e) => {
+                        self.new_error(e.to_string());
+                        return;
+                    }
+                };
+                if let Some(idx) = self.state.selected() {
+                    if let Err(e) = self.app_config.apply_edit(idx, new_remote) {
+                        self.new_error(e.to_string());
+                        return;
+                    }
+                    self.refresh_table();
+                    if let Err(e) = self.save_config() {
+                        self.new_error(format!("{} {e}", tr("error.save_config")));
+                    } else {
+                        self.mode = TuiMode::Normal;
+                    }
+                } else {
+                    self.new_error("Cannot edit remote");
+                }
+            }
+            KeyCode::Left => edit_string.selected().move_cursor_left(),
+            KeyCode::Right => edit_string.selected().move_cursor_right(),
+            KeyCode::Char(to_insert) => edit_string.selected().enter_char(to_insert),
+            KeyCode::Backspace => edit_string.selected().delete_char(),
+            _ => {}
+        }
+    }
+
+    /// Ratatui handle key for the settings form
+    fn handle_key_event_settings_mode(&mut self, key_event: KeyEvent) {
+        let TuiMode::Settings(form) = &mut self.mode else {
+            return;
+        };
+        match key_event.code {
+            KeyCode::Esc => self.mode = TuiMode::Normal,
+            KeyCode::Down | KeyCode::Tab => form.next_field(),
+            KeyCode::Up | KeyCode::BackTab => form.previous_field(),
+            KeyCode::Enter => {
+                if let Err(e) = form.apply(self.app_config) {
+                    self.new_error(e.to_string());
+                    return;
+                }
+                if let Err(e) = self.save_config() {
+                    self.new_error(format!("{} {e}", tr("error.save_config")));
+                } else {
+                    self.mode = TuiMode::Normal;
+                    self.notify("Settings saved");
+                }
+            }
+            KeyCode::Left => form.selected().move_cursor_left(),
+            KeyCode::Right => form.selected().move_cursor_right(),
+            KeyCode::Char(to_insert) => form.selected().enter_char(to_insert),
+            KeyCode::Backspace => form.selected().delete_char(),
+            _ => {}
+        }
+    }
+
+    /// Ratatui handle key for the bisync resolution popup
+    fn handle_key_event_bisync_mode(&mut self, key_event: KeyEvent) {
+        let TuiMode::Bisync(conflicts) = &mut self.mode else {
+            return;
+        };
+        match key_event.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.close_popup(),
+            KeyCode::Char('j') | KeyCode::Down => conflicts.next(),
+            KeyCode::Char('k') | KeyCode::Up => conflicts.previous(),
+            KeyCode::Tab => conflicts.toggle_focus(),
+            KeyCode::Char('l' | ' ') | KeyCode::Right => {
+                conflicts.cycle_resolution(true);
+            }
+            KeyCode::Char('h') | KeyCode::Left => conflicts.cycle_resolution(false),
+            KeyCode::Enter if conflicts.files_focused => {
+                let res = conflicts.apply_files(self.rclone);
+                self.close_popup();
+                match res {
+                    Ok(resolved) => self.notify(format!("{resolved} conflicts resolved")),
+                    Err(e) => self.new_error(format!("Cannot resolve the conflicts: {e}")),
+                }
+            }
+            KeyCode::Enter => {
+                let job = conflicts.resolve();
+                let name = job.name.clone();
+                self.close_popup();
+                if self
+                    .tx_to_thread
+                    .send(SyncJob::Sync(Box::new(job)))
+                    .is_err()
+                {
+                    self.new_error("Cannot run the bisync: the background thread exited");
+                } else {
+                    self.notify(format!("Running the bisync of {name} again"));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Open the resolution popup if a finished bisync has conflicts or needs a resync
+    fn check_bisync(&mut self, job: SyncJobData, job_state: &JobState) {
+        let error = match job_state {
+            JobState::Done(_, _) | JobState::Failed(_) | JobState::TimedOut(_) => job_state.error(),
+            JobState::Queued
+            | JobState::Offline(_)
+            | JobState::Retrying(_)
+            | JobState::Sent
+            | JobState::Pending(_, _)
+            | JobState::Cancelled => return,
+        };
+        let needs_resync = needs_resync(&error);
+        let files = if needs_resync {
+            Vec::new()
+        } else {
+            // bisync keeps both versions of a conflict, renamed with a suffix
+            FileConflict::from_items(
+                self.rclone
+                    .search(&job.src, CONFLICT_RULE)
+                    .unwrap_or_default(),
+            )
+        };
+        if !needs_resync && files.is_empty() {
+            return;
+        }
+        if !matches!(self.mode, TuiMode::Normal | TuiMode::Browse) {
+            self.notify(format!("The bisync of {} needs a resolution", job.name));
+            return;
+        }
+        self.mode = TuiMode::Bisync(BisyncConflicts::new(job, files, needs_resync));
+    }
+
     /// Ratatui handle key
     fn handle_key_event(&mut self, key_event: KeyEvent) {
         // Handle CRTL + c
@@ -649,141 +2534,244 @@ impl<'a> TuiApp<'a> {
         }
         match &mut self.mode {
             TuiMode::Normal => self.handle_key_event_normal_mode(key_event),
+            TuiMode::Browse => self.handle_key_event_browse_mode(key_event),
+            TuiMode::Prompt(_) => self.handle_key_event_prompt_mode(key_event),
+            TuiMode::Union(_) => self.handle_key_event_union_mode(key_event),
+            TuiMode::Wizard(_) => self.handle_key_event_wizard_mode(key_event),
+            TuiMode::Bisync(_) => self.handle_key_event_bisync_mode(key_event),
+            TuiMode::Filters(_) => self.handle_key_event_filters_mode(key_event),
+            TuiMode::Stats(_) => self.handle_key_event_stats_mode(key_event),
             TuiMode::Error(_) => match key_event.code {
-                KeyCode::Char('q') | KeyCode::Esc => {
-                    self.mode = TuiMode::Normal;
-                }
+                KeyCode::Char('q') | KeyCode::Esc => self.close_popup(),
                 _ => {}
             },
-            TuiMode::Delete => match key_event.code {
-                KeyCode::Char('q' | 'n') | KeyCode::Esc => {
-                    self.mode = TuiMode::Normal;
-                }
+            TuiMode::Details(_, _, scroll) => match key_event.code {
+                KeyCode::Char('q') | KeyCode::Esc => self.close_popup(),
+                KeyCode::Char('j') | KeyCode::Down => *scroll = scroll.saturating_add(1),
+                KeyCode::Char('k') | KeyCode::Up => *scroll = scroll.saturating_sub(1),
+                KeyCode::PageDown => *scroll = scroll.saturating_add(10),
+                KeyCode::PageUp => *scroll = scroll.saturating_sub(10),
+                _ => {}
+            },
+            TuiMode::Restore(_) => match key_event.code {
+                KeyCode::Char('q' | 'n') | KeyCode::Esc => self.close_popup(),
                 KeyCode::Char('y') | KeyCode::Enter => {
-                    if let Some(idx) = self.state.selected()
-                        && let Some(config) = self.app_config.remotes().get(idx)
+                    if let TuiMode::Restore(job) =
+                        std::mem::replace(&mut self.mode, TuiMode::Browse)
                     {
-                        if config.config_origin == ConfigOrigin::RcloneConfig {
-                            self.new_error("Cannot delete a remote from the rclone config");
-                            return;
-                        }
-                        self.app_config.remote_configurations.remove(idx);
-                        if let Err(e) = self.app_config.save_config() {
-                            self.new_error(format!(
-                                "Failed to save the config after remote deletion {e}"
-                            ));
+                        let dest = job.dest.clone();
+                        if self
+                            .tx_to_thread
+                            .send(SyncJob::Sync(Box::new(job)))
+                            .is_err()
+                        {
+                            self.new_error("Cannot restore: the background thread exited");
                         } else {
-                            self.mode = TuiMode::Normal;
+                            self.notify(format!("Restoring {dest}"));
                         }
                     }
                 }
                 _ => {}
             },
-            TuiMode::EditString(edit_string) => match key_event.code {
-                KeyCode::Esc => {
+            TuiMode::DeleteFile(_) => match key_event.code {
+                KeyCode::Char('q' | 'n') | KeyCode::Esc => self.close_popup(),
+                KeyCode::Char('y') | KeyCode::Enter => self.delete_selected_file(),
+                _ => {}
+            },
+            TuiMode::Quit => match key_event.code {
+                KeyCode::Char('q' | 'n') | KeyCode::Esc => {
                     self.mode = TuiMode::Normal;
                 }
-                KeyCode::Down | KeyCode::Tab => {
-                    if edit_string.idx_string != 2 {
-                        edit_string.idx_string += 1;
-                        edit_string.reset_char_index();
-                    }
+                KeyCode::Char('c' | 'y') | KeyCode::Enter => self.exit(),
+                KeyCode::Char('w') => self.exit_when_done(),
+                _ => {}
+            },
+            TuiMode::Delete => match key_event.code {
+                KeyCode::Char('q' | 'n') | KeyCode::Esc => {
+                    self.mode = TuiMode::Normal;
                 }
-                KeyCode::Up => {
-                    if edit_string.idx_string != 0 {
-                        edit_string.idx_string -= 1;
-                        edit_string.reset_char_index();
-                    }
+                KeyCode::Char('y') | KeyCode::Enter => self.delete_selected_remote(),
+                _ => {}
+            },
+            TuiMode::DeleteRclone(name, sure) => match key_event.code {
+                KeyCode::Char('q' | 'n') | KeyCode::Esc => {
+                    self.mode = TuiMode::Normal;
                 }
-                KeyCode::Enter => {
-                    let new_remote = edit_string.finish();
-                    if let Some(idx) = self.state.selected()
-                        && let Some(config) = self.app_config.remote_configurations.get_mut(idx)
-                    {
-                        if config.config_origin == ConfigOrigin::GalionConfig {
-                            *config = new_remote;
-                        } else {
-                            self.app_config.remote_configurations.insert(0, new_remote);
-                        }
-                        if let Err(e) = self.app_config.save_config() {
-                            self.new_error(format!("Error save the config {e}"));
-                        } else {
-                            self.mode = TuiMode::Normal;
-                        }
-                    } else {
-                        self.new_error("Cannot edit remote");
-                    }
+                KeyCode::Char('y') | KeyCode::Enter if *sure => {
+                    let name = name.clone();
+                    self.delete_rclone_remote(&name);
                 }
-                KeyCode::Left => edit_string.move_cursor_left(),
-                KeyCode::Right => edit_string.move_cursor_right(),
-                KeyCode::Char(to_insert) => edit_string.enter_char(to_insert),
-                KeyCode::Backspace => edit_string.delete_char(),
+                KeyCode::Char('y') | KeyCode::Enter => *sure = true,
                 _ => {}
             },
+            TuiMode::EditString(_) => self.handle_key_event_edit_mode(key_event),
+            TuiMode::Settings(_) => self.handle_key_event_settings_mode(key_event),
+        }
+    }
+
+    /// Handle the keys of the statistics
+    fn handle_key_event_stats_mode(&mut self, key_event: KeyEvent) {
+        let TuiMode::Stats(view) = &mut self.mode else {
+            return;
+        };
+        match key_event.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.close_popup(),
+            KeyCode::Tab | KeyCode::Right => {
+                view.next_window();
+                self.session.stats_window = view.window;
+            }
+            KeyCode::Char('x') => {
+                let res =
+                    RemoteStats::export(&view.stats, view.window, &self.app_config.reports_path());
+                match res {
+                    Ok(path) => {
+                        self.notify(format!("Statistics written to {}", path.display()));
+                    }
+                    Err(e) => self.new_error(format!("Failed to export the statistics: {e}")),
+                }
+            }
+            _ => {}
         }
     }
 
-    /// exit
+    /// Quit, asking what to do with the running jobs
+    fn quit(&mut self) {
+        if self.jobs.values().any(JobState::is_waiting) {
+            self.mode = TuiMode::Quit;
+        } else {
+            self.exit();
+        }
+    }
+
+    /// Exit once the running jobs are done
+    fn exit_when_done(&mut self) {
+        self.draining = true;
+        self.mode = TuiMode::Normal;
+        if let Err(_e) = self.tx_to_thread.send(SyncJob::Exit(ShutdownPolicy::Wait)) {
+            // background thread already exited
+            self.exit = true;
+        }
+    }
+
+    /// exit, stopping the running jobs and servers
     fn exit(&mut self) {
         self.exit = true;
-        if let Err(_e) = self.tx_to_thread.send(SyncJob::Exit) {
+        for server in self.servers.drain(..) {
+            if let Err(_e) = server.stop(self.rclone) {
+                // the servers also stop with librclone
+            }
+        }
+        if let Err(_e) = self
+            .tx_to_thread
+            .send(SyncJob::Exit(ShutdownPolicy::Cancel))
+        {
             // background thread already exited?
             // eprintln!("{}", _e);
         }
     }
 
+    /// Key helpers of the tree explorer
+    fn browse_key_helpers(&self) -> Vec<Span<'static>> {
+        let can_restore = self
+            .browser
+            .as_ref()
+            .is_some_and(|browser| browser.restore_to.is_some());
+        let helpers = BROWSE_KEYS
+            .iter()
+            .copied()
+            .filter(|(key, _)| *key != "(R)" || can_restore)
+            .collect::<Vec<(&str, &str)>>();
+        hints(&helpers)
+    }
+
+    /// Key helpers of the bottom bar
+    fn key_helpers(&self) -> Vec<Span<'static>> {
+        match &self.mode {
+            TuiMode::Error(_e) => hints(&[("(esc)", "hint.close_error")]),
+            TuiMode::Details(_, _, _) => hints(&[
+                ("(esc)", "hint.close"),
+                ("(arrow_up/arrow_down)", "hint.scroll"),
+            ]),
+            TuiMode::Prompt(_) => hints(&[("(esc)", "hint.cancel"), ("(enter)", "hint.validate")]),
+            TuiMode::Browse => self.browse_key_helpers(),
+            TuiMode::Normal => hints(NORMAL_KEYS),
+            TuiMode::EditString(_) => hints(EDIT_KEYS),
+            TuiMode::Settings(_) => hints(&[
+                ("(esc)", "hint.leave"),
+                ("(arrow_up/arrow_down)", "hint.select"),
+                ("(enter)", "hint.save"),
+            ]),
+            TuiMode::Union(_) => hints(&[
+                ("(esc)", "hint.cancel"),
+                ("(tab)", "hint.next"),
+                ("(arrows)", "hint.select"),
+                ("(enter)", "hint.create"),
+            ]),
+            TuiMode::Wizard(wizard) if wizard.step == WizardStep::Backend => hints(&[
+                ("(esc)", "hint.cancel"),
+                ("(arrow_up/arrow_down)", "hint.select"),
+                ("(enter)", "hint.next"),
+            ]),
+            TuiMode::Wizard(_) => hints(&[
+                ("(esc)", "hint.back"),
+                ("(arrow_up/arrow_down)", "hint.select"),
+                ("(enter)", "hint.create"),
+            ]),
+            TuiMode::Stats(_) => hints(STATS_KEYS),
+            TuiMode::Filters(_) => hints(FILTER_KEYS),
+            TuiMode::Bisync(conflicts) if conflicts.files_focused => hints(BISYNC_KEYS),
+            TuiMode::Bisync(_) => hints(&[
+                ("(esc)", "hint.close"),
+                ("(arrow_up/arrow_down)", "hint.select"),
+                ("(tab)", "hint.files"),
+                ("(enter)", "hint.run_resolution"),
+            ]),
+            TuiMode::Restore(_) => hints(&[("(esc/n)", "hint.cancel"), ("(y)", "hint.restore")]),
+            TuiMode::Delete | TuiMode::DeleteRclone(_, _) | TuiMode::DeleteFile(_) => {
+                hints(&[("(esc/n)", "hint.cancel"), ("(y)", "hint.delete")])
+            }
+            TuiMode::Quit => hints(&[
+                ("(esc/n)", "hint.stay"),
+                ("(c)", "hint.cancel_and_quit"),
+                ("(w)", "hint.quit_when_done"),
+            ]),
+        }
+    }
+
     /// Render bottom bar
     fn render_bottom_bar(&mut self, frame: &mut Frame<'_>, area: Rect) {
-        let [left_area, right_area] = Layout::default()
+        let notification = match &self.notification {
+            Some((msg, created)) if created.elapsed() < Self::NOTIFICATION_TIME => msg.as_str(),
+            _ if self.draining => tr("message.exiting"),
+            _ => "",
+        };
+        let [left_area, notification_area, right_area] = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([Constraint::Min(1), Constraint::Length(50)])
+            .constraints([
+                Constraint::Min(1),
+                Constraint::Length(u16::try_from(notification.len()).unwrap_or(0)),
+                Constraint::Length(50),
+            ])
             .areas(area);
+        let notification_widget =
+            Paragraph::new(notification).style(Style::default().bg(Color::Yellow).fg(Color::Black));
+        frame.render_widget(notification_widget, notification_area);
 
         let bg_color = if let TuiMode::Error(_) = &self.mode {
             Color::Red
         } else {
             Color::Black
         };
-        let text_helper = match &self.mode {
-            TuiMode::Error(_e) => vec!["(esc)".bold(), " close error".into()],
-            TuiMode::Normal => {
-                vec![
-                    "(esc)".bold(),
-                    " leave | ".into(),
-                    "(arrow_up/arrow_down)".bold(),
-                    " select | ".into(),
-                    "(arrow_right)".bold(),
-                    " launch job | ".into(),
-                    "(r)".bold(),
-                    " remove | ".into(),
-                    "(e)".bold(),
-                    " edit | ".into(),
-                    "(d)".bold(),
-                    " duplicate".into(),
-                ]
-            }
-            TuiMode::EditString(_) => vec![
-                "(esc)".bold(),
-                " leave | ".into(),
-                "(arrow_up/arrow_down)".bold(),
-                " select | ".into(),
-                "(enter)".bold(),
-                " save".into(),
-            ],
-            TuiMode::Delete => vec![
-                "(esc/n)".bold(),
-                " cancel | ".into(),
-                "(y)".bold(),
-                " delete".into(),
-            ],
-        };
-        let left_text = Line::from(text_helper);
+        let left_text = Line::from(self.key_helpers());
         let now = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
         let format = format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
         let date_str = now
             .format(&format)
             .unwrap_or("Unable to format date".to_string());
-        let right_text = Line::from(format!("{} - {}", Self::APP, date_str));
+        let right_text = match self.rclone.backend_name() {
+            Some(backend) => Line::from(format!("{} @{backend} - {}", Self::APP, date_str)),
+            None => Line::from(format!("{} - {}", Self::APP, date_str)),
+        };
         let left_widget =
             Paragraph::new(left_text).style(Style::default().bg(bg_color).fg(Color::White));
         let right_widget = Paragraph::new(right_text)
@@ -793,11 +2781,42 @@ impl<'a> TuiApp<'a> {
         frame.render_widget(right_widget, right_area);
     }
 
+    /// Status of the remotes chained after a sync, empty without chained remotes
+    fn chain_status(&self, job_data: &SyncJobData, state: &JobState) -> String {
+        if !job_data.is_sync() {
+            return String::new();
+        }
+        let chained = self
+            .app_config
+            .chained_remotes(&job_data.name)
+            .map(|remote| remote.remote_name.as_str())
+            .collect::<Vec<&str>>()
+            .join(", ");
+        if chained.is_empty() {
+            return String::new();
+        }
+        match state {
+            JobState::Done(job_status, _) if job_status.success => format!(" -> {chained} queued"),
+            JobState::Done(_, _) | JobState::Failed(_) | JobState::TimedOut(_) => {
+                format!(" -> {chained} skipped")
+            }
+            _ => format!(" -> then {chained}"),
+        }
+    }
+
     /// Render right panel
     fn render_right_panel(&mut self, frame: &mut Frame<'_>, area: Rect) {
-        let job_block = Block::default()
+        let mut job_block = Block::default()
             .borders(Borders::ALL)
             .style(Style::default());
+        if let Some((banner, created)) = &self.banner
+            && created.elapsed() < Self::BANNER_TIME
+        {
+            job_block = job_block.title(Span::styled(
+                banner.as_str(),
+                Style::default().bg(Color::Red).fg(Color::White),
+            ));
+        }
         let job_text: Vec<Line<'_>> = if self.jobs.is_empty() {
             let str_to_show = match self.mode {
                 TuiMode::Normal => GalionApp::logo_random_waves(),
@@ -806,15 +2825,17 @@ impl<'a> TuiApp<'a> {
             str_to_show
                 .lines()
                 .map(|s| Line::from(String::from(s)))
-                .chain(std::iter::once(Line::from("Nothing to do, just sailing")))
+                .chain(std::iter::once(Line::from(tr("message.nothing_to_do"))))
                 .collect()
         } else {
             let mut str_to_show = Vec::new();
             // Show latest jobs first
             for (one_job_data, state) in self.jobs.iter().rev() {
                 let job_string = format!(
-                    "job {} ({}): {}\n",
-                    one_job_data.name, one_job_data.job_id, state
+                    "{}{}: {}\n",
+                    one_job_data.label(),
+                    self.chain_status(one_job_data, state),
+                    state
                 );
                 str_to_show.push(Line::from(Span::styled(
                     job_string,
@@ -823,43 +2844,113 @@ impl<'a> TuiApp<'a> {
             }
             str_to_show
         };
-        let job_paragraph = Paragraph::new(Text::from(job_text))
-            .wrap(Wrap { trim: false })
-            .block(job_block);
-        frame.render_widget(job_paragraph, area);
+        let server_text = self.servers.iter().map(|server| {
+            Line::from(Span::styled(
+                format!("serving {server}"),
+                Style::default().fg(Color::Cyan),
+            ))
+        });
+        // the description and the notes of the selected remote, to keep its intent in sight
+        let notes_text = self
+            .state
+            .selected()
+            .and_then(|idx| self.app_config.remotes().get(idx))
+            .into_iter()
+            .flat_map(|remote| remote.description.iter().chain(&remote.notes))
+            .flat_map(|text| text.lines())
+            .map(|line| {
+                Line::from(Span::styled(
+                    line.to_string(),
+                    Style::default().fg(Color::Yellow),
+                ))
+            });
+        let job_text = notes_text
+            .chain(server_text)
+            .chain(job_text)
+            .collect::<Vec<Line<'_>>>();
+        let inner_area = job_block.inner(area);
+        frame.render_widget(job_block, area);
+        let gauges_area = self.render_gauges(frame, inner_area);
+        let job_paragraph = Paragraph::new(Text::from(job_text)).wrap(Wrap { trim: false });
+        frame.render_widget(
+            job_paragraph,
+            Rect {
+                y: inner_area.y + gauges_area.height,
+                height: inner_area.height - gauges_area.height,
+                ..inner_area
+            },
+        );
+    }
+
+    /// Render a progress bar per running job with a known size, returns the area used
+    fn render_gauges(&self, frame: &mut Frame<'_>, area: Rect) -> Rect {
+        let gauges = self
+            .jobs
+            .iter()
+            .rev()
+            .filter_map(|(job_data, job_state)| match job_state {
+                JobState::Pending(_, stats) if stats.total_bytes > 0 => Some((job_data, stats)),
+                _ => None,
+            })
+            .take(Self::MAX_GAUGES.min(usize::from(area.height / 2)))
+            .collect::<Vec<(&SyncJobData, &RcStats)>>();
+        let gauges_area = Rect {
+            height: u16::try_from(gauges.len()).unwrap_or(0),
+            ..area
+        };
+        for ((job_data, stats), row) in gauges.into_iter().zip(gauges_area.rows()) {
+            #[allow(clippy::cast_precision_loss)] // only shown as a bar
+            let ratio = (stats.bytes as f64 / stats.total_bytes as f64).clamp(0.0, 1.0);
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // a positive speed
+            let speed = format_size(stats.speed as u64);
+            let eta = stats
+                .eta
+                .map(|eta| format!(", eta {eta:.0}s"))
+                .unwrap_or_default();
+            let gauge = LineGauge::default()
+                .ratio(ratio)
+                .label(format!("{} {speed}/s{eta}", job_data.name))
+                .filled_style(Style::default().fg(Color::Blue));
+            frame.render_widget(gauge, row);
+        }
+        gauges_area
     }
 
     /// Ratatui render table
     fn render_table(&mut self, frame: &mut Frame<'_>, area: Rect) {
         let header_style = Style::default();
+        let theme = self.app_config.theme;
         let bg_color_selected = if let TuiMode::Error(_err_str) = &self.mode {
             Color::Red
         } else {
-            Color::Blue
+            theme.selected_color()
         };
-        let header = ["name/origin", "src", "dest"]
+        let header = ["name/origin", "src", "dest", "schedule", "health"]
             .into_iter()
             .map(Cell::from)
             .collect::<Row<'_>>()
             .style(header_style)
             .height(1);
-        let rows = self
-            .app_config
-            .remotes()
-            .iter()
-            .enumerate()
-            .map(|(i, data)| {
-                let _color = match i % 2 {
-                    0 => Color::Gray,
-                    _ => Color::DarkGray,
-                };
-                let item = data.to_table_row();
-                item.into_iter()
-                    .map(|content| Cell::from(Text::from(format!("\n{content}\n"))))
-                    .collect::<Row<'_>>()
-                    .style(Style::new().fg(Color::Black).bg(Color::White))
-                    .height(4)
-            });
+        let remotes = self.app_config.remotes();
+        let rows = self.table_rows.iter().enumerate().map(|(idx, item)| {
+            let remote = remotes.get(idx);
+            let style = if remote.is_some_and(|remote| remote.quarantined) {
+                theme.row_style().fg(Color::Red)
+            } else {
+                theme.row_style()
+            };
+            let health = remote
+                .and_then(|remote| self.health.get(&remote.remote_name))
+                .map_or_else(Cell::default, |health| {
+                    Cell::from(Text::from("\n ●\n").fg(health.color()))
+                });
+            item.iter()
+                .map(|content| Cell::from(Text::from(content.as_str())))
+                .chain(std::iter::once(health))
+                .collect::<Row<'_>>()
+                .style(style)
+                .height(4)
+        });
         let bar = " █ ";
         let t = Table::new(
             rows,
@@ -867,7 +2958,9 @@ impl<'a> TuiApp<'a> {
                 // + 1 is for padding.
                 Constraint::Length(self.longest_item_lens.0 + 1),
                 Constraint::Min(self.longest_item_lens.1 + 1),
-                Constraint::Min(self.longest_item_lens.2),
+                Constraint::Min(self.longest_item_lens.2 + 1),
+                Constraint::Min(self.longest_item_lens.3),
+                Constraint::Length(6),
             ],
         )
         .header(header)
@@ -0,0 +1,85 @@
+//! Password prompt of an encrypted rclone config, shown before the tui starts
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Position};
+use ratatui::style::{Color, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Clear, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::errors::GalionError;
+use crate::i18n::tr;
+use crate::input::TextInput;
+
+/// Prompt of the config password
+struct PasswordPrompt {
+    /// typed password
+    input: TextInput,
+    /// error of the previous attempt
+    error: Option<String>,
+}
+
+impl PasswordPrompt {
+    /// Render the masked input, with the error of the previous attempt
+    fn render(&self, frame: &mut Frame<'_>) {
+        let area = frame
+            .area()
+            .centered(Constraint::Percentage(40), Constraint::Length(4));
+        frame.render_widget(Clear, area); //this clears out the background
+        let block = Block::bordered().title(tr("title.config_password"));
+        let inner_area = block.inner(area);
+        let mut lines = vec![Line::from("*".repeat(self.input.value.chars().count()))];
+        if let Some(error) = &self.error {
+            lines.push(Line::styled(
+                error.as_str(),
+                Style::default().fg(Color::Red),
+            ));
+        }
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+        frame.set_cursor_position(Position::new(
+            inner_area.x + u16::try_from(self.input.character_index).unwrap_or(0),
+            inner_area.y,
+        ));
+    }
+
+    /// Read the keys until the password is validated, `None` if cancelled
+    /// # Errors
+    /// Fails if the terminal fails
+    fn run(mut self, terminal: &mut DefaultTerminal) -> Result<Option<String>, GalionError> {
+        loop {
+            terminal.draw(|frame| self.render(frame))?;
+            let Event::Key(key_event) = event::read()? else {
+                continue;
+            };
+            if key_event.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key_event.code {
+                KeyCode::Char('c') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Ok(None);
+                }
+                KeyCode::Esc => return Ok(None),
+                KeyCode::Enter => return Ok(Some(self.input.value)),
+                KeyCode::Char(to_insert) => self.input.enter_char(to_insert),
+                KeyCode::Backspace => self.input.delete_char(),
+                KeyCode::Left => self.input.move_cursor_left(),
+                KeyCode::Right => self.input.move_cursor_right(),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Ask the password of the rclone config, `None` if cancelled
+/// # Errors
+/// Fails if the terminal fails
+pub(crate) fn ask_password(error: Option<String>) -> Result<Option<String>, GalionError> {
+    let prompt = PasswordPrompt {
+        input: TextInput::default(),
+        error,
+    };
+    let mut terminal = ratatui::init();
+    let password = prompt.run(&mut terminal);
+    ratatui::restore();
+    password
+}
@@ -0,0 +1,210 @@
+//! Watch of the local sources of the remotes, a change queues a sync once the files are quiet
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+
+use crate::errors::GalionError;
+use crate::remote::{RclonePath, RemoteConfiguration, expand_path};
+
+/// Time without change before a sync is queued, a copy of many files is a single sync
+const DEBOUNCE: Duration = Duration::from_secs(5);
+
+/// Watcher of the sources of the remotes with `watch`
+pub(crate) struct SourceWatcher {
+    /// file system watcher, created with the first watched source
+    watcher: Option<RecommendedWatcher>,
+    /// events of the watcher
+    rx_events: Receiver<notify::Result<Event>>,
+    /// sender given to the watcher
+    tx_events: mpsc::Sender<notify::Result<Event>>,
+    /// watched sources, remote name by path
+    watched: BTreeMap<PathBuf, String>,
+    /// last change of the sources, by remote name
+    changes: BTreeMap<String, Instant>,
+}
+
+impl SourceWatcher {
+    /// Watcher without source
+    pub fn new() -> Self {
+        let (tx_events, rx_events) = mpsc::channel();
+        Self {
+            watcher: None,
+            rx_events,
+            tx_events,
+            watched: BTreeMap::new(),
+            changes: BTreeMap::new(),
+        }
+    }
+
+    /// Watch the local sources of the remotes with `watch`, and only them
+    /// # Errors
+    /// Fails if the watcher cannot be created or a source cannot be watched, the other sources are watched
+    pub fn update(&mut self, remotes: &[RemoteConfiguration]) -> Result<(), GalionError> {
+        let sources = remotes
+            .iter()
+            .filter(|remote| remote.watch)
+            .filter_map(|remote| {
+                let src = expand_path(remote.remote_src.as_deref()?);
+                match RclonePath::parse(&src) {
+                    RclonePath::Local(path) => {
+                        Some((PathBuf::from(path), remote.remote_name.clone()))
+                    }
+                    RclonePath::Remote(..) | RclonePath::Backend(_) => None,
+                }
+            })
+            .collect::<BTreeMap<PathBuf, String>>();
+        if sources == self.watched {
+            return Ok(());
+        }
+        if let Some(watcher) = &mut self.watcher {
+            for path in self.watched.keys() {
+                if !sources.contains_key(path) {
+                    // fails if the directory was removed
+                    let _ = watcher.unwatch(path);
+                }
+            }
+        }
+        self.changes
+            .retain(|name, _| sources.values().any(|source| source == name));
+        let added = sources
+            .keys()
+            .filter(|path| !self.watched.contains_key(*path))
+            .cloned()
+            .collect::<Vec<PathBuf>>();
+        self.watched = sources;
+        if added.is_empty() {
+            return Ok(());
+        }
+        let watcher = match &mut self.watcher {
+            Some(watcher) => watcher,
+            None => self.watcher.insert(
+                notify::recommended_watcher(self.tx_events.clone())
+                    .map_err(|e| format!("Cannot watch the sources: {e}"))?,
+            ),
+        };
+        let mut errors = Vec::new();
+        for path in added {
+            if let Err(e) = watcher.watch(&path, RecursiveMode::Recursive) {
+                errors.push(format!("Cannot watch {}: {e}", path.display()));
+                self.watched.remove(&path);
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.join("\n").into())
+        }
+    }
+
+    /// Record the changes of the sources
+    fn read_events(&mut self) {
+        while let Ok(event) = self.rx_events.try_recv() {
+            let Ok(event) = event else {
+                continue;
+            };
+            if matches!(event.kind, EventKind::Access(_)) {
+                continue;
+            }
+            for path in &event.paths {
+                if let Some((_, name)) = self
+                    .watched
+                    .iter()
+                    .find(|(source, _)| path.starts_with(source))
+                {
+                    self.changes.insert(name.clone(), Instant::now());
+                }
+            }
+        }
+    }
+
+    /// Remotes whose source changed and is quiet since [`DEBOUNCE`]
+    pub fn changed(&mut self) -> Vec<String> {
+        self.read_events();
+        let changed = self
+            .changes
+            .iter()
+            .filter(|(_, last_change)| last_change.elapsed() >= DEBOUNCE)
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<String>>();
+        for name in &changed {
+            self.changes.remove(name);
+        }
+        changed
+    }
+
+    /// Time until the next check of the changes, `None` without watched source
+    pub fn next_check(&self) -> Option<Duration> {
+        if self.watched.is_empty() {
+            return None;
+        }
+        // the events arrive on a channel of their own, they are polled
+        Some(
+            self.changes
+                .values()
+                .map(|last_change| DEBOUNCE.saturating_sub(last_change.elapsed()))
+                .min()
+                .unwrap_or(DEBOUNCE),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{AccessKind, ModifyKind};
+
+    /// Watcher of a source, without file system watcher
+    fn watcher() -> SourceWatcher {
+        let mut watcher = SourceWatcher::new();
+        watcher
+            .watched
+            .insert(PathBuf::from("/data/photos"), "photos".to_string());
+        watcher
+    }
+
+    /// Send a change of a path to the watcher
+    fn change(watcher: &SourceWatcher, kind: EventKind, path: &str) {
+        let event = Event::new(kind).add_path(PathBuf::from(path));
+        let _ = watcher.tx_events.send(Ok(event));
+    }
+
+    #[test]
+    fn test_change_is_debounced() {
+        let mut watcher = watcher();
+        assert_eq!(watcher.next_check(), Some(DEBOUNCE));
+        change(
+            &watcher,
+            EventKind::Modify(ModifyKind::Any),
+            "/data/photos/a.jpg",
+        );
+        change(
+            &watcher,
+            EventKind::Modify(ModifyKind::Any),
+            "/data/music/b.mp3",
+        );
+        assert!(watcher.changed().is_empty(), "the source is not quiet");
+        assert!(watcher.next_check().is_some_and(|next| next <= DEBOUNCE));
+        // quiet since the debounce
+        let Some(quiet) = Instant::now().checked_sub(DEBOUNCE) else {
+            return;
+        };
+        watcher.changes.insert("photos".to_string(), quiet);
+        assert_eq!(watcher.changed(), ["photos"]);
+        assert_eq!(watcher.changed(), Vec::<String>::new(), "queued once");
+    }
+
+    #[test]
+    fn test_access_is_not_a_change() {
+        let mut watcher = watcher();
+        change(
+            &watcher,
+            EventKind::Access(AccessKind::Any),
+            "/data/photos/a.jpg",
+        );
+        assert!(watcher.changed().is_empty());
+        assert!(watcher.changes.is_empty());
+    }
+}
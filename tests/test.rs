@@ -3,8 +3,10 @@
 #[cfg(test)]
 mod tests {
     use galion::librclone::rclone::Rclone;
+    #[cfg(feature = "librclone")]
     use std::{thread::sleep, time::Duration};
 
+    #[cfg(feature = "librclone")]
     #[test]
     fn test_get_config() {
         let mut rclone = Rclone::default();
@@ -15,6 +17,7 @@ mod tests {
         rclone.finalize();
     }
 
+    #[cfg(feature = "librclone")]
     #[test]
     fn test_get_job_list() {
         let mut rclone = Rclone::default();
@@ -30,4 +33,24 @@ mod tests {
         }
         rclone.finalize();
     }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn test_mock_job_status() {
+        use galion::librclone::MockRclone;
+        use serde_json::json;
+
+        let mock = MockRclone::new()
+            .respond("job/status", Ok(json!({ "id": 1, "finished": false })))
+            .respond(
+                "job/status",
+                Ok(json!({ "id": 1, "finished": true, "success": true })),
+            );
+        let rclone = Rclone::with_backend(mock);
+        assert!(!rclone.job_status(1).unwrap().finished);
+        let status = rclone.job_status(1).unwrap();
+        assert!(status.finished && status.success);
+        assert!(rclone.job_list().is_err());
+        assert_eq!(rclone.backend_name(), Some("mock"));
+    }
 }